@@ -44,3 +44,23 @@ fn put_ux_unaligned() {
     let _ = bits_mut.put_u9::<NetworkOrder>(u9::new(6));
     let _ = bits_mut.put_u12::<NetworkOrder>(u12::new(8));
 }
+
+static BULK_DATA: [u8; 1024] = [0x5a; 1024];
+
+#[divan::bench(sample_size = 1000)]
+fn get_u13_loop() {
+    let mut bits = Bits::from_static_bytes(&BULK_DATA);
+    let mut values = [0u32; 512];
+    for value in values.iter_mut() {
+        *value = bits.get_u13::<NetworkOrder>().unwrap().into();
+    }
+    divan::black_box(values);
+}
+
+#[divan::bench(sample_size = 1000)]
+fn get_u13_many() {
+    let mut bits = Bits::from_static_bytes(&BULK_DATA);
+    let mut values = [0u32; 512];
+    bits.get_many::<NetworkOrder, 13>(&mut values).unwrap();
+    divan::black_box(values);
+}
@@ -1,7 +1,11 @@
 use bitvec::{
-    order::Msb0,
+    field::BitField,
+    order::{Lsb0, Msb0},
     ptr::{BitPtr, BitSpanError, Mut},
 };
+use funty::Integral;
+
+use crate::buf::byte_order::{BigEndian, ByteOrder, LittleEndian};
 
 // We only want to support `u8` as a `BitStore` for our `BitSlice` types, but because of bitvec's
 // storage aliasing to support mutable `BitSlice`s whose backing bytes overlap, we need to also
@@ -11,6 +15,8 @@ mod sealed {
     pub trait Sealed {}
     impl Sealed for u8 {}
     impl Sealed for bitvec::access::BitSafeU8 {}
+    impl Sealed for bitvec::order::Msb0 {}
+    impl Sealed for bitvec::order::Lsb0 {}
 }
 
 // We use sealed above to ensure that these are the only two possible impls of our `BitStore`
@@ -26,6 +32,66 @@ pub type BitSlice<O = u8> = bitvec::slice::BitSlice<O, bitvec::order::Msb0>;
 
 pub type BitVec = bitvec::vec::BitVec<u8, bitvec::order::Msb0>;
 
+/// A `BitSlice` whose bits are ordered least-significant first within each byte, as used by
+/// DEFLATE, GIF/LZW and other LSB-first formats.
+pub type LsbBitSlice<O = u8> = bitvec::slice::BitSlice<O, bitvec::order::Lsb0>;
+
+pub type LsbBitVec = bitvec::vec::BitVec<u8, bitvec::order::Lsb0>;
+
+/// The order of the bits within each byte of a buffer: [`Msb0`] (the default for every buffer
+/// trait) or [`Lsb0`].  Like `BitStore`, this is sealed so that code which is generic over the
+/// order only has these two cases to handle.
+pub trait BitOrder: bitvec::order::BitOrder + Sized + sealed::Sealed {
+    /// The byte order which reads a field in the order its bits are stored, so that the first bit
+    /// of the field is its most significant ([`BigEndian`]) for `Msb0` and its least significant
+    /// ([`LittleEndian`]) for `Lsb0`.  Fields of 8 bits or fewer are always read this way.
+    type FieldOrder: ByteOrder;
+
+    /// Whether the first bit of each byte is its most significant.
+    const MSB_FIRST: bool;
+
+    fn load_be<S: BitStore, U: Integral>(src: &bitvec::slice::BitSlice<S, Self>) -> U;
+    fn load_le<S: BitStore, U: Integral>(src: &bitvec::slice::BitSlice<S, Self>) -> U;
+    fn store_be<S: BitStore, U: Integral>(dest: &mut bitvec::slice::BitSlice<S, Self>, value: U);
+    fn store_le<S: BitStore, U: Integral>(dest: &mut bitvec::slice::BitSlice<S, Self>, value: U);
+}
+
+// bitvec implements `BitField` separately for each order, so each impl forwards to its own.
+macro_rules! impl_bit_order {
+    ($order:ty, $field_order:ty, $msb_first:expr) => {
+        impl BitOrder for $order {
+            type FieldOrder = $field_order;
+
+            const MSB_FIRST: bool = $msb_first;
+
+            fn load_be<S: BitStore, U: Integral>(src: &bitvec::slice::BitSlice<S, Self>) -> U {
+                src.load_be()
+            }
+
+            fn load_le<S: BitStore, U: Integral>(src: &bitvec::slice::BitSlice<S, Self>) -> U {
+                src.load_le()
+            }
+
+            fn store_be<S: BitStore, U: Integral>(
+                dest: &mut bitvec::slice::BitSlice<S, Self>,
+                value: U,
+            ) {
+                dest.store_be(value)
+            }
+
+            fn store_le<S: BitStore, U: Integral>(
+                dest: &mut bitvec::slice::BitSlice<S, Self>,
+                value: U,
+            ) {
+                dest.store_le(value)
+            }
+        }
+    };
+}
+
+impl_bit_order!(Msb0, BigEndian, true);
+impl_bit_order!(Lsb0, LittleEndian, false);
+
 /// Create a mutable BitSlice from raw parts.  This is a wrapper of a bitvec function and just
 /// hardcodes the storage to u8 and order to Msb0
 ///
@@ -83,3 +149,24 @@ macro_rules! bitvec {
         ($crate::internal::bitvec::bitvec!(u8, Msb0; $($bit),*))
     };
 }
+
+/// The same as `bits!`, but creates an [`LsbBitSlice`].
+#[macro_export]
+macro_rules! lsb_bits {
+    ($value:expr; $len:expr) => {{
+        use $crate::internal::bitvec::order::Lsb0;
+        ($crate::internal::bitvec::bits![u8, Lsb0; $value; $len])
+    }};
+    (mut $value:expr; $len:expr) => {{
+        use $crate::internal::bitvec::order::Lsb0;
+        ($crate::internal::bitvec::bits![mut u8, Lsb0; $value; $len])
+    }};
+    (mut $($bit:expr),* $(,)?) => {{
+        use $crate::internal::bitvec::order::Lsb0;
+        ($crate::internal::bitvec::bits![mut u8, Lsb0; $($bit),*])
+    }};
+    ($($bit:expr),* $(,)?) => {{
+        use $crate::internal::bitvec::order::Lsb0;
+        ($crate::internal::bitvec::bits![u8, Lsb0; $($bit),*])
+    }};
+}
@@ -0,0 +1,358 @@
+use std::marker::PhantomData;
+
+use crate::prelude::*;
+
+/// The largest scale (log2 of the sum of frequencies) a [`RansTable`] can be built with.
+pub const RANS_MAX_SCALE_BITS: u32 = 16;
+
+/// The parameters of a rANS state: its size on the wire, how much is moved in and out of it at a
+/// time when renormalizing, and the bottom of its normalized interval.
+pub trait RansVariant {
+    const STATE_BITS: usize;
+    const IO_BITS: usize;
+    const LOWER_BOUND: u64;
+}
+
+/// A 32-bit state renormalized a byte at a time.
+pub struct Rans32 {}
+
+/// A 64-bit state renormalized 32 bits at a time, which renormalizes less often.
+pub struct Rans64 {}
+
+impl RansVariant for Rans32 {
+    const STATE_BITS: usize = 32;
+    const IO_BITS: usize = 8;
+    const LOWER_BOUND: u64 = 1 << 23;
+}
+
+impl RansVariant for Rans64 {
+    const STATE_BITS: usize = 64;
+    const IO_BITS: usize = 32;
+    const LOWER_BOUND: u64 = 1 << 31;
+}
+
+/// Static symbol frequencies for rANS coding, quantized so they add up to `1 << scale_bits`.
+#[derive(Clone, Debug)]
+pub struct RansTable {
+    scale_bits: u32,
+    freqs: Vec<u32>,
+    /// The sum of the frequencies of the symbols before each one
+    starts: Vec<u32>,
+    /// The symbol for each of the `1 << scale_bits` slots
+    slot_symbols: Vec<u16>,
+}
+
+impl RansTable {
+    /// Builds a table from frequencies which add up to exactly `1 << scale_bits`.  Symbols with a
+    /// frequency of zero can't be coded.
+    pub fn from_frequencies(freqs: &[u32], scale_bits: u32) -> std::io::Result<Self> {
+        if scale_bits == 0 || scale_bits > RANS_MAX_SCALE_BITS {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!(
+                    "Scale bits ({}) must be between 1 and {}",
+                    scale_bits, RANS_MAX_SCALE_BITS
+                ),
+            ));
+        }
+        if freqs.len() > u16::MAX as usize + 1 {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!("Too many symbols ({})", freqs.len()),
+            ));
+        }
+        let total: u64 = freqs.iter().map(|&freq| freq as u64).sum();
+        if total != 1 << scale_bits {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!(
+                    "Frequencies add up to {} rather than {}",
+                    total,
+                    1u64 << scale_bits
+                ),
+            ));
+        }
+        let mut starts = Vec::with_capacity(freqs.len());
+        let mut slot_symbols = Vec::with_capacity(1 << scale_bits);
+        for (symbol, &freq) in freqs.iter().enumerate() {
+            starts.push(slot_symbols.len() as u32);
+            slot_symbols.extend(std::iter::repeat_n(symbol as u16, freq as usize));
+        }
+        Ok(Self {
+            scale_bits,
+            freqs: freqs.to_vec(),
+            starts,
+            slot_symbols,
+        })
+    }
+
+    /// Builds a table from symbol counts, scaling them to add up to `1 << scale_bits`.  Every
+    /// symbol with a non-zero count keeps a frequency of at least 1.
+    pub fn from_counts(counts: &[u64], scale_bits: u32) -> std::io::Result<Self> {
+        let total: u128 = counts.iter().map(|&count| count as u128).sum();
+        let used = counts.iter().filter(|&&count| count != 0).count();
+        if total == 0 || scale_bits > RANS_MAX_SCALE_BITS || used > 1 << scale_bits {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!(
+                    "Can't scale {} symbols with a total count of {} to {} bits",
+                    used, total, scale_bits
+                ),
+            ));
+        }
+        let target = 1i64 << scale_bits;
+        let mut freqs: Vec<u32> = counts
+            .iter()
+            .map(|&count| match count {
+                0 => 0,
+                _ => ((count as u128 * target as u128 / total) as u32).max(1),
+            })
+            .collect();
+        // Rounding leaves the sum a little off, so take from (or give to) the most frequent
+        // symbols, which changes their cost the least
+        let mut excess = freqs.iter().map(|&freq| freq as i64).sum::<i64>() - target;
+        while excess != 0 {
+            let (largest, _) = freqs
+                .iter()
+                .enumerate()
+                .filter(|&(_, &freq)| excess < 0 || freq > 1)
+                .max_by_key(|&(symbol, &freq)| (freq, std::cmp::Reverse(symbol)))
+                .expect("a symbol can always be adjusted");
+            let step = excess.signum();
+            freqs[largest] = (freqs[largest] as i64 - step) as u32;
+            excess -= step;
+        }
+        Self::from_frequencies(&freqs, scale_bits)
+    }
+
+    pub fn scale_bits(&self) -> u32 {
+        self.scale_bits
+    }
+
+    /// The quantized frequency of each symbol.
+    pub fn frequencies(&self) -> &[u32] {
+        &self.freqs
+    }
+}
+
+/// A static-frequency rANS encoder with one or more interleaved states, writing a stream for
+/// [`RansDecoder`].
+///
+/// rANS decodes in the reverse of the order it encodes in, so symbols are given to the encoder
+/// last first, and the output is held until [`RansEncoder::finish`] writes it out in decoding
+/// order.
+pub struct RansEncoder<V: RansVariant> {
+    states: Vec<u64>,
+    /// Renormalization output, in the order it was produced
+    words: Vec<u64>,
+    _variant: PhantomData<V>,
+}
+
+impl<V: RansVariant> RansEncoder<V> {
+    /// Creates an encoder with `num_streams` interleaved states.
+    pub fn new(num_streams: usize) -> Self {
+        assert!(num_streams > 0, "rANS needs at least one stream");
+        Self {
+            states: vec![V::LOWER_BOUND; num_streams],
+            words: Vec::new(),
+            _variant: PhantomData,
+        }
+    }
+
+    /// Encodes `symbol` with the given stream's state.  Symbols must be encoded in the reverse of
+    /// the order they'll be decoded in.
+    pub fn put_symbol(
+        &mut self,
+        table: &RansTable,
+        stream: usize,
+        symbol: u16,
+    ) -> std::io::Result<()> {
+        let freq = table.freqs.get(symbol as usize).copied().unwrap_or(0) as u64;
+        if freq == 0 {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!("Symbol {} has a frequency of zero", symbol),
+            ));
+        }
+        let start = table.starts[symbol as usize] as u64;
+        let mut state = self.states[stream];
+        let state_max = ((V::LOWER_BOUND >> table.scale_bits) << V::IO_BITS) * freq;
+        while state >= state_max {
+            self.words.push(state & ((1 << V::IO_BITS) - 1));
+            state >>= V::IO_BITS;
+        }
+        self.states[stream] = ((state / freq) << table.scale_bits) + state % freq + start;
+        Ok(())
+    }
+
+    /// Encodes all of `symbols`, with symbol `i` (in decoding order) using stream
+    /// `i % num_streams`.  This is what [`RansDecoder::get_symbols`] reads.
+    pub fn put_symbols(&mut self, table: &RansTable, symbols: &[u16]) -> std::io::Result<()> {
+        let num_streams = self.states.len();
+        for (i, &symbol) in symbols.iter().enumerate().rev() {
+            self.put_symbol(table, i % num_streams, symbol)?;
+        }
+        Ok(())
+    }
+
+    /// Writes the final states followed by the renormalization output.
+    pub fn finish<B: BitBufMut + ?Sized>(self, buf: &mut B) -> std::io::Result<()> {
+        for &state in &self.states {
+            buf.put_bits_from::<u64, BigEndian>(V::STATE_BITS, state)?;
+        }
+        for &word in self.words.iter().rev() {
+            buf.put_bits_from::<u64, BigEndian>(V::IO_BITS, word)?;
+        }
+        Ok(())
+    }
+}
+
+/// A static-frequency rANS decoder with one or more interleaved states, reading a stream written
+/// by [`RansEncoder`].
+pub struct RansDecoder<V: RansVariant, B: BitBuf> {
+    inner: B,
+    states: Vec<u64>,
+    _variant: PhantomData<V>,
+}
+
+impl<V: RansVariant, B: BitBuf> RansDecoder<V, B> {
+    /// Creates a decoder with `num_streams` interleaved states, reading their initial values
+    /// from `inner`.
+    pub fn new(mut inner: B, num_streams: usize) -> std::io::Result<Self> {
+        assert!(num_streams > 0, "rANS needs at least one stream");
+        let states = (0..num_streams)
+            .map(|_| inner.get_bits_as::<u64, BigEndian>(V::STATE_BITS))
+            .collect::<std::io::Result<Vec<_>>>()?;
+        if let Some(state) = states.iter().find(|&&state| state < V::LOWER_BOUND) {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                format!("Initial rANS state ({}) is not normalized", state),
+            ));
+        }
+        Ok(Self {
+            inner,
+            states,
+            _variant: PhantomData,
+        })
+    }
+
+    /// Decodes a symbol with the given stream's state.
+    pub fn get_symbol(&mut self, table: &RansTable, stream: usize) -> std::io::Result<u16> {
+        let mut state = self.states[stream];
+        let slot = state & ((1 << table.scale_bits) - 1);
+        let symbol = table.slot_symbols[slot as usize];
+        let freq = table.freqs[symbol as usize] as u64;
+        let start = table.starts[symbol as usize] as u64;
+        state = freq * (state >> table.scale_bits) + slot - start;
+        while state < V::LOWER_BOUND {
+            state = (state << V::IO_BITS) | self.inner.get_bits_as::<u64, BigEndian>(V::IO_BITS)?;
+        }
+        self.states[stream] = state;
+        Ok(symbol)
+    }
+
+    /// Decodes `count` symbols, with symbol `i` using stream `i % num_streams`.
+    pub fn get_symbols(&mut self, table: &RansTable, count: usize) -> std::io::Result<Vec<u16>> {
+        let num_streams = self.states.len();
+        (0..count)
+            .map(|i| self.get_symbol(table, i % num_streams))
+            .collect()
+    }
+
+    /// Checks that every state is back where the encoder started, which catches most corruption,
+    /// and returns the inner buffer.
+    pub fn finish(self) -> std::io::Result<B> {
+        if self.states.iter().any(|&state| state != V::LOWER_BOUND) {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "rANS stream did not end in its initial state",
+            ));
+        }
+        Ok(self.inner)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn skewed_symbols(count: usize) -> Vec<u16> {
+        let mut seed = 0x2545_f491u32;
+        (0..count)
+            .map(|_| {
+                seed ^= seed << 13;
+                seed ^= seed >> 17;
+                seed ^= seed << 5;
+                // Roughly geometric: symbol 0 half the time, 1 a quarter of the time, ...
+                (seed.trailing_zeros() as u16).min(9)
+            })
+            .collect()
+    }
+
+    fn round_trip<V: RansVariant>(num_streams: usize) {
+        let symbols = skewed_symbols(3000);
+        let mut counts = [0u64; 12];
+        for &symbol in &symbols {
+            counts[symbol as usize] += 1;
+        }
+        let table = RansTable::from_counts(&counts, 12).unwrap();
+
+        let mut encoder = RansEncoder::<V>::new(num_streams);
+        encoder.put_symbols(&table, &symbols).unwrap();
+        let mut bits_mut = BitsMut::new();
+        // Start unaligned, to show the stream doesn't need to be byte-aligned
+        bits_mut.put_u3(u3::new(0b101)).unwrap();
+        encoder.finish(&mut bits_mut).unwrap();
+        bits_mut.put_u8(0x5a).unwrap();
+        // Close to the entropy of the distribution (about 2 bits per symbol)
+        assert!(bits_mut.len_bits() < symbols.len() * 2 + 64 * num_streams + 64);
+
+        let mut bits = bits_mut.freeze();
+        bits.advance_bits(3);
+        let mut decoder = RansDecoder::<V, _>::new(bits, num_streams).unwrap();
+        assert_eq!(decoder.get_symbols(&table, symbols.len()).unwrap(), symbols);
+        let mut bits = decoder.finish().unwrap();
+        assert_eq!(bits.get_u8().unwrap(), 0x5a);
+    }
+
+    #[test]
+    fn test_round_trip_32() {
+        round_trip::<Rans32>(1);
+        round_trip::<Rans32>(4);
+    }
+
+    #[test]
+    fn test_round_trip_64() {
+        round_trip::<Rans64>(1);
+        round_trip::<Rans64>(2);
+    }
+
+    #[test]
+    fn test_table() {
+        let table = RansTable::from_counts(&[1000, 0, 1, 3], 4).unwrap();
+        assert_eq!(table.frequencies(), [14, 0, 1, 1]);
+        assert!(RansTable::from_frequencies(&[8, 7], 4).is_err());
+        assert!(RansTable::from_counts(&[1; 17], 4).is_err());
+
+        let mut encoder = RansEncoder::<Rans32>::new(1);
+        assert!(encoder.put_symbol(&table, 0, 1).is_err());
+        assert!(encoder.put_symbol(&table, 0, 4).is_err());
+    }
+
+    #[test]
+    fn test_corrupt_stream() {
+        let table = RansTable::from_frequencies(&[12, 4], 4).unwrap();
+        let mut encoder = RansEncoder::<Rans32>::new(1);
+        encoder.put_symbols(&table, &[0, 1, 1, 0, 0, 0, 1]).unwrap();
+        let mut bits_mut = BitsMut::new();
+        encoder.finish(&mut bits_mut).unwrap();
+        let mut bytes = bits_mut.freeze().chunk_bytes().to_vec();
+        bytes[3] ^= 0x40;
+
+        let mut decoder = RansDecoder::<Rans32, _>::new(Bits::copy_from_bytes(&bytes), 1).unwrap();
+        // Depending on the damage, the decoder either runs out of data or ends in the wrong state
+        if decoder.get_symbols(&table, 7).is_ok() {
+            assert!(decoder.finish().is_err());
+        }
+    }
+}
@@ -2,7 +2,10 @@ use crate::prelude::*;
 
 use super::{chain::Chain, take::Take};
 
-pub trait BitBuf {
+/// The read side of a bit buffer.  `Order` is the order of the bits within each byte: [`Msb0`]
+/// (the default) for most formats, or [`Lsb0`] for those which pack bits least-significant first
+/// (DEFLATE, Vorbis, GIF/LZW, USB, Arrow bitmaps, ...).
+pub trait BitBuf<Order: BitOrder = Msb0> {
     /// Advance the internal cursor of the `BitBuf` by `count` bits.
     ///
     /// The next call to chunk() will return a slice starting count bits further into the
@@ -45,7 +48,7 @@ pub trait BitBuf {
     ///
     /// The returned `BitBuf` instance will first consume all data from `self`.  Afterwards the
     /// output is equivalent to the output of `next`.
-    fn chain<U: BitBuf>(self, next: U) -> Chain<Self, U>
+    fn chain<U: BitBuf<Order>>(self, next: U) -> Chain<Self, U>
     where
         Self: Sized,
     {
@@ -54,7 +57,7 @@ pub trait BitBuf {
 
     /// Returns a [`BitSlice`] starting at the current position and of length between 0 and
     /// `BitBuf::remaining`.  Note that this _can_ return a shorter slice.
-    fn chunk_bits(&self) -> &BitSlice;
+    fn chunk_bits(&self) -> &bitvec::slice::BitSlice<u8, Order>;
 
     /// Returns a slice of bytes starting at the current position and of length between 0 and
     /// `BitBuf::remaining_bytes`.  Note that this _can_ return a shorter slice.
@@ -64,11 +67,14 @@ pub trait BitBuf {
     ///
     /// The cursor is advanced by the number of bits copied.  `self` must have enough remaining
     /// bits to fill `dest`.
-    fn copy_to_bit_slice(&mut self, dest: &mut BitSlice) {
+    fn copy_to_bit_slice(&mut self, dest: &mut bitvec::slice::BitSlice<u8, Order>) {
         self.try_copy_to_bit_slice(dest).unwrap()
     }
 
-    fn try_copy_to_bit_slice(&mut self, mut dest: &mut BitSlice) -> std::io::Result<()> {
+    fn try_copy_to_bit_slice(
+        &mut self,
+        mut dest: &mut bitvec::slice::BitSlice<u8, Order>,
+    ) -> std::io::Result<()> {
         if self.remaining_bits() < dest.len() {
             return Err(std::io::Error::new(
                 std::io::ErrorKind::UnexpectedEof,
@@ -1,9 +1,14 @@
-use funty::Integral;
+use bitvec::{order::Msb0, view::BitView};
+use funty::{Integral, Signed};
 
-use super::bit_buf::BitBuf;
+use super::{
+    bit_buf::BitBuf,
+    bulk::{self, BULK_BLOCK_BYTES, BULK_BLOCK_LEN},
+    util::consume_run,
+};
 use crate::prelude::*;
 
-pub trait BitBufExts: BitBuf {
+pub trait BitBufExts<Order: BitOrder = Msb0>: BitBuf<Order> {
     #[allow(non_snake_case)]
     fn get_uN<O: ByteOrder, const N: usize, U, V: Integral>(&mut self) -> std::io::Result<U>
     where
@@ -20,8 +25,11 @@ pub trait BitBufExts: BitBuf {
 
             Ok(U::try_from(value).map_err(|_| std::io::ErrorKind::InvalidData)?)
         } else {
-            let mut bits = BitVec::repeat(false, N);
-            let slice = bits.as_mut_bitslice();
+            // A little-endian value depends on where it starts within a byte, so the intermediary
+            // starts at the same bit offset as the value does
+            let offset = slice.as_bitptr().bit().into_inner() as usize;
+            let mut bits = bitvec::vec::BitVec::<u8, Order>::repeat(false, offset + N);
+            let slice = &mut bits[offset..];
             // Copy the raw bits into the slice
             self.try_copy_to_bit_slice(slice)?;
             // Now 'load' the value from that slice according to the given ByteOrder.
@@ -31,36 +39,137 @@ pub trait BitBufExts: BitBuf {
         }
     }
 
+    /// Reads an `N`-bit two's-complement value, sign-extending it into `I`.  `V` is the signed
+    /// primitive type that holds the raw value (e.g. `i16` for an `i12`).
+    #[allow(non_snake_case)]
+    fn get_iN<O: ByteOrder, const N: usize, I, V: Signed>(&mut self) -> std::io::Result<I>
+    where
+        I: TryFrom<V>,
+        I::Error: std::fmt::Debug,
+    {
+        // bitvec sign-extends when loading into a signed type, so this is the same operation as
+        // reading an unsigned value.
+        self.get_uN::<O, N, I, V>()
+    }
+
+    /// Reads `n` bits into an integral value of type `T`, where `n` is only known at runtime.
+    /// Signed types are sign-extended from bit `n - 1`.
+    ///
+    /// Returns an error if `n` is larger than the width of `T`.
+    fn get_bits_as<T: Integral, O: ByteOrder>(&mut self, n: usize) -> std::io::Result<T> {
+        if n > T::BITS as usize {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!("Can't read {} bits into a {}-bit type", n, T::BITS),
+            ));
+        }
+        if n == 0 {
+            return Ok(T::ZERO);
+        }
+        // As in get_uN: read directly from the chunk when the bits are contiguous.
+        let slice = self.chunk_bits();
+        if slice.len() >= n {
+            let value: T = O::load(&slice[..n]);
+            self.advance_bits(n);
+            Ok(value)
+        } else {
+            let offset = slice.as_bitptr().bit().into_inner() as usize;
+            let mut bits = bitvec::vec::BitVec::<u8, Order>::repeat(false, offset + n);
+            self.try_copy_to_bit_slice(&mut bits[offset..])?;
+            Ok(O::load(&bits[offset..]))
+        }
+    }
+
+    /// Reads `dest.len()` `N`-bit (1 to 32) values, giving the same results as calling `get_uN`
+    /// for each one.  Values are extracted a block at a time with word-level shifts and masks (and
+    /// with AVX2 on x86_64, when it's available) rather than with a bit-slice load per value.
+    ///
+    /// Returns an error, without consuming anything, if there aren't enough bits remaining.  An `N`
+    /// outside of 1 to 32 doesn't compile.
+    fn get_many<O: ByteOrder, const N: usize>(&mut self, dest: &mut [u32]) -> std::io::Result<()> {
+        const {
+            assert!(
+                N >= 1 && N <= 32,
+                "Can't read values into a u32 with N outside 1..=32"
+            )
+        }
+        if self.remaining_bits() < dest.len() * N {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::UnexpectedEof,
+                format!(
+                    "Remaining bits ({}) are less than the size of the dest ({})",
+                    self.remaining_bits(),
+                    dest.len() * N
+                ),
+            ));
+        }
+        let mut bytes = [0u8; BULK_BLOCK_BYTES];
+        let mut dest = dest;
+        while !dest.is_empty() {
+            let slice = self.chunk_bits();
+            // Unpack as many whole values as the current chunk holds.  A value which spans chunks
+            // is read the slow way, as get_uN would.
+            let count = (slice.len() / N).min(dest.len()).min(BULK_BLOCK_LEN);
+            if count == 0 {
+                dest[0] = self.get_uN::<O, N, u32, u32>()?;
+                dest = &mut dest[1..];
+                continue;
+            }
+            let (block, rest) = dest.split_at_mut(count);
+            let len = count * N;
+            // The byte order depends on where each value falls within its bytes
+            let offset = slice.as_bitptr().bit().into_inner() as usize;
+            bytes.view_bits_mut::<Order>()[..len].copy_from_bitslice(&slice[..len]);
+            bulk::unpack::<Order>(&bytes, N, block);
+            for (i, value) in block.iter_mut().enumerate() {
+                *value =
+                    bulk::from_raw_bits::<Order, O>(*value as u64, N, (offset + i * N) % 8) as u32;
+            }
+            self.advance_bits(len);
+            dest = rest;
+        }
+        Ok(())
+    }
+
+    /// Reads any [`BitDecode`] type.  Types narrower than a byte ignore `O` and are always read
+    /// in `Order`'s natural field order.
+    ///
+    /// Note that this shadows `BitSlice::get` for types like [`Bits`] which deref to a
+    /// `BitSlice`: use `bits[..].get(index)` to look up a single bit.
+    fn get<T: BitDecode, O: ByteOrder>(&mut self) -> std::io::Result<T> {
+        T::bit_decode::<O, Order, Self>(self)
+    }
+
     fn get_bool(&mut self) -> std::io::Result<bool> {
         Ok(self.get_u1()?.into())
     }
 
     fn get_u1(&mut self) -> std::io::Result<u1> {
-        self.get_uN::<BigEndian, 1, u1, u8>()
+        self.get_uN::<Order::FieldOrder, 1, u1, u8>()
     }
 
     fn get_u2(&mut self) -> std::io::Result<u2> {
-        self.get_uN::<BigEndian, 2, u2, u8>()
+        self.get_uN::<Order::FieldOrder, 2, u2, u8>()
     }
 
     fn get_u3(&mut self) -> std::io::Result<u3> {
-        self.get_uN::<BigEndian, 3, u3, u8>()
+        self.get_uN::<Order::FieldOrder, 3, u3, u8>()
     }
 
     fn get_u4(&mut self) -> std::io::Result<u4> {
-        self.get_uN::<BigEndian, 4, u4, u8>()
+        self.get_uN::<Order::FieldOrder, 4, u4, u8>()
     }
 
     fn get_u5(&mut self) -> std::io::Result<u5> {
-        self.get_uN::<BigEndian, 5, u5, u8>()
+        self.get_uN::<Order::FieldOrder, 5, u5, u8>()
     }
 
     fn get_u6(&mut self) -> std::io::Result<u6> {
-        self.get_uN::<BigEndian, 6, u6, u8>()
+        self.get_uN::<Order::FieldOrder, 6, u6, u8>()
     }
 
     fn get_u7(&mut self) -> std::io::Result<u7> {
-        self.get_uN::<BigEndian, 7, u7, u8>()
+        self.get_uN::<Order::FieldOrder, 7, u7, u8>()
     }
 
     fn get_u8(&mut self) -> std::io::Result<u8> {
@@ -78,7 +187,7 @@ pub trait BitBufExts: BitBuf {
             self.advance_bytes(1);
             return Ok(value);
         }
-        self.get_uN::<BigEndian, 8, u8, u8>()
+        self.get_uN::<Order::FieldOrder, 8, u8, u8>()
     }
 
     fn get_u9<O: ByteOrder>(&mut self) -> std::io::Result<u9> {
@@ -195,9 +304,526 @@ pub trait BitBufExts: BitBuf {
         }
         self.get_uN::<O, 32, u32, u32>()
     }
+    fn get_u33<O: ByteOrder>(&mut self) -> std::io::Result<u33> {
+        self.get_uN::<O, 33, u33, u64>()
+    }
+    fn get_u34<O: ByteOrder>(&mut self) -> std::io::Result<u34> {
+        self.get_uN::<O, 34, u34, u64>()
+    }
+    fn get_u35<O: ByteOrder>(&mut self) -> std::io::Result<u35> {
+        self.get_uN::<O, 35, u35, u64>()
+    }
+    fn get_u36<O: ByteOrder>(&mut self) -> std::io::Result<u36> {
+        self.get_uN::<O, 36, u36, u64>()
+    }
+    fn get_u37<O: ByteOrder>(&mut self) -> std::io::Result<u37> {
+        self.get_uN::<O, 37, u37, u64>()
+    }
+    fn get_u38<O: ByteOrder>(&mut self) -> std::io::Result<u38> {
+        self.get_uN::<O, 38, u38, u64>()
+    }
+    fn get_u39<O: ByteOrder>(&mut self) -> std::io::Result<u39> {
+        self.get_uN::<O, 39, u39, u64>()
+    }
+    fn get_u40<O: ByteOrder>(&mut self) -> std::io::Result<u40> {
+        if self.byte_aligned() {
+            if self.remaining_bytes() < 5 {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::UnexpectedEof,
+                    format!(
+                        "Remaining bytes ({}) are less than the size of the dest (5)",
+                        self.remaining_bytes(),
+                    ),
+                ));
+            }
+            let mut dest = [0u8; 5];
+            self.try_copy_to_slice_bytes(&mut dest)?;
+            return Ok(O::load_u40(&dest));
+        }
+        self.get_uN::<O, 40, u40, u64>()
+    }
+    fn get_u41<O: ByteOrder>(&mut self) -> std::io::Result<u41> {
+        self.get_uN::<O, 41, u41, u64>()
+    }
+    fn get_u42<O: ByteOrder>(&mut self) -> std::io::Result<u42> {
+        self.get_uN::<O, 42, u42, u64>()
+    }
+    fn get_u43<O: ByteOrder>(&mut self) -> std::io::Result<u43> {
+        self.get_uN::<O, 43, u43, u64>()
+    }
+    fn get_u44<O: ByteOrder>(&mut self) -> std::io::Result<u44> {
+        self.get_uN::<O, 44, u44, u64>()
+    }
+    fn get_u45<O: ByteOrder>(&mut self) -> std::io::Result<u45> {
+        self.get_uN::<O, 45, u45, u64>()
+    }
+    fn get_u46<O: ByteOrder>(&mut self) -> std::io::Result<u46> {
+        self.get_uN::<O, 46, u46, u64>()
+    }
+    fn get_u47<O: ByteOrder>(&mut self) -> std::io::Result<u47> {
+        self.get_uN::<O, 47, u47, u64>()
+    }
+    fn get_u48<O: ByteOrder>(&mut self) -> std::io::Result<u48> {
+        if self.byte_aligned() {
+            if self.remaining_bytes() < 6 {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::UnexpectedEof,
+                    format!(
+                        "Remaining bytes ({}) are less than the size of the dest (6)",
+                        self.remaining_bytes(),
+                    ),
+                ));
+            }
+            let mut dest = [0u8; 6];
+            self.try_copy_to_slice_bytes(&mut dest)?;
+            return Ok(O::load_u48(&dest));
+        }
+        self.get_uN::<O, 48, u48, u64>()
+    }
+    fn get_u49<O: ByteOrder>(&mut self) -> std::io::Result<u49> {
+        self.get_uN::<O, 49, u49, u64>()
+    }
+    fn get_u50<O: ByteOrder>(&mut self) -> std::io::Result<u50> {
+        self.get_uN::<O, 50, u50, u64>()
+    }
+    fn get_u51<O: ByteOrder>(&mut self) -> std::io::Result<u51> {
+        self.get_uN::<O, 51, u51, u64>()
+    }
+    fn get_u52<O: ByteOrder>(&mut self) -> std::io::Result<u52> {
+        self.get_uN::<O, 52, u52, u64>()
+    }
+    fn get_u53<O: ByteOrder>(&mut self) -> std::io::Result<u53> {
+        self.get_uN::<O, 53, u53, u64>()
+    }
+    fn get_u54<O: ByteOrder>(&mut self) -> std::io::Result<u54> {
+        self.get_uN::<O, 54, u54, u64>()
+    }
+    fn get_u55<O: ByteOrder>(&mut self) -> std::io::Result<u55> {
+        self.get_uN::<O, 55, u55, u64>()
+    }
+    fn get_u56<O: ByteOrder>(&mut self) -> std::io::Result<u56> {
+        if self.byte_aligned() {
+            if self.remaining_bytes() < 7 {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::UnexpectedEof,
+                    format!(
+                        "Remaining bytes ({}) are less than the size of the dest (7)",
+                        self.remaining_bytes(),
+                    ),
+                ));
+            }
+            let mut dest = [0u8; 7];
+            self.try_copy_to_slice_bytes(&mut dest)?;
+            return Ok(O::load_u56(&dest));
+        }
+        self.get_uN::<O, 56, u56, u64>()
+    }
+    fn get_u57<O: ByteOrder>(&mut self) -> std::io::Result<u57> {
+        self.get_uN::<O, 57, u57, u64>()
+    }
+    fn get_u58<O: ByteOrder>(&mut self) -> std::io::Result<u58> {
+        self.get_uN::<O, 58, u58, u64>()
+    }
+    fn get_u59<O: ByteOrder>(&mut self) -> std::io::Result<u59> {
+        self.get_uN::<O, 59, u59, u64>()
+    }
+    fn get_u60<O: ByteOrder>(&mut self) -> std::io::Result<u60> {
+        self.get_uN::<O, 60, u60, u64>()
+    }
+    fn get_u61<O: ByteOrder>(&mut self) -> std::io::Result<u61> {
+        self.get_uN::<O, 61, u61, u64>()
+    }
+    fn get_u62<O: ByteOrder>(&mut self) -> std::io::Result<u62> {
+        self.get_uN::<O, 62, u62, u64>()
+    }
+    fn get_u63<O: ByteOrder>(&mut self) -> std::io::Result<u63> {
+        self.get_uN::<O, 63, u63, u64>()
+    }
+    fn get_u64<O: ByteOrder>(&mut self) -> std::io::Result<u64> {
+        if self.byte_aligned() {
+            if self.remaining_bytes() < 8 {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::UnexpectedEof,
+                    format!(
+                        "Remaining bytes ({}) are less than the size of the dest (8)",
+                        self.remaining_bytes(),
+                    ),
+                ));
+            }
+            let mut dest = [0u8; 8];
+            self.try_copy_to_slice_bytes(&mut dest)?;
+            return Ok(O::load_u64(&dest));
+        }
+        self.get_uN::<O, 64, u64, u64>()
+    }
+    fn get_u128<O: ByteOrder>(&mut self) -> std::io::Result<u128> {
+        if self.byte_aligned() {
+            if self.remaining_bytes() < 16 {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::UnexpectedEof,
+                    format!(
+                        "Remaining bytes ({}) are less than the size of the dest (16)",
+                        self.remaining_bytes(),
+                    ),
+                ));
+            }
+            let mut dest = [0u8; 16];
+            self.try_copy_to_slice_bytes(&mut dest)?;
+            return Ok(O::load_u128(&dest));
+        }
+        self.get_uN::<O, 128, u128, u128>()
+    }
+
+    fn get_i1(&mut self) -> std::io::Result<i1> {
+        // i1 has no conversions from the primitive types, so build it from the raw bit.
+        let value: u8 = self.get_u1()?.into();
+        Ok(i1::new(-(value as i8)))
+    }
+
+    fn get_i2(&mut self) -> std::io::Result<i2> {
+        self.get_iN::<Order::FieldOrder, 2, i2, i8>()
+    }
+
+    fn get_i3(&mut self) -> std::io::Result<i3> {
+        self.get_iN::<Order::FieldOrder, 3, i3, i8>()
+    }
+
+    fn get_i4(&mut self) -> std::io::Result<i4> {
+        self.get_iN::<Order::FieldOrder, 4, i4, i8>()
+    }
+
+    fn get_i5(&mut self) -> std::io::Result<i5> {
+        self.get_iN::<Order::FieldOrder, 5, i5, i8>()
+    }
+
+    fn get_i6(&mut self) -> std::io::Result<i6> {
+        self.get_iN::<Order::FieldOrder, 6, i6, i8>()
+    }
+
+    fn get_i7(&mut self) -> std::io::Result<i7> {
+        self.get_iN::<Order::FieldOrder, 7, i7, i8>()
+    }
+
+    fn get_i8(&mut self) -> std::io::Result<i8> {
+        Ok(self.get_u8()? as i8)
+    }
+
+    fn get_i9<O: ByteOrder>(&mut self) -> std::io::Result<i9> {
+        self.get_iN::<O, 9, i9, i16>()
+    }
+    fn get_i10<O: ByteOrder>(&mut self) -> std::io::Result<i10> {
+        self.get_iN::<O, 10, i10, i16>()
+    }
+    fn get_i11<O: ByteOrder>(&mut self) -> std::io::Result<i11> {
+        self.get_iN::<O, 11, i11, i16>()
+    }
+    fn get_i12<O: ByteOrder>(&mut self) -> std::io::Result<i12> {
+        self.get_iN::<O, 12, i12, i16>()
+    }
+    fn get_i13<O: ByteOrder>(&mut self) -> std::io::Result<i13> {
+        self.get_iN::<O, 13, i13, i16>()
+    }
+    fn get_i14<O: ByteOrder>(&mut self) -> std::io::Result<i14> {
+        self.get_iN::<O, 14, i14, i16>()
+    }
+    fn get_i15<O: ByteOrder>(&mut self) -> std::io::Result<i15> {
+        self.get_iN::<O, 15, i15, i16>()
+    }
+    fn get_i16<O: ByteOrder>(&mut self) -> std::io::Result<i16> {
+        Ok(self.get_u16::<O>()? as i16)
+    }
+    fn get_i17<O: ByteOrder>(&mut self) -> std::io::Result<i17> {
+        self.get_iN::<O, 17, i17, i32>()
+    }
+    fn get_i18<O: ByteOrder>(&mut self) -> std::io::Result<i18> {
+        self.get_iN::<O, 18, i18, i32>()
+    }
+    fn get_i19<O: ByteOrder>(&mut self) -> std::io::Result<i19> {
+        self.get_iN::<O, 19, i19, i32>()
+    }
+    fn get_i20<O: ByteOrder>(&mut self) -> std::io::Result<i20> {
+        self.get_iN::<O, 20, i20, i32>()
+    }
+    fn get_i21<O: ByteOrder>(&mut self) -> std::io::Result<i21> {
+        self.get_iN::<O, 21, i21, i32>()
+    }
+    fn get_i22<O: ByteOrder>(&mut self) -> std::io::Result<i22> {
+        self.get_iN::<O, 22, i22, i32>()
+    }
+    fn get_i23<O: ByteOrder>(&mut self) -> std::io::Result<i23> {
+        self.get_iN::<O, 23, i23, i32>()
+    }
+    fn get_i24<O: ByteOrder>(&mut self) -> std::io::Result<i24> {
+        // Go through get_u{n} for its byte-aligned fast path, then sign-extend
+        let value: u32 = self.get_u24::<O>()?.into();
+        Ok(i24::new(((value << 8) as i32) >> 8))
+    }
+    fn get_i25<O: ByteOrder>(&mut self) -> std::io::Result<i25> {
+        self.get_iN::<O, 25, i25, i32>()
+    }
+    fn get_i26<O: ByteOrder>(&mut self) -> std::io::Result<i26> {
+        self.get_iN::<O, 26, i26, i32>()
+    }
+    fn get_i27<O: ByteOrder>(&mut self) -> std::io::Result<i27> {
+        self.get_iN::<O, 27, i27, i32>()
+    }
+    fn get_i28<O: ByteOrder>(&mut self) -> std::io::Result<i28> {
+        self.get_iN::<O, 28, i28, i32>()
+    }
+    fn get_i29<O: ByteOrder>(&mut self) -> std::io::Result<i29> {
+        self.get_iN::<O, 29, i29, i32>()
+    }
+    fn get_i30<O: ByteOrder>(&mut self) -> std::io::Result<i30> {
+        self.get_iN::<O, 30, i30, i32>()
+    }
+    fn get_i31<O: ByteOrder>(&mut self) -> std::io::Result<i31> {
+        self.get_iN::<O, 31, i31, i32>()
+    }
+    fn get_i32<O: ByteOrder>(&mut self) -> std::io::Result<i32> {
+        Ok(self.get_u32::<O>()? as i32)
+    }
+    fn get_i33<O: ByteOrder>(&mut self) -> std::io::Result<i33> {
+        self.get_iN::<O, 33, i33, i64>()
+    }
+    fn get_i34<O: ByteOrder>(&mut self) -> std::io::Result<i34> {
+        self.get_iN::<O, 34, i34, i64>()
+    }
+    fn get_i35<O: ByteOrder>(&mut self) -> std::io::Result<i35> {
+        self.get_iN::<O, 35, i35, i64>()
+    }
+    fn get_i36<O: ByteOrder>(&mut self) -> std::io::Result<i36> {
+        self.get_iN::<O, 36, i36, i64>()
+    }
+    fn get_i37<O: ByteOrder>(&mut self) -> std::io::Result<i37> {
+        self.get_iN::<O, 37, i37, i64>()
+    }
+    fn get_i38<O: ByteOrder>(&mut self) -> std::io::Result<i38> {
+        self.get_iN::<O, 38, i38, i64>()
+    }
+    fn get_i39<O: ByteOrder>(&mut self) -> std::io::Result<i39> {
+        self.get_iN::<O, 39, i39, i64>()
+    }
+    fn get_i40<O: ByteOrder>(&mut self) -> std::io::Result<i40> {
+        let value: u64 = self.get_u40::<O>()?.into();
+        Ok(i40::new(((value << 24) as i64) >> 24))
+    }
+    fn get_i41<O: ByteOrder>(&mut self) -> std::io::Result<i41> {
+        self.get_iN::<O, 41, i41, i64>()
+    }
+    fn get_i42<O: ByteOrder>(&mut self) -> std::io::Result<i42> {
+        self.get_iN::<O, 42, i42, i64>()
+    }
+    fn get_i43<O: ByteOrder>(&mut self) -> std::io::Result<i43> {
+        self.get_iN::<O, 43, i43, i64>()
+    }
+    fn get_i44<O: ByteOrder>(&mut self) -> std::io::Result<i44> {
+        self.get_iN::<O, 44, i44, i64>()
+    }
+    fn get_i45<O: ByteOrder>(&mut self) -> std::io::Result<i45> {
+        self.get_iN::<O, 45, i45, i64>()
+    }
+    fn get_i46<O: ByteOrder>(&mut self) -> std::io::Result<i46> {
+        self.get_iN::<O, 46, i46, i64>()
+    }
+    fn get_i47<O: ByteOrder>(&mut self) -> std::io::Result<i47> {
+        self.get_iN::<O, 47, i47, i64>()
+    }
+    fn get_i48<O: ByteOrder>(&mut self) -> std::io::Result<i48> {
+        let value: u64 = self.get_u48::<O>()?.into();
+        Ok(i48::new(((value << 16) as i64) >> 16))
+    }
+    fn get_i49<O: ByteOrder>(&mut self) -> std::io::Result<i49> {
+        self.get_iN::<O, 49, i49, i64>()
+    }
+    fn get_i50<O: ByteOrder>(&mut self) -> std::io::Result<i50> {
+        self.get_iN::<O, 50, i50, i64>()
+    }
+    fn get_i51<O: ByteOrder>(&mut self) -> std::io::Result<i51> {
+        self.get_iN::<O, 51, i51, i64>()
+    }
+    fn get_i52<O: ByteOrder>(&mut self) -> std::io::Result<i52> {
+        self.get_iN::<O, 52, i52, i64>()
+    }
+    fn get_i53<O: ByteOrder>(&mut self) -> std::io::Result<i53> {
+        self.get_iN::<O, 53, i53, i64>()
+    }
+    fn get_i54<O: ByteOrder>(&mut self) -> std::io::Result<i54> {
+        self.get_iN::<O, 54, i54, i64>()
+    }
+    fn get_i55<O: ByteOrder>(&mut self) -> std::io::Result<i55> {
+        self.get_iN::<O, 55, i55, i64>()
+    }
+    fn get_i56<O: ByteOrder>(&mut self) -> std::io::Result<i56> {
+        let value: u64 = self.get_u56::<O>()?.into();
+        Ok(i56::new(((value << 8) as i64) >> 8))
+    }
+    fn get_i57<O: ByteOrder>(&mut self) -> std::io::Result<i57> {
+        self.get_iN::<O, 57, i57, i64>()
+    }
+    fn get_i58<O: ByteOrder>(&mut self) -> std::io::Result<i58> {
+        self.get_iN::<O, 58, i58, i64>()
+    }
+    fn get_i59<O: ByteOrder>(&mut self) -> std::io::Result<i59> {
+        self.get_iN::<O, 59, i59, i64>()
+    }
+    fn get_i60<O: ByteOrder>(&mut self) -> std::io::Result<i60> {
+        self.get_iN::<O, 60, i60, i64>()
+    }
+    fn get_i61<O: ByteOrder>(&mut self) -> std::io::Result<i61> {
+        self.get_iN::<O, 61, i61, i64>()
+    }
+    fn get_i62<O: ByteOrder>(&mut self) -> std::io::Result<i62> {
+        self.get_iN::<O, 62, i62, i64>()
+    }
+    fn get_i63<O: ByteOrder>(&mut self) -> std::io::Result<i63> {
+        self.get_iN::<O, 63, i63, i64>()
+    }
+
+    fn get_i64<O: ByteOrder>(&mut self) -> std::io::Result<i64> {
+        Ok(self.get_u64::<O>()? as i64)
+    }
+
+    fn get_i128<O: ByteOrder>(&mut self) -> std::io::Result<i128> {
+        Ok(self.get_u128::<O>()? as i128)
+    }
+
+    /// Reads a k-th order Exp-Golomb code: `n` leading zero bits, a one bit, and then `n + k`
+    /// suffix bits.
+    ///
+    /// Returns an error if the code has more than 32 leading zeros or `k` is larger than 32.
+    fn get_exp_golomb(&mut self, k: u32) -> std::io::Result<u64> {
+        if k > 32 {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!("Exp-Golomb order ({}) must be at most 32", k),
+            ));
+        }
+        let leading_zeros = consume_run(self, false, 32)?;
+        let suffix_len = leading_zeros + k as usize;
+        let suffix: u64 = self.get_bits_as::<u64, Order::FieldOrder>(suffix_len)?;
+
+        (((1u64 << leading_zeros) - 1) << k)
+            .checked_add(suffix)
+            .ok_or_else(|| {
+                std::io::Error::new(
+                    std::io::ErrorKind::InvalidData,
+                    "Exp-Golomb code overflows a u64",
+                )
+            })
+    }
+
+    /// Reads an unsigned Exp-Golomb code, the `ue(v)` descriptor in H.264/HEVC/VVC.
+    fn get_ue(&mut self) -> std::io::Result<u64> {
+        self.get_exp_golomb(0)
+    }
+
+    /// Reads a signed Exp-Golomb code, the `se(v)` descriptor in H.264/HEVC/VVC.
+    fn get_se(&mut self) -> std::io::Result<i64> {
+        let value = self.get_ue()?;
+        // Odd code numbers map to positive values and even ones to negative values
+        if value & 1 == 1 {
+            Ok(value.div_ceil(2) as i64)
+        } else {
+            Ok(-((value / 2) as i64))
+        }
+    }
+
+    /// Reads a unary code: a run of bits opposite to `terminator`, followed by the `terminator`
+    /// bit.  Returns the length of the run.  The run is counted a chunk at a time rather than one
+    /// bit at a time.
+    fn get_unary(&mut self, terminator: bool) -> std::io::Result<u64> {
+        Ok(consume_run(self, !terminator, usize::MAX)? as u64)
+    }
+
+    /// Reads a Golomb-Rice code with parameter `k`: a unary quotient (zeros terminated by a one)
+    /// followed by a `k`-bit remainder.
+    fn get_rice(&mut self, k: u32) -> std::io::Result<u64> {
+        if k >= 64 {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!("Rice parameter ({}) must be less than 64", k),
+            ));
+        }
+        let quotient = self.get_unary(true)?;
+        // Shifting the quotient up by k mustn't lose any of its bits
+        if k > 0 && quotient.leading_zeros() < k {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                format!("Rice code with k = {} overflows a u64", k),
+            ));
+        }
+        let remainder = self.get_bits_as::<u64, Order::FieldOrder>(k as usize)?;
+
+        Ok((quotient << k) | remainder)
+    }
+
+    /// Reads a Golomb code with parameter `m`: a unary quotient (zeros terminated by a one)
+    /// followed by a truncated binary remainder in `[0, m)`.
+    fn get_golomb(&mut self, m: u64) -> std::io::Result<u64> {
+        if m == 0 {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "Golomb parameter must be non-zero",
+            ));
+        }
+        if m.is_power_of_two() {
+            return self.get_rice(m.trailing_zeros());
+        }
+        let quotient = self.get_unary(true)?;
+        // Truncated binary: the first `cutoff` remainders use b - 1 bits and the rest use b bits.
+        let b = 64 - (m - 1).leading_zeros();
+        // b is 64 for m above 2^63, so the shift needs more than 64 bits
+        let cutoff = ((1u128 << b) - m as u128) as u64;
+        let mut remainder = self.get_bits_as::<u64, Order::FieldOrder>(b as usize - 1)?;
+        if remainder >= cutoff {
+            remainder = ((remainder << 1) | self.get_bool()? as u64) - cutoff;
+        }
+
+        quotient
+            .checked_mul(m)
+            .and_then(|value| value.checked_add(remainder))
+            .ok_or_else(|| {
+                std::io::Error::new(
+                    std::io::ErrorKind::InvalidData,
+                    format!("Golomb code with m = {} overflows a u64", m),
+                )
+            })
+    }
+
+    fn get_f32<O: ByteOrder>(&mut self) -> std::io::Result<f32> {
+        Ok(f32::from_bits(self.get_u32::<O>()?))
+    }
+
+    fn get_f64<O: ByteOrder>(&mut self) -> std::io::Result<f64> {
+        Ok(f64::from_bits(self.get_u64::<O>()?))
+    }
+
+    /// Reads an IEEE-754 half precision (binary16) value and widens it to an `f32`.
+    fn get_f16<O: ByteOrder>(&mut self) -> std::io::Result<f32> {
+        let raw = self.get_u16::<O>()?;
+        Ok(MinifloatFormat::F16.decode(raw as u64) as f32)
+    }
+
+    /// Reads a bfloat16 value and widens it to an `f32`.
+    fn get_bf16<O: ByteOrder>(&mut self) -> std::io::Result<f32> {
+        let raw = self.get_u16::<O>()?;
+        Ok(f32::from_bits((raw as u32) << 16))
+    }
+
+    /// Reads a floating point value in the given [`MinifloatFormat`], which may be any width and
+    /// may start at any bit offset, and widens it to an `f64`.
+    fn get_minifloat<O: ByteOrder>(&mut self, format: MinifloatFormat) -> std::io::Result<f64> {
+        // As in get_uN, the value is staged at the bit offset it starts at
+        let offset = self.chunk_bits().as_bitptr().bit().into_inner() as usize;
+        let mut bits = bitvec::vec::BitVec::<u8, Order>::repeat(false, offset + format.bit_width());
+        self.try_copy_to_bit_slice(&mut bits[offset..])?;
+        let raw: u64 = O::load(&bits[offset..]);
+
+        Ok(format.decode(raw))
+    }
 }
 
-impl<T: BitBuf + ?Sized> BitBufExts for T {}
+impl<Order: BitOrder, T: BitBuf<Order> + ?Sized> BitBufExts<Order> for T {}
 
 #[cfg(test)]
 mod tests {
@@ -284,4 +910,356 @@ mod tests {
             u20::new(0b1010_10111100_11011110)
         );
     }
+
+    #[test]
+    fn test_get_wide() {
+        // 33-bit PTS-style value, not byte-aligned
+        let mut bits = Bits::copy_from_bit_slice(bits![1; 33]);
+        assert_eq!(bits.get_u33::<BigEndian>().unwrap(), u33::MAX);
+
+        // 48-bit MAC address, byte-aligned
+        let mut bits = Bits::from_static_bytes(&[0x00, 0x1A, 0x2B, 0x3C, 0x4D, 0x5E]);
+        assert_eq!(
+            bits.get_u48::<NetworkOrder>().unwrap(),
+            u48::new(0x001A2B3C4D5E)
+        );
+        let mut bits = Bits::from_static_bytes(&[0x00, 0x1A, 0x2B, 0x3C, 0x4D, 0x5E]);
+        assert_eq!(
+            bits.get_u48::<LittleEndian>().unwrap(),
+            u48::new(0x5E4D3C2B1A00)
+        );
+
+        // 64-bit value after a single bit, so it can't take the byte-aligned path
+        let mut bits = Bits::from_static_bytes(&[
+            0b1000_0000,
+            0x00,
+            0x00,
+            0x00,
+            0x00,
+            0x00,
+            0x00,
+            0x00,
+            0b1000_0000,
+        ]);
+        assert!(bits.get_bool().unwrap());
+        assert_eq!(bits.get_u64::<BigEndian>().unwrap(), 0x01);
+
+        let mut bits = Bits::from_static_bytes(&[0xAB; 16]);
+        assert_eq!(
+            bits.get_u128::<BigEndian>().unwrap(),
+            u128::from_be_bytes([0xAB; 16])
+        );
+        assert!(bits.get_u40::<BigEndian>().is_err());
+    }
+
+    #[test]
+    fn test_get_signed() {
+        let mut bits = Bits::copy_from_bit_slice(bits![1, 1, 0, 0, 0, 0, 0, 0, 1, 0, 1, 1]);
+        assert_eq!(bits.get_i1().unwrap(), i1::new(-1));
+        assert_eq!(bits.get_i7().unwrap(), i7::new(-64));
+        assert_eq!(bits.get_i4().unwrap(), i4::new(-5));
+
+        // 12-bit signed deltas
+        let mut bits = Bits::copy_from_bit_slice(bits![
+            1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1
+        ]);
+        assert_eq!(bits.get_i12::<BigEndian>().unwrap(), i12::new(-2048));
+        assert_eq!(bits.get_i12::<BigEndian>().unwrap(), i12::new(-1));
+
+        let mut bits = Bits::from_static_bytes(&[0xFF, 0xFE, 0x80, 0x00, 0x00, 0x00]);
+        assert_eq!(bits.get_i16::<BigEndian>().unwrap(), -2);
+        assert_eq!(bits.get_i32::<BigEndian>().unwrap(), i32::MIN);
+    }
+
+    #[test]
+    fn test_signed_round_trip() {
+        let mut bits_mut = BitsMut::new();
+        bits_mut.put_u3(u3::new(0)).unwrap();
+        bits_mut.put_i20::<LittleEndian>(i20::new(-12345)).unwrap();
+        bits_mut.put_i33::<BigEndian>(i33::MIN).unwrap();
+        bits_mut.put_i64::<BigEndian>(-42).unwrap();
+        let mut bits = bits_mut.freeze();
+        bits.get_u3().unwrap();
+        assert_eq!(bits.get_i20::<LittleEndian>().unwrap(), i20::new(-12345));
+        assert_eq!(bits.get_i33::<BigEndian>().unwrap(), i33::MIN);
+        assert_eq!(bits.get_i64::<BigEndian>().unwrap(), -42);
+    }
+
+    #[test]
+    fn test_get_floats() {
+        let mut bits = Bits::from_static_bytes(&[
+            0x40, 0x49, 0x0F, 0xDB, // f32 pi, big endian
+            0x00, 0x00, 0x80, 0x3F, // f32 1.0, little endian
+            0x3C, 0x00, // f16 1.0
+            0x3F, 0x80, // bf16 1.0
+        ]);
+        assert_eq!(bits.get_f32::<BigEndian>().unwrap(), std::f32::consts::PI);
+        assert_eq!(bits.get_f32::<LittleEndian>().unwrap(), 1.0);
+        assert_eq!(bits.get_f16::<BigEndian>().unwrap(), 1.0);
+        assert_eq!(bits.get_bf16::<BigEndian>().unwrap(), 1.0);
+
+        // An unaligned f64
+        let mut bits_mut = BitsMut::new();
+        bits_mut.put_u3(u3::new(0b101)).unwrap();
+        bits_mut.put_u64::<BigEndian>((-1.25f64).to_bits()).unwrap();
+        let mut bits = bits_mut.freeze();
+        bits.get_u3().unwrap();
+        assert_eq!(bits.get_f64::<BigEndian>().unwrap(), -1.25);
+    }
+
+    #[test]
+    fn test_get_minifloat() {
+        // 1 sign bit, 3 exponent bits, 2 mantissa bits: 0_100_10 is 1.5 * 2^(4-3) = 3.0
+        let mut bits = Bits::copy_from_bit_slice(bits![1, 0, 1, 0, 0, 1, 0]);
+        bits.get_bool().unwrap();
+        let format = MinifloatFormat::new(3, 2);
+        assert_eq!(bits.get_minifloat::<BigEndian>(format).unwrap(), 3.0);
+    }
+
+    #[test]
+    fn test_get_bits_as() {
+        let mut bits = Bits::copy_from_bit_slice(bits![1, 0, 1, 1, 1, 1, 1, 0, 1, 0, 1]);
+        // Widths chosen at runtime
+        let widths = [1usize, 3, 7];
+        let values: Vec<u32> = widths
+            .iter()
+            .map(|&n| bits.get_bits_as::<u32, BigEndian>(n).unwrap())
+            .collect();
+        assert_eq!(values, [0b1, 0b011, 0b1110101]);
+
+        let mut bits = Bits::copy_from_bit_slice(bits![1, 1, 1, 0]);
+        assert_eq!(bits.get_bits_as::<i8, BigEndian>(4).unwrap(), -2);
+
+        let mut bits = Bits::from_static_bytes(&[0xFF, 0xFF]);
+        assert!(bits.get_bits_as::<u8, BigEndian>(9).is_err());
+        // A failed read doesn't consume anything
+        assert_eq!(bits.remaining_bits(), 16);
+        assert_eq!(bits.get_bits_as::<u16, BigEndian>(0).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_get_bits_as_across_chain() {
+        let left = Bits::copy_from_bit_slice(bits![1, 0, 1]);
+        let right = Bits::copy_from_bit_slice(bits![1, 0, 0, 1]);
+        let mut chain = left.chain(right);
+        assert_eq!(chain.get_bits_as::<u16, BigEndian>(7).unwrap(), 0b1011001);
+    }
+
+    #[test]
+    fn test_get_little_endian_across_chain() {
+        // A little-endian value split across chunks reads the same as it does contiguously
+        let mut bits_mut = BitsMut::with_capacity(1024);
+        bits_mut.put_u5(u5::new(0b10110)).unwrap();
+        for i in 0..20u16 {
+            bits_mut.put_u13::<LittleEndian>(u13::new(i * 373)).unwrap();
+            bits_mut.put_f16::<LittleEndian>(i as f32 / 4.0).unwrap();
+        }
+        let bits = bits_mut.freeze();
+        for split in [7, 12, 30, 101] {
+            let mut chain = bits
+                .slice_bits(0..split)
+                .chain(bits.slice_bits(split..bits.len_bits()));
+            let mut contiguous = bits.clone();
+            assert_eq!(chain.get_u5().unwrap(), contiguous.get_u5().unwrap());
+            for _ in 0..20 {
+                assert_eq!(
+                    chain.get_u13::<LittleEndian>().unwrap(),
+                    contiguous.get_u13::<LittleEndian>().unwrap()
+                );
+                assert_eq!(
+                    chain.get_bits_as::<u16, LittleEndian>(16).unwrap(),
+                    contiguous.get_bits_as::<u16, LittleEndian>(16).unwrap()
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_get_ue_se() {
+        // ue(v) codes for 0, 1, 2, 3, 7
+        let mut bits = Bits::copy_from_bit_slice(bits![
+            1, //
+            0, 1, 0, //
+            0, 1, 1, //
+            0, 0, 1, 0, 0, //
+            0, 0, 0, 1, 0, 0, 0
+        ]);
+        let values: Vec<u64> = (0..5).map(|_| bits.get_ue().unwrap()).collect();
+        assert_eq!(values, [0, 1, 2, 3, 7]);
+
+        // se(v) codes for 0, 1, -1, 2, -2
+        let mut bits = Bits::copy_from_bit_slice(bits![
+            1, //
+            0, 1, 0, //
+            0, 1, 1, //
+            0, 0, 1, 0, 0, //
+            0, 0, 1, 0, 1
+        ]);
+        let values: Vec<i64> = (0..5).map(|_| bits.get_se().unwrap()).collect();
+        assert_eq!(values, [0, 1, -1, 2, -2]);
+    }
+
+    #[test]
+    fn test_get_exp_golomb_k() {
+        // 2nd order: 1 followed by 2 suffix bits is 0..3, 01 followed by 3 suffix bits is 4..11
+        let mut bits = Bits::copy_from_bit_slice(bits![1, 1, 0, 0, 1, 0, 1, 1]);
+        assert_eq!(bits.get_exp_golomb(2).unwrap(), 2);
+        assert_eq!(bits.get_exp_golomb(2).unwrap(), 4 + 0b011);
+    }
+
+    #[test]
+    fn test_get_ue_overlong() {
+        // 33 leading zeros
+        let mut data = BitVec::repeat(false, 33);
+        data.extend_from_bitslice(bits![1; 34]);
+        let mut bits = Bits::from(data);
+        assert_eq!(
+            bits.get_ue().unwrap_err().kind(),
+            std::io::ErrorKind::InvalidData
+        );
+
+        // Exactly 32 leading zeros is the longest code allowed
+        let mut data = BitVec::repeat(false, 32);
+        data.extend_from_bitslice(bits![1; 33]);
+        let mut bits = Bits::from(data);
+        assert_eq!(bits.get_ue().unwrap(), (1 << 33) - 2);
+
+        let mut bits = Bits::copy_from_bit_slice(bits![0, 0, 0]);
+        assert_eq!(
+            bits.get_ue().unwrap_err().kind(),
+            std::io::ErrorKind::UnexpectedEof
+        );
+    }
+
+    #[test]
+    fn test_get_ue_across_chain() {
+        // ue(v) for 7 split across the boundary in the zero prefix and again in the suffix
+        let left = Bits::copy_from_bit_slice(bits![0, 0]);
+        let right = Bits::copy_from_bit_slice(bits![0, 1, 0]);
+        let last = Bits::copy_from_bit_slice(bits![0, 0]);
+        let mut chain = left.chain(right).chain(last);
+        assert_eq!(chain.get_ue().unwrap(), 7);
+    }
+
+    #[test]
+    fn test_get_unary() {
+        let mut bits = Bits::copy_from_bit_slice(bits![0, 0, 0, 1, 1, 1, 0, 1]);
+        assert_eq!(bits.get_unary(true).unwrap(), 3);
+        assert_eq!(bits.get_unary(false).unwrap(), 2);
+        assert_eq!(bits.get_unary(true).unwrap(), 0);
+        assert!(bits.get_unary(true).is_err());
+
+        // A long run that spans chunks
+        let left = Bits::from(BitVec::repeat(false, 100));
+        let right = Bits::copy_from_bit_slice(bits![0, 0, 1]);
+        let mut chain = left.chain(right);
+        assert_eq!(chain.get_unary(true).unwrap(), 102);
+    }
+
+    #[test]
+    fn test_get_rice() {
+        // k = 2: 5 is quotient 1 (01) and remainder 1 (01)
+        let mut bits = Bits::copy_from_bit_slice(bits![0, 1, 0, 1, 1, 1, 1]);
+        assert_eq!(bits.get_rice(2).unwrap(), 5);
+        assert_eq!(bits.get_rice(2).unwrap(), 3);
+
+        // k = 0 is plain unary
+        let mut bits = Bits::copy_from_bit_slice(bits![0, 0, 0, 0, 0, 1, 1]);
+        assert_eq!(bits.get_rice(0).unwrap(), 5);
+        assert_eq!(bits.get_rice(0).unwrap(), 0);
+
+        let mut bits = Bits::copy_from_bit_slice(bits![1]);
+        assert_eq!(
+            bits.get_rice(64).unwrap_err().kind(),
+            std::io::ErrorKind::InvalidInput
+        );
+        // A quotient of 2 can't be shifted up by 63 bits
+        let mut bits = Bits::copy_from_bit_slice(bits![0, 0, 1]);
+        assert_eq!(
+            bits.get_rice(63).unwrap_err().kind(),
+            std::io::ErrorKind::InvalidData
+        );
+    }
+
+    #[test]
+    fn test_get_golomb() {
+        // m = 5: remainders 0..=2 use 2 bits, 3 and 4 use 3 bits (110, 111)
+        let mut bits = Bits::copy_from_bit_slice(bits![1, 1, 0, 0, 1, 1, 1, 1, 1, 0, 1]);
+        assert_eq!(bits.get_golomb(5).unwrap(), 2);
+        assert_eq!(bits.get_golomb(5).unwrap(), 9);
+        assert_eq!(bits.get_golomb(5).unwrap(), 1);
+
+        // m = 1 has no remainder bits
+        let mut bits = Bits::copy_from_bit_slice(bits![0, 0, 0, 1]);
+        assert_eq!(bits.get_golomb(1).unwrap(), 3);
+    }
+
+    fn check_get_many<const N: usize>(data: &Bits) {
+        for offset in 0..8 {
+            let count = (data.len_bits() - offset) / N - 1;
+            let mut bits = data.slice_bits(offset..data.len_bits());
+            let mut expected_bits = bits.clone();
+            let mut values = vec![0; count];
+            bits.get_many::<BigEndian, N>(&mut values).unwrap();
+            for (i, &value) in values.iter().enumerate() {
+                let expected: u32 = expected_bits.get_uN::<BigEndian, N, u32, u32>().unwrap();
+                assert_eq!(
+                    value, expected,
+                    "BigEndian u{} {} at offset {}",
+                    N, i, offset
+                );
+            }
+
+            let mut bits = data.slice_bits(offset..data.len_bits());
+            let mut expected_bits = bits.clone();
+            bits.get_many::<LittleEndian, N>(&mut values).unwrap();
+            for (i, &value) in values.iter().enumerate() {
+                let expected: u32 = expected_bits.get_uN::<LittleEndian, N, u32, u32>().unwrap();
+                assert_eq!(
+                    value, expected,
+                    "LittleEndian u{} {} at offset {}",
+                    N, i, offset
+                );
+            }
+            assert_eq!(bits.remaining_bits(), expected_bits.remaining_bits());
+        }
+    }
+
+    macro_rules! check_get_many {
+        ($data:expr; $($n:literal)*) => {
+            $(check_get_many::<$n>($data);)*
+        };
+    }
+
+    #[test]
+    fn test_get_many() {
+        let data: Vec<u8> = (0..600u32)
+            .map(|i| (i.wrapping_mul(0x9e37_79b9) >> 11) as u8)
+            .collect();
+        let data = Bits::copy_from_bytes(&data);
+        check_get_many!(&data; 1 2 3 4 5 6 7 8 9 10 11 12 13 14 15 16 17 18 19 20 21 22 23 24 25 26
+            27 28 29 30 31 32);
+
+        let mut bits = Bits::from_static_bytes(&[0xab, 0xcd]);
+        let mut values = [0; 3];
+        assert!(bits.get_many::<BigEndian, 6>(&mut values).is_err());
+        assert_eq!(bits.remaining_bits(), 16);
+    }
+
+    #[test]
+    fn test_get_many_across_chain() {
+        let data: Vec<u8> = (0..40u8).map(|i| i.wrapping_mul(73)).collect();
+        let left = Bits::copy_from_bytes(&data[..17]).slice_bits(3..136);
+        let right = Bits::copy_from_bytes(&data[17..]);
+        let mut chain = left.clone().chain(right.clone());
+        let mut expected_chain = left.chain(right);
+        let mut values = [0; 30];
+        chain.get_many::<LittleEndian, 10>(&mut values).unwrap();
+        for value in values {
+            let expected: u32 = expected_chain
+                .get_uN::<LittleEndian, 10, u32, u32>()
+                .unwrap();
+            assert_eq!(value, expected);
+        }
+    }
 }
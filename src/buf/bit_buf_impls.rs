@@ -2,7 +2,7 @@ use bitvec::view::BitView;
 
 use crate::prelude::*;
 
-impl<T: BitBuf + ?Sized> BitBuf for &mut T {
+impl<Order: BitOrder, T: BitBuf<Order> + ?Sized> BitBuf<Order> for &mut T {
     fn advance_bits(&mut self, count: usize) {
         (**self).advance_bits(count);
     }
@@ -11,7 +11,7 @@ impl<T: BitBuf + ?Sized> BitBuf for &mut T {
         (**self).remaining_bits()
     }
 
-    fn chunk_bits(&self) -> &BitSlice {
+    fn chunk_bits(&self) -> &bitvec::slice::BitSlice<u8, Order> {
         (**self).chunk_bits()
     }
 
@@ -113,7 +113,7 @@ impl BitBuf for &[u8] {
 // TODO: I think we're gonna get bit by not supporting BitSlice<O> here, but come back to that
 // later--hopefully we don't need a generic on the trait
 // impl BitBuf for &BitSlice {
-impl BitBuf for &BitSlice {
+impl<Order: BitOrder> BitBuf<Order> for &bitvec::slice::BitSlice<u8, Order> {
     fn advance_bits(&mut self, count: usize) {
         if self.len() < count {
             panic!("Can't advance past end of BitSlice");
@@ -125,7 +125,7 @@ impl BitBuf for &BitSlice {
         self.len()
     }
 
-    fn chunk_bits(&self) -> &BitSlice {
+    fn chunk_bits(&self) -> &bitvec::slice::BitSlice<u8, Order> {
         self
     }
 
@@ -190,7 +190,7 @@ impl BitBuf for BitCursor<&[u8]> {
     }
 }
 
-impl BitBuf for BitCursor<&BitSlice> {
+impl<Order: BitOrder> BitBuf<Order> for BitCursor<&bitvec::slice::BitSlice<u8, Order>> {
     fn advance_bits(&mut self, count: usize) {
         let len = self.get_ref().len();
         let pos = self.position();
@@ -208,7 +208,7 @@ impl BitBuf for BitCursor<&BitSlice> {
             .saturating_sub(self.position() as usize)
     }
 
-    fn chunk_bits(&self) -> &BitSlice {
+    fn chunk_bits(&self) -> &bitvec::slice::BitSlice<u8, Order> {
         let slice = self.get_ref();
         let start = slice.len().min(self.position() as usize);
         &slice[start..]
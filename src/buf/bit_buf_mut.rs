@@ -4,7 +4,9 @@ use crate::prelude::*;
 
 use super::{chain::Chain, limit::Limit};
 
-pub trait BitBufMut {
+/// The write side of a bit buffer.  As with [`BitBuf`], `Order` is the order of the bits within
+/// each byte.
+pub trait BitBufMut<Order: BitOrder = Msb0> {
     /// Advance the internal cursor of the BitBufMut by `count` bits.
     ///
     /// The next call to chunk_mut will return a slice starting `count` bits further into the
@@ -26,7 +28,7 @@ pub trait BitBufMut {
     /// This is a lower level function. Most operations are done with other functions.
     ///
     /// The returned byte slice may represent uninitialized memory and should not be read from.
-    fn chunk_mut_bits(&mut self) -> &mut BitSlice;
+    fn chunk_mut_bits(&mut self) -> &mut bitvec::slice::BitSlice<u8, Order>;
 
     /// Returns a mutable `UninitSlice` starting at the current `BitBufMut` position and of length
     /// between 0 and BitBufMut::remaining_mut(). Note that this can be shorter than the whole
@@ -98,7 +100,7 @@ pub trait BitBufMut {
     ///
     /// The returned `BitBufMut` instance will first write to all bytes from `self`. Afterwards it
     /// will write to `next`.
-    fn chain_mut<U: BitBufMut>(self, next: U) -> Chain<Self, U>
+    fn chain_mut<U: BitBufMut<Order>>(self, next: U) -> Chain<Self, U>
     where
         Self: Sized,
     {
@@ -108,7 +110,7 @@ pub trait BitBufMut {
     /// Transfer bits into `self` from `src` and advance the cursor by the number of bits written.
     ///
     /// `self` must have enough remaining capacity to contain all of `src`.
-    fn put_bit_slice(&mut self, src: &BitSlice) {
+    fn put_bit_slice(&mut self, src: &bitvec::slice::BitSlice<u8, Order>) {
         self.try_put_bit_slice(src).unwrap()
     }
 
@@ -116,7 +118,10 @@ pub trait BitBufMut {
     /// written.
     ///
     /// Returns an error if `self` doesn't have enough remaining capacity to contain all of `src`.
-    fn try_put_bit_slice(&mut self, mut src: &BitSlice) -> std::io::Result<()> {
+    fn try_put_bit_slice(
+        &mut self,
+        mut src: &bitvec::slice::BitSlice<u8, Order>,
+    ) -> std::io::Result<()> {
         if self.remaining_mut_bits() < src.len() {
             return Err(std::io::Error::new(
                 std::io::ErrorKind::UnexpectedEof,
@@ -1,8 +1,10 @@
-use funty::Integral;
+use bitvec::{order::Msb0, view::BitView};
+use funty::{Integral, Signed};
 
+use super::bulk::{self, BULK_BLOCK_BYTES, BULK_BLOCK_LEN};
 use crate::prelude::*;
 
-pub trait BitBufMutExts: BitBufMut {
+pub trait BitBufMutExts<Order: BitOrder = Msb0>: BitBufMut<Order> {
     #[allow(non_snake_case)]
     fn put_uN<O: ByteOrder, const N: usize, U, V: Integral>(
         &mut self,
@@ -23,44 +25,172 @@ pub trait BitBufMutExts: BitBufMut {
             self.advance_mut_bits(N);
             Ok(())
         } else {
-            let mut bits = BitVec::repeat(false, N);
-            let value_slice = bits.as_mut_bitslice();
+            // A little-endian value depends on where it starts within a byte, so the intermediary
+            // starts at the same bit offset as the value will
+            let offset = slice.as_bitptr().bit().into_inner() as usize;
+            let mut bits = bitvec::vec::BitVec::<u8, Order>::repeat(false, offset + N);
+            let value_slice = &mut bits[offset..];
             O::store(value_slice, value_integral);
             self.try_put_bit_slice(value_slice)?;
             Ok(())
         }
     }
 
+    /// Writes `value` as an `N`-bit two's-complement value.  `V` is the signed primitive type
+    /// the value is converted to before it's written (e.g. `i16` for an `i12`).
+    ///
+    /// As with [`put_uN`](Self::put_uN), `value` is expected to fit in `N` bits, which the
+    /// `iN` types guarantee; only its low `N` bits are written.
+    #[allow(non_snake_case)]
+    fn put_iN<O: ByteOrder, const N: usize, I, V: Signed>(
+        &mut self,
+        value: I,
+    ) -> std::io::Result<()>
+    where
+        I: Into<V>,
+    {
+        // Storing a signed value only writes the low N bits, which is its two's-complement form.
+        self.put_uN::<O, N, I, V>(value)
+    }
+
+    /// Writes the low `n` bits of `value`, where `n` is only known at runtime.
+    ///
+    /// Returns an error if `n` is larger than the width of `T` or if `value` doesn't fit in `n`
+    /// bits (for signed types, as an `n`-bit two's-complement value).
+    fn put_bits_from<T: Integral, O: ByteOrder>(
+        &mut self,
+        n: usize,
+        value: T,
+    ) -> std::io::Result<()> {
+        if n > T::BITS as usize {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!("Can't write {} bits from a {}-bit type", n, T::BITS),
+            ));
+        }
+        // Shifting the value up and back down sign- or zero-extends it from bit n - 1, so this
+        // only gives back the same value if it fit in n bits.
+        let shift = T::BITS - n as u32;
+        let fits = if n == 0 {
+            value == T::ZERO
+        } else {
+            (value << shift) >> shift == value
+        };
+        if !fits {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!("Value ({}) does not fit in {} bits", value, n),
+            ));
+        }
+        if n == 0 {
+            return Ok(());
+        }
+        let slice = self.chunk_mut_bits();
+        if slice.len() >= n {
+            O::store(&mut slice[..n], value);
+            self.advance_mut_bits(n);
+            Ok(())
+        } else {
+            let offset = slice.as_bitptr().bit().into_inner() as usize;
+            let mut bits = bitvec::vec::BitVec::<u8, Order>::repeat(false, offset + n);
+            O::store(&mut bits[offset..], value);
+            self.try_put_bit_slice(&bits[offset..])
+        }
+    }
+
+    /// Writes the low `N` (1 to 32) bits of each of `src`, giving the same results as calling
+    /// `put_uN` for each one.  Values are packed a block at a time with word-level shifts rather
+    /// than with a bit-slice store per value.
+    ///
+    /// Returns an error, without writing anything, if any value doesn't fit in `N` bits or there
+    /// isn't enough room for all of them.  An `N` outside of 1 to 32 doesn't compile.
+    fn put_many<O: ByteOrder, const N: usize>(&mut self, src: &[u32]) -> std::io::Result<()> {
+        const {
+            assert!(
+                N >= 1 && N <= 32,
+                "Can't write values from a u32 with N outside 1..=32"
+            )
+        }
+        if let Some(value) = src.iter().find(|&&value| N < 32 && value >> N != 0) {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!("Value ({}) does not fit in {} bits", value, N),
+            ));
+        }
+        if self.remaining_mut_bits() < src.len() * N {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::UnexpectedEof,
+                format!(
+                    "Remaining bits ({}) are less than the size of the source ({})",
+                    self.remaining_mut_bits(),
+                    src.len() * N
+                ),
+            ));
+        }
+        let mut raw = [0u32; BULK_BLOCK_LEN];
+        let mut bytes = [0u8; BULK_BLOCK_BYTES];
+        let mut src = src;
+        while !src.is_empty() {
+            let slice = self.chunk_mut_bits();
+            // Pack as many whole values as fit in the current chunk.  A value which spans chunks
+            // is written the slow way, as put_uN would.
+            let count = (slice.len() / N).min(src.len()).min(BULK_BLOCK_LEN);
+            if count == 0 {
+                self.put_uN::<O, N, u32, u32>(src[0])?;
+                src = &src[1..];
+                continue;
+            }
+            let (block, rest) = src.split_at(count);
+            let len = count * N;
+            // The byte order depends on where each value falls within its bytes
+            let offset = slice.as_bitptr().bit().into_inner() as usize;
+            for (i, (raw, &value)) in raw.iter_mut().zip(block).enumerate() {
+                *raw = bulk::to_raw_bits::<Order, O>(value as u64, N, (offset + i * N) % 8) as u32;
+            }
+            bulk::pack::<Order>(&raw[..count], N, &mut bytes);
+            slice[..len].copy_from_bitslice(&bytes.view_bits::<Order>()[..len]);
+            self.advance_mut_bits(len);
+            src = rest;
+        }
+        Ok(())
+    }
+
+    /// Writes any [`BitEncode`] type.  Types narrower than a byte ignore `O` and are always
+    /// written in `Order`'s natural field order.
+    fn put<O: ByteOrder, T: BitEncode>(&mut self, value: T) -> std::io::Result<()> {
+        value.bit_encode::<O, Order, Self>(self)
+    }
+
     fn put_bool(&mut self, value: bool) -> std::io::Result<()> {
         self.put_u1(u1::new(value as u8))
     }
 
     fn put_u1(&mut self, value: u1) -> std::io::Result<()> {
-        self.put_uN::<BigEndian, 1, u1, u8>(value)
+        self.put_uN::<Order::FieldOrder, 1, u1, u8>(value)
     }
 
     fn put_u2(&mut self, value: u2) -> std::io::Result<()> {
-        self.put_uN::<BigEndian, 2, u2, u8>(value)
+        self.put_uN::<Order::FieldOrder, 2, u2, u8>(value)
     }
 
     fn put_u3(&mut self, value: u3) -> std::io::Result<()> {
-        self.put_uN::<BigEndian, 3, u3, u8>(value)
+        self.put_uN::<Order::FieldOrder, 3, u3, u8>(value)
     }
 
     fn put_u4(&mut self, value: u4) -> std::io::Result<()> {
-        self.put_uN::<BigEndian, 4, u4, u8>(value)
+        self.put_uN::<Order::FieldOrder, 4, u4, u8>(value)
     }
 
     fn put_u5(&mut self, value: u5) -> std::io::Result<()> {
-        self.put_uN::<BigEndian, 5, u5, u8>(value)
+        self.put_uN::<Order::FieldOrder, 5, u5, u8>(value)
     }
 
     fn put_u6(&mut self, value: u6) -> std::io::Result<()> {
-        self.put_uN::<BigEndian, 6, u6, u8>(value)
+        self.put_uN::<Order::FieldOrder, 6, u6, u8>(value)
     }
 
     fn put_u7(&mut self, value: u7) -> std::io::Result<()> {
-        self.put_uN::<BigEndian, 7, u7, u8>(value)
+        self.put_uN::<Order::FieldOrder, 7, u7, u8>(value)
     }
 
     fn put_u8(&mut self, value: u8) -> std::io::Result<()> {
@@ -78,7 +208,7 @@ pub trait BitBufMutExts: BitBufMut {
             self.advance_mut_bytes(1);
             return Ok(());
         }
-        self.put_uN::<BigEndian, 8, u8, u8>(value)
+        self.put_uN::<Order::FieldOrder, 8, u8, u8>(value)
     }
 
     fn put_u9<O: ByteOrder>(&mut self, value: u9) -> std::io::Result<()> {
@@ -196,9 +326,531 @@ pub trait BitBufMutExts: BitBufMut {
         }
         self.put_uN::<O, 32, u32, u32>(value)
     }
+    fn put_u33<O: ByteOrder>(&mut self, value: u33) -> std::io::Result<()> {
+        self.put_uN::<O, 33, u33, u64>(value)
+    }
+    fn put_u34<O: ByteOrder>(&mut self, value: u34) -> std::io::Result<()> {
+        self.put_uN::<O, 34, u34, u64>(value)
+    }
+    fn put_u35<O: ByteOrder>(&mut self, value: u35) -> std::io::Result<()> {
+        self.put_uN::<O, 35, u35, u64>(value)
+    }
+    fn put_u36<O: ByteOrder>(&mut self, value: u36) -> std::io::Result<()> {
+        self.put_uN::<O, 36, u36, u64>(value)
+    }
+    fn put_u37<O: ByteOrder>(&mut self, value: u37) -> std::io::Result<()> {
+        self.put_uN::<O, 37, u37, u64>(value)
+    }
+    fn put_u38<O: ByteOrder>(&mut self, value: u38) -> std::io::Result<()> {
+        self.put_uN::<O, 38, u38, u64>(value)
+    }
+    fn put_u39<O: ByteOrder>(&mut self, value: u39) -> std::io::Result<()> {
+        self.put_uN::<O, 39, u39, u64>(value)
+    }
+    fn put_u40<O: ByteOrder>(&mut self, value: u40) -> std::io::Result<()> {
+        if self.byte_aligned_mut() {
+            if self.remaining_mut_bytes() < 5 {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::UnexpectedEof,
+                    format!(
+                        "Remaining bytes ({}) are less than the size of the source (5)",
+                        self.remaining_mut_bytes(),
+                    ),
+                ));
+            }
+            let mut buf = [0u8; 5];
+            O::store_u40(&mut buf, value);
+            return self.try_put_slice_bytes(&buf);
+        }
+        self.put_uN::<O, 40, u40, u64>(value)
+    }
+    fn put_u41<O: ByteOrder>(&mut self, value: u41) -> std::io::Result<()> {
+        self.put_uN::<O, 41, u41, u64>(value)
+    }
+    fn put_u42<O: ByteOrder>(&mut self, value: u42) -> std::io::Result<()> {
+        self.put_uN::<O, 42, u42, u64>(value)
+    }
+    fn put_u43<O: ByteOrder>(&mut self, value: u43) -> std::io::Result<()> {
+        self.put_uN::<O, 43, u43, u64>(value)
+    }
+    fn put_u44<O: ByteOrder>(&mut self, value: u44) -> std::io::Result<()> {
+        self.put_uN::<O, 44, u44, u64>(value)
+    }
+    fn put_u45<O: ByteOrder>(&mut self, value: u45) -> std::io::Result<()> {
+        self.put_uN::<O, 45, u45, u64>(value)
+    }
+    fn put_u46<O: ByteOrder>(&mut self, value: u46) -> std::io::Result<()> {
+        self.put_uN::<O, 46, u46, u64>(value)
+    }
+    fn put_u47<O: ByteOrder>(&mut self, value: u47) -> std::io::Result<()> {
+        self.put_uN::<O, 47, u47, u64>(value)
+    }
+    fn put_u48<O: ByteOrder>(&mut self, value: u48) -> std::io::Result<()> {
+        if self.byte_aligned_mut() {
+            if self.remaining_mut_bytes() < 6 {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::UnexpectedEof,
+                    format!(
+                        "Remaining bytes ({}) are less than the size of the source (6)",
+                        self.remaining_mut_bytes(),
+                    ),
+                ));
+            }
+            let mut buf = [0u8; 6];
+            O::store_u48(&mut buf, value);
+            return self.try_put_slice_bytes(&buf);
+        }
+        self.put_uN::<O, 48, u48, u64>(value)
+    }
+    fn put_u49<O: ByteOrder>(&mut self, value: u49) -> std::io::Result<()> {
+        self.put_uN::<O, 49, u49, u64>(value)
+    }
+    fn put_u50<O: ByteOrder>(&mut self, value: u50) -> std::io::Result<()> {
+        self.put_uN::<O, 50, u50, u64>(value)
+    }
+    fn put_u51<O: ByteOrder>(&mut self, value: u51) -> std::io::Result<()> {
+        self.put_uN::<O, 51, u51, u64>(value)
+    }
+    fn put_u52<O: ByteOrder>(&mut self, value: u52) -> std::io::Result<()> {
+        self.put_uN::<O, 52, u52, u64>(value)
+    }
+    fn put_u53<O: ByteOrder>(&mut self, value: u53) -> std::io::Result<()> {
+        self.put_uN::<O, 53, u53, u64>(value)
+    }
+    fn put_u54<O: ByteOrder>(&mut self, value: u54) -> std::io::Result<()> {
+        self.put_uN::<O, 54, u54, u64>(value)
+    }
+    fn put_u55<O: ByteOrder>(&mut self, value: u55) -> std::io::Result<()> {
+        self.put_uN::<O, 55, u55, u64>(value)
+    }
+    fn put_u56<O: ByteOrder>(&mut self, value: u56) -> std::io::Result<()> {
+        if self.byte_aligned_mut() {
+            if self.remaining_mut_bytes() < 7 {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::UnexpectedEof,
+                    format!(
+                        "Remaining bytes ({}) are less than the size of the source (7)",
+                        self.remaining_mut_bytes(),
+                    ),
+                ));
+            }
+            let mut buf = [0u8; 7];
+            O::store_u56(&mut buf, value);
+            return self.try_put_slice_bytes(&buf);
+        }
+        self.put_uN::<O, 56, u56, u64>(value)
+    }
+    fn put_u57<O: ByteOrder>(&mut self, value: u57) -> std::io::Result<()> {
+        self.put_uN::<O, 57, u57, u64>(value)
+    }
+    fn put_u58<O: ByteOrder>(&mut self, value: u58) -> std::io::Result<()> {
+        self.put_uN::<O, 58, u58, u64>(value)
+    }
+    fn put_u59<O: ByteOrder>(&mut self, value: u59) -> std::io::Result<()> {
+        self.put_uN::<O, 59, u59, u64>(value)
+    }
+    fn put_u60<O: ByteOrder>(&mut self, value: u60) -> std::io::Result<()> {
+        self.put_uN::<O, 60, u60, u64>(value)
+    }
+    fn put_u61<O: ByteOrder>(&mut self, value: u61) -> std::io::Result<()> {
+        self.put_uN::<O, 61, u61, u64>(value)
+    }
+    fn put_u62<O: ByteOrder>(&mut self, value: u62) -> std::io::Result<()> {
+        self.put_uN::<O, 62, u62, u64>(value)
+    }
+    fn put_u63<O: ByteOrder>(&mut self, value: u63) -> std::io::Result<()> {
+        self.put_uN::<O, 63, u63, u64>(value)
+    }
+    fn put_u64<O: ByteOrder>(&mut self, value: u64) -> std::io::Result<()> {
+        if self.byte_aligned_mut() {
+            if self.remaining_mut_bytes() < 8 {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::UnexpectedEof,
+                    format!(
+                        "Remaining bytes ({}) are less than the size of the source (8)",
+                        self.remaining_mut_bytes(),
+                    ),
+                ));
+            }
+            let mut buf = [0u8; 8];
+            O::store_u64(&mut buf, value);
+            return self.try_put_slice_bytes(&buf);
+        }
+        self.put_uN::<O, 64, u64, u64>(value)
+    }
+    fn put_u128<O: ByteOrder>(&mut self, value: u128) -> std::io::Result<()> {
+        if self.byte_aligned_mut() {
+            if self.remaining_mut_bytes() < 16 {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::UnexpectedEof,
+                    format!(
+                        "Remaining bytes ({}) are less than the size of the source (16)",
+                        self.remaining_mut_bytes(),
+                    ),
+                ));
+            }
+            let mut buf = [0u8; 16];
+            O::store_u128(&mut buf, value);
+            return self.try_put_slice_bytes(&buf);
+        }
+        self.put_uN::<O, 128, u128, u128>(value)
+    }
+
+    fn put_i1(&mut self, value: i1) -> std::io::Result<()> {
+        self.put_bool(value == i1::new(-1))
+    }
+
+    fn put_i2(&mut self, value: i2) -> std::io::Result<()> {
+        self.put_iN::<Order::FieldOrder, 2, i2, i8>(value)
+    }
+
+    fn put_i3(&mut self, value: i3) -> std::io::Result<()> {
+        self.put_iN::<Order::FieldOrder, 3, i3, i8>(value)
+    }
+
+    fn put_i4(&mut self, value: i4) -> std::io::Result<()> {
+        self.put_iN::<Order::FieldOrder, 4, i4, i8>(value)
+    }
+
+    fn put_i5(&mut self, value: i5) -> std::io::Result<()> {
+        self.put_iN::<Order::FieldOrder, 5, i5, i8>(value)
+    }
+
+    fn put_i6(&mut self, value: i6) -> std::io::Result<()> {
+        self.put_iN::<Order::FieldOrder, 6, i6, i8>(value)
+    }
+
+    fn put_i7(&mut self, value: i7) -> std::io::Result<()> {
+        self.put_iN::<Order::FieldOrder, 7, i7, i8>(value)
+    }
+
+    fn put_i8(&mut self, value: i8) -> std::io::Result<()> {
+        self.put_u8(value as u8)
+    }
+
+    fn put_i9<O: ByteOrder>(&mut self, value: i9) -> std::io::Result<()> {
+        self.put_iN::<O, 9, i9, i16>(value)
+    }
+    fn put_i10<O: ByteOrder>(&mut self, value: i10) -> std::io::Result<()> {
+        self.put_iN::<O, 10, i10, i16>(value)
+    }
+    fn put_i11<O: ByteOrder>(&mut self, value: i11) -> std::io::Result<()> {
+        self.put_iN::<O, 11, i11, i16>(value)
+    }
+    fn put_i12<O: ByteOrder>(&mut self, value: i12) -> std::io::Result<()> {
+        self.put_iN::<O, 12, i12, i16>(value)
+    }
+    fn put_i13<O: ByteOrder>(&mut self, value: i13) -> std::io::Result<()> {
+        self.put_iN::<O, 13, i13, i16>(value)
+    }
+    fn put_i14<O: ByteOrder>(&mut self, value: i14) -> std::io::Result<()> {
+        self.put_iN::<O, 14, i14, i16>(value)
+    }
+    fn put_i15<O: ByteOrder>(&mut self, value: i15) -> std::io::Result<()> {
+        self.put_iN::<O, 15, i15, i16>(value)
+    }
+    fn put_i16<O: ByteOrder>(&mut self, value: i16) -> std::io::Result<()> {
+        self.put_u16::<O>(value as u16)
+    }
+    fn put_i17<O: ByteOrder>(&mut self, value: i17) -> std::io::Result<()> {
+        self.put_iN::<O, 17, i17, i32>(value)
+    }
+    fn put_i18<O: ByteOrder>(&mut self, value: i18) -> std::io::Result<()> {
+        self.put_iN::<O, 18, i18, i32>(value)
+    }
+    fn put_i19<O: ByteOrder>(&mut self, value: i19) -> std::io::Result<()> {
+        self.put_iN::<O, 19, i19, i32>(value)
+    }
+    fn put_i20<O: ByteOrder>(&mut self, value: i20) -> std::io::Result<()> {
+        self.put_iN::<O, 20, i20, i32>(value)
+    }
+    fn put_i21<O: ByteOrder>(&mut self, value: i21) -> std::io::Result<()> {
+        self.put_iN::<O, 21, i21, i32>(value)
+    }
+    fn put_i22<O: ByteOrder>(&mut self, value: i22) -> std::io::Result<()> {
+        self.put_iN::<O, 22, i22, i32>(value)
+    }
+    fn put_i23<O: ByteOrder>(&mut self, value: i23) -> std::io::Result<()> {
+        self.put_iN::<O, 23, i23, i32>(value)
+    }
+    fn put_i24<O: ByteOrder>(&mut self, value: i24) -> std::io::Result<()> {
+        // Go through put_u{n} for its byte-aligned fast path with the two's-complement bits
+        let value: i32 = value.into();
+        self.put_u24::<O>(u24::new(value as u32 & (u32::MAX >> 8)))
+    }
+    fn put_i25<O: ByteOrder>(&mut self, value: i25) -> std::io::Result<()> {
+        self.put_iN::<O, 25, i25, i32>(value)
+    }
+    fn put_i26<O: ByteOrder>(&mut self, value: i26) -> std::io::Result<()> {
+        self.put_iN::<O, 26, i26, i32>(value)
+    }
+    fn put_i27<O: ByteOrder>(&mut self, value: i27) -> std::io::Result<()> {
+        self.put_iN::<O, 27, i27, i32>(value)
+    }
+    fn put_i28<O: ByteOrder>(&mut self, value: i28) -> std::io::Result<()> {
+        self.put_iN::<O, 28, i28, i32>(value)
+    }
+    fn put_i29<O: ByteOrder>(&mut self, value: i29) -> std::io::Result<()> {
+        self.put_iN::<O, 29, i29, i32>(value)
+    }
+    fn put_i30<O: ByteOrder>(&mut self, value: i30) -> std::io::Result<()> {
+        self.put_iN::<O, 30, i30, i32>(value)
+    }
+    fn put_i31<O: ByteOrder>(&mut self, value: i31) -> std::io::Result<()> {
+        self.put_iN::<O, 31, i31, i32>(value)
+    }
+    fn put_i32<O: ByteOrder>(&mut self, value: i32) -> std::io::Result<()> {
+        self.put_u32::<O>(value as u32)
+    }
+    fn put_i33<O: ByteOrder>(&mut self, value: i33) -> std::io::Result<()> {
+        self.put_iN::<O, 33, i33, i64>(value)
+    }
+    fn put_i34<O: ByteOrder>(&mut self, value: i34) -> std::io::Result<()> {
+        self.put_iN::<O, 34, i34, i64>(value)
+    }
+    fn put_i35<O: ByteOrder>(&mut self, value: i35) -> std::io::Result<()> {
+        self.put_iN::<O, 35, i35, i64>(value)
+    }
+    fn put_i36<O: ByteOrder>(&mut self, value: i36) -> std::io::Result<()> {
+        self.put_iN::<O, 36, i36, i64>(value)
+    }
+    fn put_i37<O: ByteOrder>(&mut self, value: i37) -> std::io::Result<()> {
+        self.put_iN::<O, 37, i37, i64>(value)
+    }
+    fn put_i38<O: ByteOrder>(&mut self, value: i38) -> std::io::Result<()> {
+        self.put_iN::<O, 38, i38, i64>(value)
+    }
+    fn put_i39<O: ByteOrder>(&mut self, value: i39) -> std::io::Result<()> {
+        self.put_iN::<O, 39, i39, i64>(value)
+    }
+    fn put_i40<O: ByteOrder>(&mut self, value: i40) -> std::io::Result<()> {
+        let value: i64 = value.into();
+        self.put_u40::<O>(u40::new(value as u64 & (u64::MAX >> 24)))
+    }
+    fn put_i41<O: ByteOrder>(&mut self, value: i41) -> std::io::Result<()> {
+        self.put_iN::<O, 41, i41, i64>(value)
+    }
+    fn put_i42<O: ByteOrder>(&mut self, value: i42) -> std::io::Result<()> {
+        self.put_iN::<O, 42, i42, i64>(value)
+    }
+    fn put_i43<O: ByteOrder>(&mut self, value: i43) -> std::io::Result<()> {
+        self.put_iN::<O, 43, i43, i64>(value)
+    }
+    fn put_i44<O: ByteOrder>(&mut self, value: i44) -> std::io::Result<()> {
+        self.put_iN::<O, 44, i44, i64>(value)
+    }
+    fn put_i45<O: ByteOrder>(&mut self, value: i45) -> std::io::Result<()> {
+        self.put_iN::<O, 45, i45, i64>(value)
+    }
+    fn put_i46<O: ByteOrder>(&mut self, value: i46) -> std::io::Result<()> {
+        self.put_iN::<O, 46, i46, i64>(value)
+    }
+    fn put_i47<O: ByteOrder>(&mut self, value: i47) -> std::io::Result<()> {
+        self.put_iN::<O, 47, i47, i64>(value)
+    }
+    fn put_i48<O: ByteOrder>(&mut self, value: i48) -> std::io::Result<()> {
+        let value: i64 = value.into();
+        self.put_u48::<O>(u48::new(value as u64 & (u64::MAX >> 16)))
+    }
+    fn put_i49<O: ByteOrder>(&mut self, value: i49) -> std::io::Result<()> {
+        self.put_iN::<O, 49, i49, i64>(value)
+    }
+    fn put_i50<O: ByteOrder>(&mut self, value: i50) -> std::io::Result<()> {
+        self.put_iN::<O, 50, i50, i64>(value)
+    }
+    fn put_i51<O: ByteOrder>(&mut self, value: i51) -> std::io::Result<()> {
+        self.put_iN::<O, 51, i51, i64>(value)
+    }
+    fn put_i52<O: ByteOrder>(&mut self, value: i52) -> std::io::Result<()> {
+        self.put_iN::<O, 52, i52, i64>(value)
+    }
+    fn put_i53<O: ByteOrder>(&mut self, value: i53) -> std::io::Result<()> {
+        self.put_iN::<O, 53, i53, i64>(value)
+    }
+    fn put_i54<O: ByteOrder>(&mut self, value: i54) -> std::io::Result<()> {
+        self.put_iN::<O, 54, i54, i64>(value)
+    }
+    fn put_i55<O: ByteOrder>(&mut self, value: i55) -> std::io::Result<()> {
+        self.put_iN::<O, 55, i55, i64>(value)
+    }
+    fn put_i56<O: ByteOrder>(&mut self, value: i56) -> std::io::Result<()> {
+        let value: i64 = value.into();
+        self.put_u56::<O>(u56::new(value as u64 & (u64::MAX >> 8)))
+    }
+    fn put_i57<O: ByteOrder>(&mut self, value: i57) -> std::io::Result<()> {
+        self.put_iN::<O, 57, i57, i64>(value)
+    }
+    fn put_i58<O: ByteOrder>(&mut self, value: i58) -> std::io::Result<()> {
+        self.put_iN::<O, 58, i58, i64>(value)
+    }
+    fn put_i59<O: ByteOrder>(&mut self, value: i59) -> std::io::Result<()> {
+        self.put_iN::<O, 59, i59, i64>(value)
+    }
+    fn put_i60<O: ByteOrder>(&mut self, value: i60) -> std::io::Result<()> {
+        self.put_iN::<O, 60, i60, i64>(value)
+    }
+    fn put_i61<O: ByteOrder>(&mut self, value: i61) -> std::io::Result<()> {
+        self.put_iN::<O, 61, i61, i64>(value)
+    }
+    fn put_i62<O: ByteOrder>(&mut self, value: i62) -> std::io::Result<()> {
+        self.put_iN::<O, 62, i62, i64>(value)
+    }
+    fn put_i63<O: ByteOrder>(&mut self, value: i63) -> std::io::Result<()> {
+        self.put_iN::<O, 63, i63, i64>(value)
+    }
+
+    fn put_i64<O: ByteOrder>(&mut self, value: i64) -> std::io::Result<()> {
+        self.put_u64::<O>(value as u64)
+    }
+
+    fn put_i128<O: ByteOrder>(&mut self, value: i128) -> std::io::Result<()> {
+        self.put_u128::<O>(value as u128)
+    }
+
+    /// Writes `value` as a k-th order Exp-Golomb code.
+    ///
+    /// Returns an error if `k` is larger than 32 or if the code for `value` would need more than
+    /// 32 leading zeros.
+    fn put_exp_golomb(&mut self, k: u32, value: u64) -> std::io::Result<()> {
+        if k > 32 {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!("Exp-Golomb order ({}) must be at most 32", k),
+            ));
+        }
+        // The code is the binary form of value + 2^k, preceded by enough zeros that the number of
+        // zeros plus k is one less than that binary form's length
+        let code = (value as u128) + (1u128 << k);
+        let code_len = 128 - code.leading_zeros() as usize;
+        let leading_zeros = code_len - 1 - k as usize;
+        if leading_zeros > 32 {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!(
+                    "Value ({}) is too large for an order {} Exp-Golomb code",
+                    value, k
+                ),
+            ));
+        }
+        self.put_bits_from::<u64, Order::FieldOrder>(leading_zeros, 0)?;
+        // The code's top bit is the one which ends the zeros, whichever order fields are in
+        self.put_bool(true)?;
+        self.put_bits_from::<u128, Order::FieldOrder>(code_len - 1, code - (1 << (code_len - 1)))
+    }
+
+    /// Writes `value` as an unsigned Exp-Golomb code, the `ue(v)` descriptor in H.264/HEVC/VVC.
+    fn put_ue(&mut self, value: u64) -> std::io::Result<()> {
+        self.put_exp_golomb(0, value)
+    }
+
+    /// Writes `value` as a signed Exp-Golomb code, the `se(v)` descriptor in H.264/HEVC/VVC.
+    fn put_se(&mut self, value: i64) -> std::io::Result<()> {
+        // Positive values map to odd code numbers and the rest to even ones, so i64::MIN's code
+        // number is 2^64, which doesn't fit
+        let code_num = value
+            .unsigned_abs()
+            .checked_mul(2)
+            .map(|doubled| if value > 0 { doubled - 1 } else { doubled })
+            .ok_or_else(|| {
+                std::io::Error::new(
+                    std::io::ErrorKind::InvalidInput,
+                    format!(
+                        "Value ({}) is too large for a signed Exp-Golomb code",
+                        value
+                    ),
+                )
+            })?;
+        self.put_ue(code_num)
+    }
+
+    /// Writes `value` as a unary code: `value` bits opposite to `terminator`, followed by the
+    /// `terminator` bit.
+    fn put_unary(&mut self, value: u64, terminator: bool) -> std::io::Result<()> {
+        let run_word = if terminator { 0 } else { u64::MAX };
+        let mut remaining = value;
+        while remaining > 0 {
+            let count = remaining.min(64);
+            self.put_bits_from::<u64, Order::FieldOrder>(count as usize, run_word >> (64 - count))?;
+            remaining -= count;
+        }
+        self.put_bool(terminator)
+    }
+
+    /// Writes `value` as a Golomb-Rice code with parameter `k`.
+    fn put_rice(&mut self, k: u32, value: u64) -> std::io::Result<()> {
+        if k >= 64 {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!("Rice parameter ({}) must be less than 64", k),
+            ));
+        }
+        self.put_unary(value >> k, true)?;
+        self.put_bits_from::<u64, Order::FieldOrder>(k as usize, value & ((1 << k) - 1))
+    }
+
+    /// Writes `value` as a Golomb code with parameter `m`.
+    fn put_golomb(&mut self, m: u64, value: u64) -> std::io::Result<()> {
+        if m == 0 {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "Golomb parameter must be non-zero",
+            ));
+        }
+        if m.is_power_of_two() {
+            return self.put_rice(m.trailing_zeros(), value);
+        }
+        self.put_unary(value / m, true)?;
+        let remainder = value % m;
+        let b = 64 - (m - 1).leading_zeros();
+        // b is 64 for m above 2^63, so the shift needs more than 64 bits
+        let cutoff = ((1u128 << b) - m as u128) as u64;
+        if remainder < cutoff {
+            self.put_bits_from::<u64, Order::FieldOrder>(b as usize - 1, remainder)
+        } else {
+            // The first b - 1 bits are what tells a reader there's another bit, so they're
+            // written as a field of their own
+            let code = remainder + cutoff;
+            self.put_bits_from::<u64, Order::FieldOrder>(b as usize - 1, code >> 1)?;
+            self.put_bool(code & 1 == 1)
+        }
+    }
+
+    fn put_f32<O: ByteOrder>(&mut self, value: f32) -> std::io::Result<()> {
+        self.put_u32::<O>(value.to_bits())
+    }
+
+    fn put_f64<O: ByteOrder>(&mut self, value: f64) -> std::io::Result<()> {
+        self.put_u64::<O>(value.to_bits())
+    }
+
+    /// Writes `value` as an IEEE-754 half precision (binary16) value, rounding to nearest even.
+    fn put_f16<O: ByteOrder>(&mut self, value: f32) -> std::io::Result<()> {
+        let raw = MinifloatFormat::F16.encode(value as f64);
+        self.put_u16::<O>(raw as u16)
+    }
+
+    /// Writes `value` as a bfloat16 value, rounding to nearest even.
+    fn put_bf16<O: ByteOrder>(&mut self, value: f32) -> std::io::Result<()> {
+        let raw = MinifloatFormat::BF16.encode(value as f64);
+        self.put_u16::<O>(raw as u16)
+    }
+
+    /// Writes `value` as a floating point value in the given [`MinifloatFormat`], rounding to
+    /// nearest even.
+    fn put_minifloat<O: ByteOrder>(
+        &mut self,
+        format: MinifloatFormat,
+        value: f64,
+    ) -> std::io::Result<()> {
+        // As in put_uN, the value is staged at the bit offset it will start at
+        let offset = self.chunk_mut_bits().as_bitptr().bit().into_inner() as usize;
+        let mut bits = bitvec::vec::BitVec::<u8, Order>::repeat(false, offset + format.bit_width());
+        O::store(&mut bits[offset..], format.encode(value));
+        self.try_put_bit_slice(&bits[offset..])
+    }
 }
 
-impl<T: BitBufMut + ?Sized> BitBufMutExts for T {}
+impl<Order: BitOrder, T: BitBufMut<Order> + ?Sized> BitBufMutExts<Order> for T {}
 
 #[cfg(test)]
 mod tests {
@@ -255,4 +907,349 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn test_put_little_endian_across_chunks() {
+        // Values which don't fit in the current chunk are staged separately, which mustn't change
+        // how a little-endian value's bits are laid out
+        for offset in 0..8 {
+            let mut grown = BitsMut::new();
+            let mut preallocated = BitsMut::with_capacity(4096);
+            for bits_mut in [&mut grown, &mut preallocated] {
+                bits_mut.put_bits_from::<u8, BigEndian>(offset, 0).unwrap();
+                for i in 0..40u16 {
+                    bits_mut.put_u13::<LittleEndian>(u13::new(i * 199)).unwrap();
+                    bits_mut
+                        .put_bits_from::<u32, LittleEndian>(21, i as u32 * 52_363)
+                        .unwrap();
+                    bits_mut.put_f16::<LittleEndian>(i as f32).unwrap();
+                }
+            }
+            assert_eq!(&grown[..], &preallocated[..]);
+        }
+    }
+
+    #[test]
+    fn test_put_wide() {
+        {
+            let mut bits_mut = BitsMut::new();
+            bits_mut.put_u1(u1::new(0)).unwrap();
+            bits_mut.put_u33::<BigEndian>(u33::MAX).unwrap();
+            let mut bits = bits_mut.freeze();
+            assert_eq!(bits.get_u1().unwrap(), u1::new(0));
+            assert_eq!(bits.get_u33::<BigEndian>().unwrap(), u33::MAX);
+        }
+        {
+            let mut bits_mut = BitsMut::new();
+            bits_mut
+                .put_u48::<NetworkOrder>(u48::new(0x001A2B3C4D5E))
+                .unwrap();
+            bits_mut
+                .put_u56::<LittleEndian>(u56::new(0x0102030405060708 >> 8))
+                .unwrap();
+            assert_eq!(
+                &bits_mut[..],
+                BitSlice::from_slice(&[
+                    0x00u8, 0x1A, 0x2B, 0x3C, 0x4D, 0x5E, 0x07, 0x06, 0x05, 0x04, 0x03, 0x02, 0x01
+                ])
+            );
+        }
+        {
+            let mut bits_mut = BitsMut::new();
+            bits_mut.put_u4(u4::new(0b1111)).unwrap();
+            bits_mut.put_u64::<BigEndian>(0x0123456789ABCDEF).unwrap();
+            bits_mut.put_u128::<BigEndian>(u128::MAX - 1).unwrap();
+            bits_mut.put_i128::<LittleEndian>(i128::MIN + 1).unwrap();
+            let mut bits = bits_mut.freeze();
+            assert_eq!(bits.get_u4().unwrap(), u4::new(0b1111));
+            assert_eq!(bits.get_u64::<BigEndian>().unwrap(), 0x0123456789ABCDEF);
+            assert_eq!(bits.get_u128::<BigEndian>().unwrap(), u128::MAX - 1);
+            assert_eq!(bits.get_i128::<LittleEndian>().unwrap(), i128::MIN + 1);
+        }
+    }
+
+    #[test]
+    fn test_put_signed() {
+        let mut bits_mut = BitsMut::new();
+        bits_mut.put_i1(i1::new(-1)).unwrap();
+        bits_mut.put_i7(i7::new(-2)).unwrap();
+        bits_mut.put_i12::<BigEndian>(i12::new(-2048)).unwrap();
+        bits_mut.put_i12::<BigEndian>(i12::new(2047)).unwrap();
+        assert_eq!(
+            &bits_mut[..],
+            bits![
+                1, 1, 1, 1, 1, 1, 1, 0, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 1, 1, 1,
+                1, 1, 1, 1
+            ]
+        );
+    }
+
+    #[test]
+    fn test_signed_byte_aligned() {
+        // The byte-aligned fast paths and the bit-by-bit paths agree for both orders
+        fn check<O: ByteOrder>(offset: usize) {
+            let mut bits_mut = BitsMut::new();
+            bits_mut.put_bits_from::<u8, BigEndian>(offset, 0).unwrap();
+            bits_mut.put_i24::<O>(i24::new(-2)).unwrap();
+            bits_mut.put_i40::<O>(i40::MIN).unwrap();
+            bits_mut.put_i48::<O>(i48::new(-0x12_3456_789a)).unwrap();
+            bits_mut.put_i56::<O>(i56::MAX).unwrap();
+            let mut bits = bits_mut.freeze();
+            bits.advance_bits(offset);
+            assert_eq!(bits.get_i24::<O>().unwrap(), i24::new(-2));
+            assert_eq!(bits.get_i40::<O>().unwrap(), i40::MIN);
+            assert_eq!(bits.get_i48::<O>().unwrap(), i48::new(-0x12_3456_789a));
+            assert_eq!(bits.get_i56::<O>().unwrap(), i56::MAX);
+        }
+        for offset in [0, 3] {
+            check::<BigEndian>(offset);
+            check::<LittleEndian>(offset);
+        }
+
+        let mut bits_mut = BitsMut::new();
+        bits_mut.put_i24::<BigEndian>(i24::new(-2)).unwrap();
+        bits_mut.put_i24::<LittleEndian>(i24::new(-2)).unwrap();
+        assert_eq!(
+            bits_mut.freeze(),
+            Bits::from_static_bytes(&[0xff, 0xff, 0xfe, 0xfe, 0xff, 0xff])
+        );
+    }
+
+    #[test]
+    fn test_put_floats() {
+        let mut bits_mut = BitsMut::new();
+        bits_mut.put_f32::<LittleEndian>(1.0).unwrap();
+        bits_mut.put_f16::<BigEndian>(-2.0).unwrap();
+        bits_mut.put_bf16::<BigEndian>(1.0).unwrap();
+        assert_eq!(
+            &bits_mut[..],
+            BitSlice::from_slice(&[0x00u8, 0x00, 0x80, 0x3F, 0xC0, 0x00, 0x3F, 0x80])
+        );
+    }
+
+    #[test]
+    fn test_minifloat_round_trip() {
+        let format = MinifloatFormat::new(4, 3);
+        let mut bits_mut = BitsMut::new();
+        bits_mut.put_bool(true).unwrap();
+        bits_mut.put_minifloat::<BigEndian>(format, -0.875).unwrap();
+        bits_mut.put_f16::<LittleEndian>(0.1).unwrap();
+        let mut bits = bits_mut.freeze();
+        assert!(bits.get_bool().unwrap());
+        assert_eq!(bits.get_minifloat::<BigEndian>(format).unwrap(), -0.875);
+        // 0.1 isn't exactly representable, it rounds to the nearest f16
+        assert_eq!(bits.get_f16::<LittleEndian>().unwrap(), 0.099975586);
+    }
+
+    #[test]
+    fn test_put_bits_from() {
+        let mut bits_mut = BitsMut::new();
+        bits_mut.put_bits_from::<u32, BigEndian>(3, 0b101).unwrap();
+        bits_mut.put_bits_from::<i16, BigEndian>(5, -3).unwrap();
+        bits_mut.put_bits_from::<u64, BigEndian>(0, 0).unwrap();
+        assert_eq!(&bits_mut[..], bits![1, 0, 1, 1, 1, 1, 0, 1]);
+
+        assert!(bits_mut.put_bits_from::<u8, BigEndian>(9, 0).is_err());
+        assert!(bits_mut.put_bits_from::<u32, BigEndian>(3, 0b1000).is_err());
+        assert!(bits_mut.put_bits_from::<i8, BigEndian>(4, 8).is_err());
+        assert!(bits_mut.put_bits_from::<i8, BigEndian>(4, -9).is_err());
+        assert_eq!(bits_mut.len_bits(), 8);
+    }
+
+    #[test]
+    fn test_put_ue_se() {
+        let mut bits_mut = BitsMut::new();
+        bits_mut.put_ue(0).unwrap();
+        bits_mut.put_ue(3).unwrap();
+        bits_mut.put_se(-2).unwrap();
+        assert_eq!(&bits_mut[..], bits![1, 0, 0, 1, 0, 0, 0, 0, 1, 0, 1]);
+    }
+
+    #[test]
+    fn test_exp_golomb_round_trip() {
+        let values = [0u64, 1, 2, 254, 255, 65535, (1 << 32) - 1, (1 << 33) - 2];
+        let mut bits_mut = BitsMut::new();
+        for value in values {
+            bits_mut.put_ue(value).unwrap();
+            bits_mut.put_exp_golomb(3, value).unwrap();
+        }
+        for value in [0i64, 1, -1, i32::MAX as i64, i32::MIN as i64] {
+            bits_mut.put_se(value).unwrap();
+        }
+
+        let mut bits = bits_mut.freeze();
+        for value in values {
+            assert_eq!(bits.get_ue().unwrap(), value);
+            assert_eq!(bits.get_exp_golomb(3).unwrap(), value);
+        }
+        for value in [0i64, 1, -1, i32::MAX as i64, i32::MIN as i64] {
+            assert_eq!(bits.get_se().unwrap(), value);
+        }
+        assert!(!bits.has_remaining_bits());
+    }
+
+    #[test]
+    fn test_put_ue_too_large() {
+        let mut bits_mut = BitsMut::new();
+        assert!(bits_mut.put_ue((1 << 33) - 1).is_err());
+        assert!(bits_mut.is_empty());
+    }
+
+    #[test]
+    fn test_put_se_extremes() {
+        let mut bits_mut = BitsMut::new();
+        for value in [i64::MIN, i64::MAX] {
+            assert_eq!(
+                bits_mut.put_se(value).unwrap_err().kind(),
+                std::io::ErrorKind::InvalidInput
+            );
+        }
+        assert!(bits_mut.is_empty());
+
+        // The largest magnitudes that still fit in a ue(v) code with 32 leading zeros
+        bits_mut.put_se((1 << 32) - 1).unwrap();
+        bits_mut.put_se(-((1 << 32) - 1)).unwrap();
+        let mut bits = bits_mut.freeze();
+        assert_eq!(bits.get_se().unwrap(), (1 << 32) - 1);
+        assert_eq!(bits.get_se().unwrap(), -((1 << 32) - 1));
+    }
+
+    #[test]
+    fn test_put_unary() {
+        let mut bits_mut = BitsMut::new();
+        bits_mut.put_unary(3, true).unwrap();
+        bits_mut.put_unary(2, false).unwrap();
+        bits_mut.put_unary(0, true).unwrap();
+        assert_eq!(&bits_mut[..], bits![0, 0, 0, 1, 1, 1, 0, 1]);
+
+        let mut bits_mut = BitsMut::new();
+        bits_mut.put_unary(200, true).unwrap();
+        let mut bits = bits_mut.freeze();
+        assert_eq!(bits.get_unary(true).unwrap(), 200);
+    }
+
+    #[test]
+    fn test_rice_golomb_round_trip() {
+        let values = [0u64, 1, 2, 3, 4, 5, 17, 100, 1000];
+        let mut bits_mut = BitsMut::new();
+        for value in values {
+            bits_mut.put_rice(3, value).unwrap();
+            bits_mut.put_golomb(5, value).unwrap();
+            bits_mut.put_golomb(8, value).unwrap();
+            bits_mut.put_rice(0, value).unwrap();
+            bits_mut.put_golomb(1, value).unwrap();
+        }
+        let mut bits = bits_mut.freeze();
+        for value in values {
+            assert_eq!(bits.get_rice(3).unwrap(), value);
+            assert_eq!(bits.get_golomb(5).unwrap(), value);
+            assert_eq!(bits.get_golomb(8).unwrap(), value);
+            assert_eq!(bits.get_rice(0).unwrap(), value);
+            assert_eq!(bits.get_golomb(1).unwrap(), value);
+        }
+        assert!(!bits.has_remaining_bits());
+    }
+
+    #[test]
+    fn test_golomb_large_m() {
+        // m above 2^63 needs 64-bit remainders, with cutoff = 2^64 - m
+        for m in [(1u64 << 63) + 1, u64::MAX] {
+            let cutoff = 0u64.wrapping_sub(m);
+            let values = [0, cutoff - 1, cutoff, m - 1, u64::MAX];
+            let mut bits_mut = BitsMut::new();
+            for value in values {
+                bits_mut.put_golomb(m, value).unwrap();
+            }
+            let mut bits = bits_mut.freeze();
+            for value in values {
+                assert_eq!(bits.get_golomb(m).unwrap(), value, "m = {}", m);
+            }
+            assert!(!bits.has_remaining_bits());
+        }
+    }
+
+    #[test]
+    fn test_lsb_codes() {
+        // Suffixes and remainders are fields, so in an LSB-first buffer they're written least
+        // significant bit first, but each code still starts with its prefix
+        let mut bits_mut = LsbBitsMut::new();
+        bits_mut.put_ue(4).unwrap();
+        bits_mut.put_golomb(5, 1).unwrap();
+        assert_eq!(bits_mut[..], lsb_bits![0, 0, 1, 1, 0, 1, 1, 0]);
+
+        let values = [0u64, 1, 2, 3, 4, 5, 17, 100, 1000];
+        let mut bits_mut = LsbBitsMut::new();
+        for value in values {
+            bits_mut.put_exp_golomb(3, value).unwrap();
+            bits_mut.put_se(-(value as i64)).unwrap();
+            bits_mut.put_rice(3, value).unwrap();
+            bits_mut.put_golomb(5, value).unwrap();
+            bits_mut.put_golomb(7, value).unwrap();
+        }
+        let mut bits = bits_mut.freeze();
+        for value in values {
+            assert_eq!(bits.get_exp_golomb(3).unwrap(), value);
+            assert_eq!(bits.get_se().unwrap(), -(value as i64));
+            assert_eq!(bits.get_rice(3).unwrap(), value);
+            assert_eq!(bits.get_golomb(5).unwrap(), value);
+            assert_eq!(bits.get_golomb(7).unwrap(), value);
+        }
+        assert!(!bits.has_remaining_bits());
+    }
+
+    fn check_put_many<const N: usize>() {
+        let mask = u32::MAX >> (32 - N);
+        let values: Vec<u32> = (0..150u32)
+            .map(|i| i.wrapping_mul(0x9e37_79b9).rotate_left(i) & mask)
+            .collect();
+        for offset in 0..8 {
+            // Both buffers start empty, so they grow chunk by chunk as they're written
+            let mut bits_mut = BitsMut::new();
+            let mut expected = BitsMut::new();
+            bits_mut.put_bits_from::<u8, BigEndian>(offset, 0).unwrap();
+            expected.put_bits_from::<u8, BigEndian>(offset, 0).unwrap();
+            bits_mut.put_many::<BigEndian, N>(&values).unwrap();
+            bits_mut.put_many::<LittleEndian, N>(&values).unwrap();
+            for &value in &values {
+                expected.put_uN::<BigEndian, N, u32, u32>(value).unwrap();
+            }
+            for &value in &values {
+                expected.put_uN::<LittleEndian, N, u32, u32>(value).unwrap();
+            }
+            assert_eq!(bits_mut[..], expected[..], "u{} at offset {}", N, offset);
+
+            let mut bits = bits_mut.freeze();
+            bits.advance_bits(offset);
+            let mut read = vec![0; values.len()];
+            bits.get_many::<BigEndian, N>(&mut read).unwrap();
+            assert_eq!(read, values, "u{} at offset {}", N, offset);
+            bits.get_many::<LittleEndian, N>(&mut read).unwrap();
+            assert_eq!(read, values, "u{} at offset {}", N, offset);
+        }
+    }
+
+    macro_rules! check_put_many {
+        ($($n:literal)*) => {
+            $(check_put_many::<$n>();)*
+        };
+    }
+
+    #[test]
+    fn test_put_many() {
+        check_put_many!(1 2 3 4 5 6 7 8 9 10 11 12 13 14 15 16 17 18 19 20 21 22 23 24 25 26 27 28
+            29 30 31 32);
+
+        let mut bits_mut = BitsMut::new();
+        assert!(bits_mut.put_many::<BigEndian, 4>(&[1, 2, 16]).is_err());
+        assert!(bits_mut.is_empty());
+
+        // Writing into a fixed-size buffer
+        let mut data = [0u8; 3];
+        let mut dest = &mut data[..];
+        dest.put_many::<BigEndian, 6>(&[0b101010, 0b010101, 0b111111, 0b000000])
+            .unwrap();
+        assert_eq!(data, [0b1010_1001, 0b0101_1111, 0b1100_0000]);
+        let mut dest = &mut data[..];
+        assert!(dest.put_many::<BigEndian, 5>(&[0; 5]).is_err());
+    }
 }
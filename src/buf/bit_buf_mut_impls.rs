@@ -72,13 +72,13 @@ impl BitBufMut for &mut [u8] {
     }
 }
 
-impl BitBufMut for &mut BitSlice {
+impl<Order: BitOrder> BitBufMut<Order> for &mut bitvec::slice::BitSlice<u8, Order> {
     fn advance_mut_bits(&mut self, count: usize) {
         assert!(count <= self.len(), "advance_mut_bits past end");
         *self = &mut std::mem::take(self)[count..];
     }
 
-    fn chunk_mut_bits(&mut self) -> &mut BitSlice {
+    fn chunk_mut_bits(&mut self) -> &mut bitvec::slice::BitSlice<u8, Order> {
         self
     }
 
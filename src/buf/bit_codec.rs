@@ -0,0 +1,259 @@
+use crate::prelude::*;
+
+/// A type which can be read from a [`BitBuf`].  This lets generic code read any supported type
+/// with a single call (`buf.get::<u13, BigEndian>()`) rather than needing to know the name of the
+/// method for each width.
+///
+/// Like the named `get_uN` methods, types that are 8 bits or narrower are always read in the
+/// buffer's [`BitOrder::FieldOrder`] regardless of `O`.
+pub trait BitDecode: Sized {
+    fn bit_decode<O: ByteOrder, Order: BitOrder, B: BitBuf<Order> + ?Sized>(
+        buf: &mut B,
+    ) -> std::io::Result<Self>;
+}
+
+/// A type which can be written to a [`BitBufMut`].  This is the write-side counterpart of
+/// [`BitDecode`].
+pub trait BitEncode {
+    fn bit_encode<O: ByteOrder, Order: BitOrder, B: BitBufMut<Order> + ?Sized>(
+        &self,
+        buf: &mut B,
+    ) -> std::io::Result<()>;
+}
+
+// Types which map directly onto one of the named methods on the ext traits.  The first form is for
+// methods which don't take a ByteOrder.
+macro_rules! impl_bit_codec_named {
+    ($type:ty, $get:ident, $put:ident) => {
+        impl BitDecode for $type {
+            fn bit_decode<O: ByteOrder, Order: BitOrder, B: BitBuf<Order> + ?Sized>(
+                buf: &mut B,
+            ) -> std::io::Result<Self> {
+                buf.$get()
+            }
+        }
+
+        impl BitEncode for $type {
+            fn bit_encode<O: ByteOrder, Order: BitOrder, B: BitBufMut<Order> + ?Sized>(
+                &self,
+                buf: &mut B,
+            ) -> std::io::Result<()> {
+                buf.$put(*self)
+            }
+        }
+    };
+    ($type:ty, $get:ident::<O>, $put:ident::<O>) => {
+        impl BitDecode for $type {
+            fn bit_decode<O: ByteOrder, Order: BitOrder, B: BitBuf<Order> + ?Sized>(
+                buf: &mut B,
+            ) -> std::io::Result<Self> {
+                buf.$get::<O>()
+            }
+        }
+
+        impl BitEncode for $type {
+            fn bit_encode<O: ByteOrder, Order: BitOrder, B: BitBufMut<Order> + ?Sized>(
+                &self,
+                buf: &mut B,
+            ) -> std::io::Result<()> {
+                buf.$put::<O>(*self)
+            }
+        }
+    };
+}
+
+// nsw-types which don't have a byte-aligned fast path and go through get_uN/put_uN directly.
+// Types of 8 bits or less always use the bit order's field order, to match get_u1..get_u7.
+macro_rules! impl_bit_codec_unsigned {
+    ($($type:ident),* => $order:ty, $integral:ty) => {
+        $(
+            impl BitDecode for $type {
+                fn bit_decode<O: ByteOrder, Order: BitOrder, B: BitBuf<Order> + ?Sized>(
+                    buf: &mut B,
+                ) -> std::io::Result<Self> {
+                    buf.get_uN::<$order, { $type::BITS as usize }, $type, $integral>()
+                }
+            }
+
+            impl BitEncode for $type {
+                fn bit_encode<O: ByteOrder, Order: BitOrder, B: BitBufMut<Order> + ?Sized>(
+                    &self,
+                    buf: &mut B,
+                ) -> std::io::Result<()> {
+                    buf.put_uN::<$order, { $type::BITS as usize }, $type, $integral>(*self)
+                }
+            }
+        )*
+    };
+}
+
+macro_rules! impl_bit_codec_signed {
+    ($($type:ident),* => $order:ty, $integral:ty) => {
+        $(
+            impl BitDecode for $type {
+                fn bit_decode<O: ByteOrder, Order: BitOrder, B: BitBuf<Order> + ?Sized>(
+                    buf: &mut B,
+                ) -> std::io::Result<Self> {
+                    buf.get_iN::<$order, { $type::BITS as usize }, $type, $integral>()
+                }
+            }
+
+            impl BitEncode for $type {
+                fn bit_encode<O: ByteOrder, Order: BitOrder, B: BitBufMut<Order> + ?Sized>(
+                    &self,
+                    buf: &mut B,
+                ) -> std::io::Result<()> {
+                    buf.put_iN::<$order, { $type::BITS as usize }, $type, $integral>(*self)
+                }
+            }
+        )*
+    };
+}
+
+impl_bit_codec_named!(bool, get_bool, put_bool);
+impl_bit_codec_named!(u8, get_u8, put_u8);
+impl_bit_codec_named!(u16, get_u16::<O>, put_u16::<O>);
+impl_bit_codec_named!(u24, get_u24::<O>, put_u24::<O>);
+impl_bit_codec_named!(u32, get_u32::<O>, put_u32::<O>);
+impl_bit_codec_named!(u40, get_u40::<O>, put_u40::<O>);
+impl_bit_codec_named!(u48, get_u48::<O>, put_u48::<O>);
+impl_bit_codec_named!(u56, get_u56::<O>, put_u56::<O>);
+impl_bit_codec_named!(u64, get_u64::<O>, put_u64::<O>);
+impl_bit_codec_named!(u128, get_u128::<O>, put_u128::<O>);
+impl_bit_codec_named!(i1, get_i1, put_i1);
+impl_bit_codec_named!(i8, get_i8, put_i8);
+impl_bit_codec_named!(i16, get_i16::<O>, put_i16::<O>);
+impl_bit_codec_named!(i32, get_i32::<O>, put_i32::<O>);
+impl_bit_codec_named!(i64, get_i64::<O>, put_i64::<O>);
+impl_bit_codec_named!(i128, get_i128::<O>, put_i128::<O>);
+impl_bit_codec_named!(f32, get_f32::<O>, put_f32::<O>);
+impl_bit_codec_named!(f64, get_f64::<O>, put_f64::<O>);
+
+impl_bit_codec_unsigned!(u1, u2, u3, u4, u5, u6, u7 => Order::FieldOrder, u8);
+impl_bit_codec_unsigned!(u9, u10, u11, u12, u13, u14, u15 => O, u16);
+impl_bit_codec_unsigned!(u17, u18, u19, u20, u21, u22, u23 => O, u32);
+impl_bit_codec_unsigned!(u25, u26, u27, u28, u29, u30, u31 => O, u32);
+impl_bit_codec_unsigned!(u33, u34, u35, u36, u37, u38, u39 => O, u64);
+impl_bit_codec_unsigned!(u41, u42, u43, u44, u45, u46, u47 => O, u64);
+impl_bit_codec_unsigned!(u49, u50, u51, u52, u53, u54, u55 => O, u64);
+impl_bit_codec_unsigned!(u57, u58, u59, u60, u61, u62, u63 => O, u64);
+
+impl_bit_codec_signed!(i2, i3, i4, i5, i6, i7 => Order::FieldOrder, i8);
+impl_bit_codec_signed!(i9, i10, i11, i12, i13, i14, i15 => O, i16);
+impl_bit_codec_signed!(i17, i18, i19, i20, i21, i22, i23, i24 => O, i32);
+impl_bit_codec_signed!(i25, i26, i27, i28, i29, i30, i31 => O, i32);
+impl_bit_codec_signed!(i33, i34, i35, i36, i37, i38, i39, i40 => O, i64);
+impl_bit_codec_signed!(i41, i42, i43, i44, i45, i46, i47, i48 => O, i64);
+impl_bit_codec_signed!(i49, i50, i51, i52, i53, i54, i55, i56 => O, i64);
+impl_bit_codec_signed!(i57, i58, i59, i60, i61, i62, i63 => O, i64);
+
+impl<T: BitDecode, const N: usize> BitDecode for [T; N] {
+    fn bit_decode<O: ByteOrder, Order: BitOrder, B: BitBuf<Order> + ?Sized>(
+        buf: &mut B,
+    ) -> std::io::Result<Self> {
+        let mut values = Vec::with_capacity(N);
+        for _ in 0..N {
+            values.push(T::bit_decode::<O, Order, B>(buf)?);
+        }
+        match values.try_into() {
+            Ok(array) => Ok(array),
+            Err(_) => unreachable!("Exactly N values were decoded"),
+        }
+    }
+}
+
+impl<T: BitEncode, const N: usize> BitEncode for [T; N] {
+    fn bit_encode<O: ByteOrder, Order: BitOrder, B: BitBufMut<Order> + ?Sized>(
+        &self,
+        buf: &mut B,
+    ) -> std::io::Result<()> {
+        for value in self {
+            value.bit_encode::<O, Order, B>(buf)?;
+        }
+        Ok(())
+    }
+}
+
+// Tuples are read and written in field order
+macro_rules! impl_bit_codec_tuple {
+    ($($name:ident),+) => {
+        impl<$($name: BitDecode),+> BitDecode for ($($name,)+) {
+            fn bit_decode<O: ByteOrder, Order: BitOrder, B: BitBuf<Order> + ?Sized>(buf: &mut B) -> std::io::Result<Self> {
+                Ok(($($name::bit_decode::<O, Order, B>(buf)?,)+))
+            }
+        }
+
+        impl<$($name: BitEncode),+> BitEncode for ($($name,)+) {
+            #[allow(non_snake_case)]
+            fn bit_encode<O: ByteOrder, Order: BitOrder, B: BitBufMut<Order> + ?Sized>(
+                &self,
+                buf: &mut B,
+            ) -> std::io::Result<()> {
+                let ($($name,)+) = self;
+                $($name.bit_encode::<O, Order, B>(buf)?;)+
+                Ok(())
+            }
+        }
+    };
+}
+
+impl_bit_codec_tuple!(A);
+impl_bit_codec_tuple!(A, B1);
+impl_bit_codec_tuple!(A, B1, C);
+impl_bit_codec_tuple!(A, B1, C, D);
+impl_bit_codec_tuple!(A, B1, C, D, E);
+impl_bit_codec_tuple!(A, B1, C, D, E, F);
+impl_bit_codec_tuple!(A, B1, C, D, E, F, G);
+impl_bit_codec_tuple!(A, B1, C, D, E, F, G, H);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn read_field<T: BitDecode>(bits: &mut Bits) -> T {
+        bits.get::<T, BigEndian>().unwrap()
+    }
+
+    #[test]
+    fn test_generic_get() {
+        let mut bits = Bits::copy_from_bit_slice(bits![
+            1, 0, 1, 0, 1, 0, 1, 0, 1, 0, 1, 0, 1, //
+            1, 1, 0, //
+            0, 1, 1, 1
+        ]);
+        let value: u13 = read_field(&mut bits);
+        assert_eq!(value, u13::new(0b1010101010101));
+        let value: (bool, u2) = read_field(&mut bits);
+        assert_eq!(value, (true, u2::new(0b10)));
+        let value: [u2; 2] = read_field(&mut bits);
+        assert_eq!(value, [u2::new(0b01), u2::new(0b11)]);
+    }
+
+    #[test]
+    fn test_generic_round_trip() {
+        let mut bits_mut = BitsMut::new();
+        bits_mut.put::<BigEndian, _>(u9::new(300)).unwrap();
+        bits_mut.put::<LittleEndian, _>(0x1234u16).unwrap();
+        bits_mut
+            .put::<BigEndian, _>((i5::new(-7), [true, false, true], 1.5f32))
+            .unwrap();
+        bits_mut.put::<BigEndian, _>(u63::MAX).unwrap();
+        bits_mut
+            .put::<LittleEndian, _>(-0x0123_4567_89ab_cdefi128 << 64)
+            .unwrap();
+
+        let mut bits = bits_mut.freeze();
+        assert_eq!(bits.get::<u9, BigEndian>().unwrap(), u9::new(300));
+        assert_eq!(bits.get::<u16, LittleEndian>().unwrap(), 0x1234);
+        assert_eq!(
+            bits.get::<(i5, [bool; 3], f32), BigEndian>().unwrap(),
+            (i5::new(-7), [true, false, true], 1.5)
+        );
+        assert_eq!(bits.get::<u63, BigEndian>().unwrap(), u63::MAX);
+        assert_eq!(
+            bits.get::<i128, LittleEndian>().unwrap(),
+            -0x0123_4567_89ab_cdefi128 << 64
+        );
+        assert!(bits.get::<bool, BigEndian>().is_err());
+    }
+}
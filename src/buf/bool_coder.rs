@@ -0,0 +1,331 @@
+use crate::prelude::*;
+
+/// The VP8/VP9 boolean entropy decoder (RFC 6386 section 7), reading from a `BitBuf`.
+///
+/// As in libvpx, reading past the end of the data shifts in zeros rather than failing, since
+/// encoders are allowed to drop trailing zero bytes.
+pub struct BoolDecoder<B: BitBuf> {
+    inner: B,
+    /// A 16-bit window onto the coded value, whose top 8 bits are compared against the split
+    value: u32,
+    range: u32,
+}
+
+impl<B: BitBuf> BoolDecoder<B> {
+    /// Initializes the decoder, which reads the first two bytes of `inner`.
+    pub fn new(mut inner: B) -> std::io::Result<Self> {
+        let value = read_padded(&mut inner, 16)?;
+        Ok(Self {
+            inner,
+            value,
+            range: 255,
+        })
+    }
+
+    pub fn into_inner(self) -> B {
+        self.inner
+    }
+
+    pub fn get_ref(&self) -> &B {
+        &self.inner
+    }
+
+    /// Reads a bool whose probability of being false is `prob / 256`.
+    pub fn read_bool(&mut self, prob: u8) -> std::io::Result<bool> {
+        let split = 1 + (((self.range - 1) * prob as u32) >> 8);
+        let big_split = split << 8;
+        let bit = if self.value >= big_split {
+            self.range -= split;
+            self.value -= big_split;
+            true
+        } else {
+            self.range = split;
+            false
+        };
+        // The range is 8 bits, so this is the number of doublings it takes to reach at least 128
+        let shift = self.range.leading_zeros() as usize - 24;
+        if shift > 0 {
+            self.range <<= shift;
+            self.value = (self.value << shift) | read_padded(&mut self.inner, shift)?;
+        }
+        Ok(bit)
+    }
+
+    /// Reads an `n`-bit (at most 32) unsigned literal, most significant bit first, with each bit
+    /// coded at even probability (the `L(n)` of RFC 6386).
+    pub fn read_literal(&mut self, n: usize) -> std::io::Result<u32> {
+        assert!(n <= 32, "Can't read a {}-bit literal into a u32", n);
+        let mut value = 0;
+        for _ in 0..n {
+            value = (value << 1) | self.read_bool(128)? as u32;
+        }
+        Ok(value)
+    }
+
+    /// Reads a value coded with a tree (RFC 6386 section 8.1).  `tree` holds pairs of entries for
+    /// the 0 and 1 branches of each node: positive entries are the index of the next node's pair,
+    /// and other entries are leaves holding the negated value.  `probs[i]` is the probability of
+    /// the node whose pair starts at `2 * i`.
+    pub fn read_tree(&mut self, tree: &[i8], probs: &[u8]) -> std::io::Result<u8> {
+        let mut index = 0;
+        loop {
+            let bit = self.read_bool(probs[index >> 1])?;
+            match tree[index + bit as usize] {
+                next if next > 0 => index = next as usize,
+                leaf => return Ok(leaf.unsigned_abs()),
+            }
+        }
+    }
+}
+
+/// Reads `n` bits from `buf`, with zeros in place of any bits past the end.
+fn read_padded<B: BitBuf + ?Sized>(buf: &mut B, n: usize) -> std::io::Result<u32> {
+    let available = n.min(buf.remaining_bits());
+    let value: u32 = buf.get_bits_as::<u32, BigEndian>(available)?;
+    Ok(value << (n - available))
+}
+
+/// The VP8/VP9 boolean entropy encoder (RFC 6386 section 7.3), writing to a `BitBufMut`.
+///
+/// Carries are resolved before bytes are written (rather than by modifying bytes which have
+/// already been output, as in the RFC), by holding back the last byte which could absorb one.
+pub struct BoolEncoder<B: BitBufMut> {
+    inner: B,
+    range: u32,
+    bottom: u32,
+    /// The number of shifts before the next byte is output
+    bit_count: u32,
+    /// The last byte output which isn't 0xff, which hasn't been written yet
+    pending: Option<u8>,
+    /// The number of 0xff bytes output after `pending`
+    ff_run: usize,
+}
+
+impl<B: BitBufMut> BoolEncoder<B> {
+    pub fn new(inner: B) -> Self {
+        Self {
+            inner,
+            range: 255,
+            bottom: 0,
+            bit_count: 24,
+            pending: None,
+            ff_run: 0,
+        }
+    }
+
+    pub fn get_ref(&self) -> &B {
+        &self.inner
+    }
+
+    /// Writes a bool whose probability of being false is `prob / 256`.
+    pub fn write_bool(&mut self, prob: u8, bit: bool) -> std::io::Result<()> {
+        let split = 1 + (((self.range - 1) * prob as u32) >> 8);
+        if bit {
+            self.bottom = self.bottom.wrapping_add(split);
+            self.range -= split;
+        } else {
+            self.range = split;
+        }
+        while self.range < 128 {
+            self.range <<= 1;
+            if self.bottom & (1 << 31) != 0 {
+                self.carry()?;
+            }
+            self.bottom <<= 1;
+            self.bit_count -= 1;
+            if self.bit_count == 0 {
+                self.output((self.bottom >> 24) as u8)?;
+                self.bottom &= (1 << 24) - 1;
+                self.bit_count = 8;
+            }
+        }
+        Ok(())
+    }
+
+    /// Writes the low `n` bits (at most 32) of `value` as a literal, most significant bit first.
+    pub fn write_literal(&mut self, n: usize, value: u32) -> std::io::Result<()> {
+        assert!(n <= 32, "Can't write a {}-bit literal from a u32", n);
+        for i in (0..n).rev() {
+            self.write_bool(128, (value >> i) & 1 != 0)?;
+        }
+        Ok(())
+    }
+
+    /// Writes `value` with a tree, as described in [`BoolDecoder::read_tree`].  Returns an error
+    /// if `value` isn't a leaf of `tree`.
+    pub fn write_tree(&mut self, tree: &[i8], probs: &[u8], value: u8) -> std::io::Result<()> {
+        let mut path = Vec::new();
+        if !find_leaf(tree, 0, value, &mut path) {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!("Value ({}) is not in the tree", value),
+            ));
+        }
+        for (index, bit) in path {
+            self.write_bool(probs[index >> 1], bit)?;
+        }
+        Ok(())
+    }
+
+    /// Flushes the remaining state (as libvpx does, by writing 32 bools at even probability) and
+    /// returns the inner buffer.
+    pub fn finish(mut self) -> std::io::Result<B> {
+        for _ in 0..32 {
+            self.write_bool(128, false)?;
+        }
+        if let Some(pending) = self.pending.take() {
+            self.inner.put_u8(pending)?;
+        }
+        for _ in 0..self.ff_run {
+            self.inner.put_u8(0xff)?;
+        }
+        Ok(self.inner)
+    }
+
+    /// Adds one to the bytes output so far.
+    fn carry(&mut self) -> std::io::Result<()> {
+        // The coded value is always less than one, so there's always a byte to carry into
+        let pending = self.pending.expect("carry before any output") + 1;
+        if self.ff_run == 0 {
+            self.pending = Some(pending);
+        } else {
+            // The run of 0xff bytes becomes zeros, the last of which can absorb the next carry
+            self.inner.put_u8(pending)?;
+            for _ in 1..self.ff_run {
+                self.inner.put_u8(0)?;
+            }
+            self.pending = Some(0);
+            self.ff_run = 0;
+        }
+        Ok(())
+    }
+
+    fn output(&mut self, byte: u8) -> std::io::Result<()> {
+        if byte == 0xff {
+            self.ff_run += 1;
+            return Ok(());
+        }
+        if let Some(pending) = self.pending.replace(byte) {
+            self.inner.put_u8(pending)?;
+        }
+        for _ in 0..self.ff_run {
+            self.inner.put_u8(0xff)?;
+        }
+        self.ff_run = 0;
+        Ok(())
+    }
+}
+
+/// Finds the path from the node pair at `index` to the leaf for `value`, as (pair index, bit).
+fn find_leaf(tree: &[i8], index: usize, value: u8, path: &mut Vec<(usize, bool)>) -> bool {
+    for bit in [false, true] {
+        path.push((index, bit));
+        let found = match tree[index + bit as usize] {
+            next if next > 0 => find_leaf(tree, next as usize, value, path),
+            leaf => leaf.unsigned_abs() == value,
+        };
+        if found {
+            return true;
+        }
+        path.pop();
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // The VP8 intra luma mode tree (RFC 6386 section 11.2) and its key frame probabilities
+    const DC_PRED: u8 = 0;
+    const V_PRED: u8 = 1;
+    const H_PRED: u8 = 2;
+    const TM_PRED: u8 = 3;
+    const B_PRED: u8 = 4;
+    const KF_YMODE_TREE: [i8; 8] = [
+        -(B_PRED as i8),
+        2,
+        4,
+        6,
+        -(DC_PRED as i8),
+        -(V_PRED as i8),
+        -(H_PRED as i8),
+        -(TM_PRED as i8),
+    ];
+    const KF_YMODE_PROB: [u8; 4] = [145, 156, 163, 128];
+
+    #[test]
+    fn test_round_trip() {
+        let mut seed = 0x9e37_79b9u32;
+        let mut next = move || {
+            seed ^= seed << 13;
+            seed ^= seed >> 17;
+            seed ^= seed << 5;
+            seed
+        };
+        let bools: Vec<(u8, bool)> = (0..5000)
+            .map(|_| {
+                let value = next();
+                let prob = (value & 0xff) as u8;
+                // Mostly follow the probability, so some runs are coded very cheaply
+                (prob, ((value >> 8) & 0xff) >= prob as u32)
+            })
+            .collect();
+
+        let mut encoder = BoolEncoder::new(BitsMut::new());
+        encoder.write_literal(7, 0x5a).unwrap();
+        for &(prob, bit) in &bools {
+            encoder.write_bool(prob, bit).unwrap();
+        }
+        for mode in [B_PRED, TM_PRED, DC_PRED, H_PRED, V_PRED] {
+            encoder
+                .write_tree(&KF_YMODE_TREE, &KF_YMODE_PROB, mode)
+                .unwrap();
+        }
+        encoder.write_literal(32, 0xdead_beef).unwrap();
+        let bits = encoder.finish().unwrap().freeze();
+        assert!(bits.len_bytes() < 5000 / 8);
+
+        let mut decoder = BoolDecoder::new(bits).unwrap();
+        assert_eq!(decoder.read_literal(7).unwrap(), 0x5a);
+        for (i, &(prob, bit)) in bools.iter().enumerate() {
+            assert_eq!(decoder.read_bool(prob).unwrap(), bit, "bool {}", i);
+        }
+        for mode in [B_PRED, TM_PRED, DC_PRED, H_PRED, V_PRED] {
+            assert_eq!(
+                decoder.read_tree(&KF_YMODE_TREE, &KF_YMODE_PROB).unwrap(),
+                mode
+            );
+        }
+        assert_eq!(decoder.read_literal(32).unwrap(), 0xdead_beef);
+    }
+
+    #[test]
+    fn test_carry() {
+        // Always coding the unlikely value pushes `bottom` up, which forces carries into runs of
+        // 0xff bytes
+        for prob in [1, 2, 3, 200, 255] {
+            let mut encoder = BoolEncoder::new(BitsMut::new());
+            for i in 0..2000 {
+                encoder.write_bool(prob, i % 7 != 0).unwrap();
+            }
+            let bits = encoder.finish().unwrap().freeze();
+            let mut decoder = BoolDecoder::new(bits).unwrap();
+            for i in 0..2000 {
+                assert_eq!(decoder.read_bool(prob).unwrap(), i % 7 != 0);
+            }
+        }
+    }
+
+    #[test]
+    fn test_read_past_end() {
+        // Missing data reads as zeros, so an empty buffer decodes as all false
+        let mut decoder = BoolDecoder::new(Bits::from_static_bytes(&[])).unwrap();
+        assert_eq!(decoder.read_literal(16).unwrap(), 0);
+
+        let mut encoder = BoolEncoder::new(BitsMut::new());
+        assert!(encoder
+            .write_tree(&KF_YMODE_TREE, &KF_YMODE_PROB, 5)
+            .is_err());
+    }
+}
@@ -0,0 +1,258 @@
+use crate::prelude::*;
+
+/// The number of values `get_many`/`put_many` handle at a time.
+pub(crate) const BULK_BLOCK_LEN: usize = 64;
+
+/// The size of the byte buffer a block of up to 32-bit values is staged in, including the padding
+/// [`unpack`] needs after the packed bits.
+pub(crate) const BULK_BLOCK_BYTES: usize = BULK_BLOCK_LEN * 4 + 8;
+
+/// Extracts `values.len()` fields of `n` (1 to 32) bits, packed in `Order` from the start of
+/// `bytes`, without applying a byte order.  `bytes` must extend at least 8 bytes past the packed
+/// bits.
+pub(crate) fn unpack<Order: BitOrder>(bytes: &[u8], n: usize, values: &mut [u32]) {
+    if !Order::MSB_FIRST {
+        unpack_lsb(bytes, n, values);
+        return;
+    }
+    #[cfg(target_arch = "x86_64")]
+    if n <= 25 && std::arch::is_x86_feature_detected!("avx2") {
+        let simd_len = values.len() / 8 * 8;
+        // SAFETY: avx2 is available, and every gather reads 4 bytes starting within the packed
+        // bits, which `bytes` extends at least 8 bytes past.
+        unsafe { unpack_avx2(bytes, n, &mut values[..simd_len]) };
+        unpack_scalar(bytes, n, simd_len, &mut values[simd_len..]);
+        return;
+    }
+    unpack_scalar(bytes, n, 0, values);
+}
+
+/// Extracts LSB-first fields, whose first bit is the least significant, with one unaligned
+/// little-endian load per field.
+fn unpack_lsb(bytes: &[u8], n: usize, values: &mut [u32]) {
+    for (i, value) in values.iter_mut().enumerate() {
+        let position = i * n;
+        let word = u64::from_le_bytes(bytes[position / 8..position / 8 + 8].try_into().unwrap());
+        *value = ((word >> (position % 8)) & ((1 << n) - 1)) as u32;
+    }
+}
+
+/// Extracts fields `first..first + values.len()` with one unaligned 64-bit load per field.
+fn unpack_scalar(bytes: &[u8], n: usize, first: usize, values: &mut [u32]) {
+    for (i, value) in values.iter_mut().enumerate() {
+        let position = (first + i) * n;
+        let word = u64::from_be_bytes(bytes[position / 8..position / 8 + 8].try_into().unwrap());
+        *value = ((word << (position % 8)) >> (64 - n)) as u32;
+    }
+}
+
+/// Extracts 8 fields at a time: each lane gathers the 4 bytes its field starts in, byte-swaps
+/// them to big-endian and shifts the field out.  A field of at most 25 bits always fits in those
+/// 4 bytes.
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "avx2")]
+unsafe fn unpack_avx2(bytes: &[u8], n: usize, values: &mut [u32]) {
+    use std::arch::x86_64::*;
+
+    // 8 fields take exactly `n` bytes, so every group of 8 has the same layout
+    let offsets: [i32; 8] = std::array::from_fn(|i| (i * n / 8) as i32);
+    let shifts: [i32; 8] = std::array::from_fn(|i| (i * n % 8) as i32);
+    let offsets = _mm256_loadu_si256(offsets.as_ptr() as *const __m256i);
+    let shifts = _mm256_loadu_si256(shifts.as_ptr() as *const __m256i);
+    let swap = _mm256_setr_epi8(
+        3, 2, 1, 0, 7, 6, 5, 4, 11, 10, 9, 8, 15, 14, 13, 12, 3, 2, 1, 0, 7, 6, 5, 4, 11, 10, 9, 8,
+        15, 14, 13, 12,
+    );
+    let right_shift = _mm_cvtsi32_si128(32 - n as i32);
+    for (group_index, group) in values.chunks_exact_mut(8).enumerate() {
+        let base = bytes.as_ptr().add(group_index * n) as *const i32;
+        let words = _mm256_shuffle_epi8(_mm256_i32gather_epi32::<1>(base, offsets), swap);
+        let fields = _mm256_srl_epi32(_mm256_sllv_epi32(words, shifts), right_shift);
+        _mm256_storeu_si256(group.as_mut_ptr() as *mut __m256i, fields);
+    }
+}
+
+/// Packs the low `n` (1 to 32) bits of each of `values` in `Order` from the start of `bytes`,
+/// without applying a byte order.  Bits after the last field, up to the end of its byte, are
+/// zeroed.
+pub(crate) fn pack<Order: BitOrder>(values: &[u32], n: usize, bytes: &mut [u8]) {
+    if !Order::MSB_FIRST {
+        pack_lsb(values, n, bytes);
+        return;
+    }
+    let mut word = 0u64;
+    let mut word_len = 0;
+    let mut byte_index = 0;
+    for &value in values {
+        word = (word << n) | value as u64;
+        word_len += n;
+        while word_len >= 8 {
+            word_len -= 8;
+            bytes[byte_index] = (word >> word_len) as u8;
+            byte_index += 1;
+        }
+    }
+    if word_len > 0 {
+        bytes[byte_index] = (word << (8 - word_len)) as u8;
+    }
+}
+
+/// Packs LSB-first fields: each field's first bit is its least significant.
+fn pack_lsb(values: &[u32], n: usize, bytes: &mut [u8]) {
+    let mut word = 0u64;
+    let mut word_len = 0;
+    let mut byte_index = 0;
+    for &value in values {
+        word |= (value as u64) << word_len;
+        word_len += n;
+        while word_len >= 8 {
+            bytes[byte_index] = word as u8;
+            word >>= 8;
+            word_len -= 8;
+            byte_index += 1;
+        }
+    }
+    if word_len > 0 {
+        bytes[byte_index] = word as u8;
+    }
+}
+
+/// Converts the bits of an `n`-bit (at most 64) field, as [`unpack`] extracts them for `Order`, to
+/// the value `O::load` gives for them when the field starts `offset` bits into a byte.  This lets
+/// bulk reads extract fields with plain shifts and masks.
+pub(crate) fn from_raw_bits<Order: BitOrder, O: ByteOrder>(
+    raw: u64,
+    n: usize,
+    offset: usize,
+) -> u64 {
+    match (Order::MSB_FIRST, is_little_endian::<O>()) {
+        (true, true) => first_segment_to_low(raw, n, offset),
+        (false, false) => first_segment_to_high(raw, n, offset),
+        // The field's bits are already in the order the byte order reads them
+        _ => raw,
+    }
+}
+
+/// The inverse of [`from_raw_bits`].
+pub(crate) fn to_raw_bits<Order: BitOrder, O: ByteOrder>(
+    value: u64,
+    n: usize,
+    offset: usize,
+) -> u64 {
+    match (Order::MSB_FIRST, is_little_endian::<O>()) {
+        (true, true) => first_segment_to_high(value, n, offset),
+        (false, false) => first_segment_to_low(value, n, offset),
+        _ => value,
+    }
+}
+
+/// bitvec splits a field which doesn't match the bit order at byte boundaries, and orders the
+/// segments by the byte order instead.  This moves the segment in the first byte from the most
+/// significant end of `raw` to the least significant end, and the rest after it.
+fn first_segment_to_low(raw: u64, n: usize, offset: usize) -> u64 {
+    let mut value = 0;
+    let mut shift = 0;
+    let mut remaining = n;
+    let mut segment_len = n.min(8 - offset);
+    while remaining > 0 {
+        let segment = (raw >> (remaining - segment_len)) & ((1 << segment_len) - 1);
+        value |= segment << shift;
+        shift += segment_len;
+        remaining -= segment_len;
+        segment_len = remaining.min(8);
+    }
+    value
+}
+
+/// The inverse of [`first_segment_to_low`].
+fn first_segment_to_high(value: u64, n: usize, offset: usize) -> u64 {
+    let mut raw = 0;
+    let mut shift = 0;
+    let mut remaining = n;
+    let mut segment_len = n.min(8 - offset);
+    while remaining > 0 {
+        let segment = (value >> shift) & ((1 << segment_len) - 1);
+        raw |= segment << (remaining - segment_len);
+        shift += segment_len;
+        remaining -= segment_len;
+        segment_len = remaining.min(8);
+    }
+    raw
+}
+
+/// Whether `O` treats the first byte as the least significant, as [`LittleEndian`] does.
+fn is_little_endian<O: ByteOrder>() -> bool {
+    O::load_u16(&[0x01, 0x00]) == 1
+}
+
+#[cfg(test)]
+mod tests {
+    use bitvec::view::BitView;
+
+    use super::*;
+
+    fn check_pack_unpack<Order: BitOrder>() {
+        for n in 1..=32 {
+            let mask = u32::MAX >> (32 - n);
+            let values: Vec<u32> = (0..BULK_BLOCK_LEN as u32 - 3)
+                .map(|i| i.wrapping_mul(0x9e37_79b9).rotate_left(i) & mask)
+                .collect();
+            let mut bytes = [0xff; BULK_BLOCK_BYTES];
+            pack::<Order>(&values, n, &mut bytes);
+            // Each field is stored in the order its bits are read
+            let packed = &bytes.view_bits::<Order>()[..values.len() * n];
+            for (i, &value) in values.iter().enumerate() {
+                let field: u32 = Order::FieldOrder::load(&packed[i * n..(i + 1) * n]);
+                assert_eq!(field, value, "width {}", n);
+            }
+
+            let mut unpacked = vec![0; values.len()];
+            unpack::<Order>(&bytes, n, &mut unpacked);
+            assert_eq!(unpacked, values, "width {}", n);
+        }
+    }
+
+    #[test]
+    fn test_pack_unpack() {
+        check_pack_unpack::<Msb0>();
+        check_pack_unpack::<Lsb0>();
+
+        // Whichever path `unpack` took, the scalar path agrees with it
+        for n in 1..=32 {
+            let bytes: Vec<u8> = (0..BULK_BLOCK_BYTES as u32)
+                .map(|i| i.wrapping_mul(0x9e37_79b9) as u8)
+                .collect();
+            let mut unpacked = vec![0; BULK_BLOCK_LEN];
+            let mut scalar = vec![0; BULK_BLOCK_LEN];
+            unpack::<Msb0>(&bytes, n, &mut unpacked);
+            unpack_scalar(&bytes, n, 0, &mut scalar);
+            assert_eq!(unpacked, scalar, "width {}", n);
+        }
+    }
+
+    fn check_raw_bits<Order: BitOrder>() {
+        let mut bytes = [0u8; 8];
+        for n in [1, 5, 8, 13, 32, 57] {
+            for offset in 0..8 {
+                let value = 0x0123_4567_89ab_cdef >> (64 - n);
+                let field = &mut bytes.view_bits_mut::<Order>()[offset..offset + n];
+                // The raw bits are the field read in the order it's stored
+                BigEndian::store(field, value);
+                let raw: u64 = Order::FieldOrder::load(field);
+                assert_eq!(to_raw_bits::<Order, BigEndian>(value, n, offset), raw);
+                assert_eq!(from_raw_bits::<Order, BigEndian>(raw, n, offset), value);
+
+                LittleEndian::store(field, value);
+                let raw: u64 = Order::FieldOrder::load(field);
+                assert_eq!(to_raw_bits::<Order, LittleEndian>(value, n, offset), raw);
+                assert_eq!(from_raw_bits::<Order, LittleEndian>(raw, n, offset), value);
+            }
+        }
+    }
+
+    #[test]
+    fn test_raw_bits() {
+        check_raw_bits::<Msb0>();
+        check_raw_bits::<Lsb0>();
+    }
+}
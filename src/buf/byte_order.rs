@@ -1,4 +1,4 @@
-use bitvec::field::BitField;
+use bitvec::{order::Msb0, view::BitView};
 use funty::Integral;
 
 use crate::prelude::*;
@@ -7,15 +7,50 @@ use crate::prelude::*;
 /// implementing them in different ways for the different byte orders (Big Endian and Little
 /// Endian).
 pub trait ByteOrder {
-    fn load<O: BitStore, U: Integral>(src: &BitSlice<O>) -> U;
+    /// Loads a value from `src`, in either bit order.
+    fn load<O: BitStore, B: BitOrder, U: Integral>(src: &bitvec::slice::BitSlice<O, B>) -> U;
     fn load_u16(src: &[u8]) -> u16;
     fn load_u24(src: &[u8]) -> u24;
     fn load_u32(src: &[u8]) -> u32;
+    fn load_u40(src: &[u8]) -> u40 {
+        u40::new(Self::load(src[..5].view_bits::<Msb0>()))
+    }
+    fn load_u48(src: &[u8]) -> u48 {
+        u48::new(Self::load(src[..6].view_bits::<Msb0>()))
+    }
+    fn load_u56(src: &[u8]) -> u56 {
+        u56::new(Self::load(src[..7].view_bits::<Msb0>()))
+    }
+    fn load_u64(src: &[u8]) -> u64 {
+        Self::load(src[..8].view_bits::<Msb0>())
+    }
+    fn load_u128(src: &[u8]) -> u128 {
+        Self::load(src[..16].view_bits::<Msb0>())
+    }
 
-    fn store<O: BitStore, U: Integral>(dest: &mut BitSlice<O>, value: U);
+    /// Stores `value` to `dest`, in either bit order.
+    fn store<O: BitStore, B: BitOrder, U: Integral>(
+        dest: &mut bitvec::slice::BitSlice<O, B>,
+        value: U,
+    );
     fn store_u16(dest: &mut [u8], value: u16);
     fn store_u24(dest: &mut [u8], value: u24);
     fn store_u32(dest: &mut [u8], value: u32);
+    fn store_u40(dest: &mut [u8], value: u40) {
+        Self::store(dest[..5].view_bits_mut::<Msb0>(), u64::from(value));
+    }
+    fn store_u48(dest: &mut [u8], value: u48) {
+        Self::store(dest[..6].view_bits_mut::<Msb0>(), u64::from(value));
+    }
+    fn store_u56(dest: &mut [u8], value: u56) {
+        Self::store(dest[..7].view_bits_mut::<Msb0>(), u64::from(value));
+    }
+    fn store_u64(dest: &mut [u8], value: u64) {
+        Self::store(dest[..8].view_bits_mut::<Msb0>(), value);
+    }
+    fn store_u128(dest: &mut [u8], value: u128) {
+        Self::store(dest[..16].view_bits_mut::<Msb0>(), value);
+    }
 }
 
 pub struct BigEndian {}
@@ -25,8 +60,8 @@ pub struct LittleEndian {}
 pub type NetworkOrder = BigEndian;
 
 impl ByteOrder for BigEndian {
-    fn load<O: BitStore, U: Integral>(src: &BitSlice<O>) -> U {
-        src.load_be()
+    fn load<O: BitStore, B: BitOrder, U: Integral>(src: &bitvec::slice::BitSlice<O, B>) -> U {
+        B::load_be(src)
     }
 
     fn load_u16(src: &[u8]) -> u16 {
@@ -48,8 +83,37 @@ impl ByteOrder for BigEndian {
         u32::from_be_bytes(src.try_into().unwrap())
     }
 
-    fn store<O: BitStore, U: Integral>(dest: &mut BitSlice<O>, value: U) {
-        dest.store_be(value);
+    fn load_u40(src: &[u8]) -> u40 {
+        let mut buf = [0u8; 8];
+        buf[3..].copy_from_slice(&src[..5]);
+        u40::new(u64::from_be_bytes(buf))
+    }
+
+    fn load_u48(src: &[u8]) -> u48 {
+        let mut buf = [0u8; 8];
+        buf[2..].copy_from_slice(&src[..6]);
+        u48::new(u64::from_be_bytes(buf))
+    }
+
+    fn load_u56(src: &[u8]) -> u56 {
+        let mut buf = [0u8; 8];
+        buf[1..].copy_from_slice(&src[..7]);
+        u56::new(u64::from_be_bytes(buf))
+    }
+
+    fn load_u64(src: &[u8]) -> u64 {
+        u64::from_be_bytes(src.try_into().unwrap())
+    }
+
+    fn load_u128(src: &[u8]) -> u128 {
+        u128::from_be_bytes(src.try_into().unwrap())
+    }
+
+    fn store<O: BitStore, B: BitOrder, U: Integral>(
+        dest: &mut bitvec::slice::BitSlice<O, B>,
+        value: U,
+    ) {
+        B::store_be(dest, value);
     }
 
     fn store_u16(dest: &mut [u8], value: u16) {
@@ -70,11 +134,34 @@ impl ByteOrder for BigEndian {
         dest[2] = (value >> 8) as u8;
         dest[3] = value as u8;
     }
+
+    fn store_u40(dest: &mut [u8], value: u40) {
+        let value: u64 = value.into();
+        dest[..5].copy_from_slice(&value.to_be_bytes()[3..]);
+    }
+
+    fn store_u48(dest: &mut [u8], value: u48) {
+        let value: u64 = value.into();
+        dest[..6].copy_from_slice(&value.to_be_bytes()[2..]);
+    }
+
+    fn store_u56(dest: &mut [u8], value: u56) {
+        let value: u64 = value.into();
+        dest[..7].copy_from_slice(&value.to_be_bytes()[1..]);
+    }
+
+    fn store_u64(dest: &mut [u8], value: u64) {
+        dest[..8].copy_from_slice(&value.to_be_bytes());
+    }
+
+    fn store_u128(dest: &mut [u8], value: u128) {
+        dest[..16].copy_from_slice(&value.to_be_bytes());
+    }
 }
 
 impl ByteOrder for LittleEndian {
-    fn load<O: BitStore, U: Integral>(src: &BitSlice<O>) -> U {
-        src.load_le()
+    fn load<O: BitStore, B: BitOrder, U: Integral>(src: &bitvec::slice::BitSlice<O, B>) -> U {
+        B::load_le(src)
     }
 
     fn load_u16(src: &[u8]) -> u16 {
@@ -95,8 +182,37 @@ impl ByteOrder for LittleEndian {
         u32::from_le_bytes(src.try_into().unwrap())
     }
 
-    fn store<O: BitStore, U: Integral>(dest: &mut BitSlice<O>, value: U) {
-        dest.store_le(value)
+    fn load_u40(src: &[u8]) -> u40 {
+        let mut buf = [0u8; 8];
+        buf[..5].copy_from_slice(&src[..5]);
+        u40::new(u64::from_le_bytes(buf))
+    }
+
+    fn load_u48(src: &[u8]) -> u48 {
+        let mut buf = [0u8; 8];
+        buf[..6].copy_from_slice(&src[..6]);
+        u48::new(u64::from_le_bytes(buf))
+    }
+
+    fn load_u56(src: &[u8]) -> u56 {
+        let mut buf = [0u8; 8];
+        buf[..7].copy_from_slice(&src[..7]);
+        u56::new(u64::from_le_bytes(buf))
+    }
+
+    fn load_u64(src: &[u8]) -> u64 {
+        u64::from_le_bytes(src.try_into().unwrap())
+    }
+
+    fn load_u128(src: &[u8]) -> u128 {
+        u128::from_le_bytes(src.try_into().unwrap())
+    }
+
+    fn store<O: BitStore, B: BitOrder, U: Integral>(
+        dest: &mut bitvec::slice::BitSlice<O, B>,
+        value: U,
+    ) {
+        B::store_le(dest, value)
     }
 
     fn store_u16(dest: &mut [u8], value: u16) {
@@ -117,6 +233,29 @@ impl ByteOrder for LittleEndian {
         dest[2] = (value >> 16) as u8;
         dest[3] = (value >> 24) as u8;
     }
+
+    fn store_u40(dest: &mut [u8], value: u40) {
+        let value: u64 = value.into();
+        dest[..5].copy_from_slice(&value.to_le_bytes()[..5]);
+    }
+
+    fn store_u48(dest: &mut [u8], value: u48) {
+        let value: u64 = value.into();
+        dest[..6].copy_from_slice(&value.to_le_bytes()[..6]);
+    }
+
+    fn store_u56(dest: &mut [u8], value: u56) {
+        let value: u64 = value.into();
+        dest[..7].copy_from_slice(&value.to_le_bytes()[..7]);
+    }
+
+    fn store_u64(dest: &mut [u8], value: u64) {
+        dest[..8].copy_from_slice(&value.to_le_bytes());
+    }
+
+    fn store_u128(dest: &mut [u8], value: u128) {
+        dest[..16].copy_from_slice(&value.to_le_bytes());
+    }
 }
 
 #[cfg(test)]
@@ -184,4 +323,76 @@ mod tests {
         let read_value: u16 = LittleEndian::load(src);
         assert_eq!(value, read_value);
     }
+
+    #[test]
+    fn test_wide_byte_loads_and_stores() {
+        let mut buf = [0u8; 8];
+        BigEndian::store_u40(&mut buf, u40::new(0x0102030405));
+        assert_eq!(buf[..5], [0x01, 0x02, 0x03, 0x04, 0x05]);
+        assert_eq!(BigEndian::load_u40(&buf[..5]), u40::new(0x0102030405));
+        LittleEndian::store_u40(&mut buf, u40::new(0x0102030405));
+        assert_eq!(buf[..5], [0x05, 0x04, 0x03, 0x02, 0x01]);
+        assert_eq!(LittleEndian::load_u40(&buf[..5]), u40::new(0x0102030405));
+
+        BigEndian::store_u64(&mut buf, 0x0102030405060708);
+        assert_eq!(buf, [0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08]);
+        assert_eq!(LittleEndian::load_u64(&buf), 0x0807060504030201);
+    }
+
+    #[test]
+    fn test_default_wide_loads_and_stores() {
+        // An order which only provides the required methods gets the wide ones from `load` and
+        // `store`
+        struct BitwiseLittleEndian;
+
+        impl ByteOrder for BitwiseLittleEndian {
+            fn load<O: BitStore, B: BitOrder, U: Integral>(
+                src: &bitvec::slice::BitSlice<O, B>,
+            ) -> U {
+                LittleEndian::load(src)
+            }
+            fn load_u16(src: &[u8]) -> u16 {
+                LittleEndian::load_u16(src)
+            }
+            fn load_u24(src: &[u8]) -> u24 {
+                LittleEndian::load_u24(src)
+            }
+            fn load_u32(src: &[u8]) -> u32 {
+                LittleEndian::load_u32(src)
+            }
+            fn store<O: BitStore, B: BitOrder, U: Integral>(
+                dest: &mut bitvec::slice::BitSlice<O, B>,
+                value: U,
+            ) {
+                LittleEndian::store(dest, value)
+            }
+            fn store_u16(dest: &mut [u8], value: u16) {
+                LittleEndian::store_u16(dest, value)
+            }
+            fn store_u24(dest: &mut [u8], value: u24) {
+                LittleEndian::store_u24(dest, value)
+            }
+            fn store_u32(dest: &mut [u8], value: u32) {
+                LittleEndian::store_u32(dest, value)
+            }
+        }
+
+        let mut buf = [0u8; 16];
+        BitwiseLittleEndian::store_u48(&mut buf, u48::new(0x010203040506));
+        assert_eq!(buf[..6], [0x06, 0x05, 0x04, 0x03, 0x02, 0x01]);
+        assert_eq!(
+            BitwiseLittleEndian::load_u48(&buf),
+            LittleEndian::load_u48(&buf)
+        );
+        BitwiseLittleEndian::store_u128(&mut buf, 0x0102030405060708090a0b0c0d0e0f10);
+        assert_eq!(
+            LittleEndian::load_u128(&buf),
+            0x0102030405060708090a0b0c0d0e0f10
+        );
+        BigEndian::store_u56(&mut buf, u56::new(0x01020304050607));
+        assert_eq!(
+            BitwiseLittleEndian::load_u56(&buf),
+            u56::new(0x07060504030201)
+        );
+    }
 }
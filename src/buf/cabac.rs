@@ -0,0 +1,417 @@
+use crate::prelude::*;
+
+/// The range of the least probable symbol, indexed by probability state and by bits 7:6 of the
+/// current range (H.264 table 9-44, HEVC table 9-52).
+#[rustfmt::skip]
+const RANGE_TAB_LPS: [[u8; 4]; 64] = [
+    [128, 176, 208, 240], [128, 167, 197, 227], [128, 158, 187, 216], [123, 150, 178, 205],
+    [116, 142, 169, 195], [111, 135, 160, 185], [105, 128, 152, 175], [100, 122, 144, 166],
+    [95, 116, 137, 158], [90, 110, 130, 150], [85, 104, 123, 142], [81, 99, 117, 135],
+    [77, 94, 111, 128], [73, 89, 105, 122], [69, 85, 100, 116], [66, 80, 95, 110],
+    [62, 76, 90, 104], [59, 72, 86, 99], [56, 69, 81, 94], [53, 65, 77, 89],
+    [51, 62, 73, 85], [48, 59, 69, 80], [46, 56, 66, 76], [43, 53, 63, 72],
+    [41, 50, 59, 69], [39, 48, 56, 65], [37, 45, 54, 62], [35, 43, 51, 59],
+    [33, 41, 48, 56], [32, 39, 46, 53], [30, 37, 43, 50], [29, 35, 41, 48],
+    [27, 33, 39, 45], [26, 31, 37, 43], [24, 30, 35, 41], [23, 28, 33, 39],
+    [22, 27, 32, 37], [21, 26, 30, 35], [20, 24, 29, 33], [19, 23, 27, 31],
+    [18, 22, 26, 30], [17, 21, 25, 28], [16, 20, 23, 27], [15, 19, 22, 25],
+    [14, 18, 21, 24], [14, 17, 20, 23], [13, 16, 19, 22], [12, 15, 18, 21],
+    [12, 14, 17, 20], [11, 14, 16, 19], [11, 13, 15, 18], [10, 12, 15, 17],
+    [10, 12, 14, 16], [9, 11, 13, 15], [9, 11, 12, 14], [8, 10, 12, 14],
+    [8, 9, 11, 13], [7, 9, 11, 12], [7, 9, 10, 12], [7, 8, 10, 11],
+    [6, 8, 9, 11], [6, 7, 9, 10], [6, 7, 8, 9], [2, 2, 2, 2],
+];
+
+/// The next probability state after decoding the least probable symbol (H.264 table 9-45, HEVC
+/// table 9-53).
+#[rustfmt::skip]
+const TRANS_IDX_LPS: [u8; 64] = [
+    0, 0, 1, 2, 2, 4, 4, 5, 6, 7, 8, 9, 9, 11, 11, 12,
+    13, 13, 15, 15, 16, 16, 18, 18, 19, 19, 21, 21, 22, 22, 23, 24,
+    24, 25, 26, 26, 27, 27, 28, 29, 29, 30, 30, 30, 31, 32, 32, 33,
+    33, 33, 34, 34, 35, 35, 35, 36, 36, 36, 37, 37, 37, 38, 38, 63,
+];
+
+/// The next probability state after decoding the most probable symbol (H.264 table 9-45, HEVC
+/// table 9-53).
+#[rustfmt::skip]
+const TRANS_IDX_MPS: [u8; 64] = [
+    1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16,
+    17, 18, 19, 20, 21, 22, 23, 24, 25, 26, 27, 28, 29, 30, 31, 32,
+    33, 34, 35, 36, 37, 38, 39, 40, 41, 42, 43, 44, 45, 46, 47, 48,
+    49, 50, 51, 52, 53, 54, 55, 56, 57, 58, 59, 60, 61, 62, 62, 63,
+];
+
+/// The adaptive probability model of one CABAC context variable: a probability state index
+/// (`pStateIdx`) and the value of the most probable symbol (`valMPS`).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CabacContext {
+    state: u8,
+    mps: bool,
+}
+
+impl CabacContext {
+    /// Creates a context with the given state, which must be less than 64 (63 is reserved for the
+    /// terminating bin and is never reached through adaptation).
+    pub fn new(state: u8, mps: bool) -> Self {
+        assert!(state < 64, "CABAC state ({}) must be less than 64", state);
+        Self { state, mps }
+    }
+
+    /// Initializes a context from the `m` and `n` values in the H.264 context initialization
+    /// tables (clause 9.3.1.1) for the given slice QP.
+    pub fn from_m_n(m: i32, n: i32, slice_qp: i32) -> Self {
+        let pre_ctx_state = (((m * slice_qp.clamp(0, 51)) >> 4) + n).clamp(1, 126);
+        if pre_ctx_state <= 63 {
+            Self::new((63 - pre_ctx_state) as u8, false)
+        } else {
+            Self::new((pre_ctx_state - 64) as u8, true)
+        }
+    }
+
+    /// Initializes a context from an HEVC `initValue` (clause 9.3.2.2) for the given slice QP.
+    pub fn from_init_value(init_value: u8, slice_qp: i32) -> Self {
+        let m = (init_value >> 4) as i32 * 5 - 45;
+        let n = (((init_value & 15) as i32) << 3) - 16;
+        Self::from_m_n(m, n, slice_qp)
+    }
+
+    /// The probability state index (`pStateIdx`).
+    pub fn state(&self) -> u8 {
+        self.state
+    }
+
+    /// The value of the most probable symbol (`valMPS`).
+    pub fn mps(&self) -> bool {
+        self.mps
+    }
+
+    /// The range of the least probable symbol, given the current range.
+    fn lps_range(&self, range: u32) -> u32 {
+        RANGE_TAB_LPS[self.state as usize][((range >> 6) & 3) as usize] as u32
+    }
+
+    /// Moves to the next state after coding `bin`.
+    fn update(&mut self, bin: bool) {
+        if bin == self.mps {
+            self.state = TRANS_IDX_MPS[self.state as usize];
+        } else {
+            if self.state == 0 {
+                self.mps = !self.mps;
+            }
+            self.state = TRANS_IDX_LPS[self.state as usize];
+        }
+    }
+}
+
+/// The CABAC arithmetic decoding engine (H.264 clause 9.3.3.2, HEVC clause 9.3.4.3), reading the
+/// slice data from a `BitBuf`.
+pub struct CabacDecoder<B: BitBuf> {
+    inner: B,
+    /// `codIRange`
+    range: u32,
+    /// `codIOffset`
+    offset: u32,
+}
+
+impl<B: BitBuf> CabacDecoder<B> {
+    /// Initializes the decoding engine, which reads the first 9 bits of `inner`.  `inner` should
+    /// be positioned at the start of the CABAC data, after any `cabac_alignment_one_bit`s.
+    pub fn new(mut inner: B) -> std::io::Result<Self> {
+        let offset = inner.get_bits_as::<u32, BigEndian>(9)?;
+        if offset >= 510 {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                format!("Initial CABAC offset ({}) must be less than 510", offset),
+            ));
+        }
+        Ok(Self {
+            inner,
+            range: 510,
+            offset,
+        })
+    }
+
+    pub fn into_inner(self) -> B {
+        self.inner
+    }
+
+    pub fn get_ref(&self) -> &B {
+        &self.inner
+    }
+
+    /// Decodes a bin using, and then updating, the probability model in `context`.
+    pub fn decode_decision(&mut self, context: &mut CabacContext) -> std::io::Result<bool> {
+        let lps_range = context.lps_range(self.range);
+        self.range -= lps_range;
+        let bin = if self.offset >= self.range {
+            self.offset -= self.range;
+            self.range = lps_range;
+            !context.mps
+        } else {
+            context.mps
+        };
+        context.update(bin);
+        self.renormalize()?;
+        Ok(bin)
+    }
+
+    /// Decodes a bin with equal probabilities, without a context.
+    pub fn decode_bypass(&mut self) -> std::io::Result<bool> {
+        self.offset = (self.offset << 1) | self.inner.get_bool()? as u32;
+        if self.offset >= self.range {
+            self.offset -= self.range;
+            Ok(true)
+        } else {
+            Ok(false)
+        }
+    }
+
+    /// Decodes `n` (at most 32) bypass bins as an unsigned value, first bin in the most
+    /// significant position.
+    pub fn decode_bypass_bits(&mut self, n: usize) -> std::io::Result<u32> {
+        assert!(n <= 32, "Can't decode {} bypass bins into a u32", n);
+        let mut value = 0;
+        for _ in 0..n {
+            value = (value << 1) | self.decode_bypass()? as u32;
+        }
+        Ok(value)
+    }
+
+    /// Decodes the bin which signals the end of a slice (or a PCM block), such as
+    /// `end_of_slice_flag`.  When this returns true the engine has finished, and the last bit
+    /// read was the final bit of the encoder's flush (the `rbsp_stop_one_bit` in H.264).
+    pub fn decode_terminate(&mut self) -> std::io::Result<bool> {
+        self.range -= 2;
+        if self.offset >= self.range {
+            Ok(true)
+        } else {
+            self.renormalize()?;
+            Ok(false)
+        }
+    }
+
+    fn renormalize(&mut self) -> std::io::Result<()> {
+        // The range is 9 bits, so this is the number of doublings it takes to reach at least 256
+        let shift = self.range.leading_zeros() as usize - 23;
+        if shift > 0 {
+            self.range <<= shift;
+            self.offset =
+                (self.offset << shift) | self.inner.get_bits_as::<u32, BigEndian>(shift)?;
+        }
+        Ok(())
+    }
+}
+
+/// The CABAC arithmetic encoding engine (H.264 clause 9.3.4.2), writing to a `BitBufMut`.  Data
+/// written by this can be read back with a [`CabacDecoder`].
+pub struct CabacEncoder<B: BitBufMut> {
+    inner: B,
+    /// `codILow`
+    low: u32,
+    /// `codIRange`
+    range: u32,
+    /// Whether the next bit is the first, which is never written
+    first_bit: bool,
+    bits_outstanding: usize,
+}
+
+impl<B: BitBufMut> CabacEncoder<B> {
+    pub fn new(inner: B) -> Self {
+        Self {
+            inner,
+            low: 0,
+            range: 510,
+            first_bit: true,
+            bits_outstanding: 0,
+        }
+    }
+
+    pub fn into_inner(self) -> B {
+        self.inner
+    }
+
+    pub fn get_ref(&self) -> &B {
+        &self.inner
+    }
+
+    /// Encodes `bin` using, and then updating, the probability model in `context`.
+    pub fn encode_decision(
+        &mut self,
+        context: &mut CabacContext,
+        bin: bool,
+    ) -> std::io::Result<()> {
+        let lps_range = context.lps_range(self.range);
+        self.range -= lps_range;
+        if bin != context.mps {
+            self.low += self.range;
+            self.range = lps_range;
+        }
+        context.update(bin);
+        self.renormalize()
+    }
+
+    /// Encodes a bin with equal probabilities, without a context.
+    pub fn encode_bypass(&mut self, bin: bool) -> std::io::Result<()> {
+        self.low <<= 1;
+        if bin {
+            self.low += self.range;
+        }
+        if self.low >= 1024 {
+            self.low -= 1024;
+            self.put_bit(true)
+        } else if self.low < 512 {
+            self.put_bit(false)
+        } else {
+            self.low -= 512;
+            self.bits_outstanding += 1;
+            Ok(())
+        }
+    }
+
+    /// Encodes the low `n` bits of `value` as bypass bins, most significant first.
+    pub fn encode_bypass_bits(&mut self, n: usize, value: u32) -> std::io::Result<()> {
+        assert!(n <= 32, "Can't encode {} bypass bins from a u32", n);
+        for i in (0..n).rev() {
+            self.encode_bypass((value >> i) & 1 != 0)?;
+        }
+        Ok(())
+    }
+
+    /// Encodes the bin which signals the end of a slice (or a PCM block).  Encoding true flushes
+    /// the engine: the last bit written is 1, and serves as the `rbsp_stop_one_bit` in H.264.
+    pub fn encode_terminate(&mut self, bin: bool) -> std::io::Result<()> {
+        self.range -= 2;
+        if bin {
+            self.low += self.range;
+            self.range = 2;
+            self.renormalize()?;
+            self.put_bit((self.low >> 9) & 1 != 0)?;
+            self.inner
+                .put_bits_from::<u32, BigEndian>(2, ((self.low >> 7) & 3) | 1)
+        } else {
+            self.renormalize()
+        }
+    }
+
+    fn renormalize(&mut self) -> std::io::Result<()> {
+        while self.range < 256 {
+            if self.low < 256 {
+                self.put_bit(false)?;
+            } else if self.low >= 512 {
+                self.low -= 512;
+                self.put_bit(true)?;
+            } else {
+                self.low -= 256;
+                self.bits_outstanding += 1;
+            }
+            self.range <<= 1;
+            self.low <<= 1;
+        }
+        Ok(())
+    }
+
+    fn put_bit(&mut self, bit: bool) -> std::io::Result<()> {
+        if self.first_bit {
+            self.first_bit = false;
+        } else {
+            self.inner.put_bool(bit)?;
+        }
+        while self.bits_outstanding > 0 {
+            self.inner.put_bool(!bit)?;
+            self.bits_outstanding -= 1;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_context_init() {
+        // H.264 mb_type (SI) context 0: m = 20, n = -15
+        let context = CabacContext::from_m_n(20, -15, 26);
+        assert_eq!((context.state(), context.mps()), (46, false));
+        // An HEVC initValue of 154 is the equiprobable state
+        let context = CabacContext::from_init_value(154, 30);
+        assert_eq!((context.state(), context.mps()), (0, true));
+        // The pre-context state is clamped to 1..=126
+        let context = CabacContext::from_m_n(0, 127, 26);
+        assert_eq!((context.state(), context.mps()), (62, true));
+    }
+
+    #[test]
+    fn test_round_trip() {
+        // A simple deterministic pseudo-random sequence, skewed so the contexts adapt
+        let mut seed = 0x1234_5678u32;
+        let mut next = move || {
+            seed ^= seed << 13;
+            seed ^= seed >> 17;
+            seed ^= seed << 5;
+            seed
+        };
+        let bins: Vec<(usize, bool)> = (0..2000)
+            .map(|_| {
+                let value = next();
+                let context = (value % 4) as usize;
+                (context, (value >> 8) % 10 < context as u32 * 3)
+            })
+            .collect();
+        let initial = [
+            CabacContext::from_init_value(154, 26),
+            CabacContext::new(20, true),
+            CabacContext::new(62, false),
+            CabacContext::from_m_n(-13, 106, 30),
+        ];
+
+        let mut encoder = CabacEncoder::new(BitsMut::new());
+        let mut contexts = initial;
+        for (i, &(context, bin)) in bins.iter().enumerate() {
+            encoder
+                .encode_decision(&mut contexts[context], bin)
+                .unwrap();
+            if i % 100 == 0 {
+                encoder.encode_bypass_bits(7, i as u32 % 128).unwrap();
+                encoder.encode_terminate(false).unwrap();
+            }
+        }
+        encoder.encode_terminate(true).unwrap();
+        let mut bits_mut = encoder.into_inner();
+        bits_mut.put_byte_alignment().unwrap();
+        let encoded_len = bits_mut.len_bits();
+        // Make sure nothing past the end of the CABAC data is read
+        bits_mut.put_u8(0xff).unwrap();
+
+        let mut decoder = CabacDecoder::new(bits_mut.freeze()).unwrap();
+        let mut contexts = initial;
+        for (i, &(context, bin)) in bins.iter().enumerate() {
+            assert_eq!(
+                decoder.decode_decision(&mut contexts[context]).unwrap(),
+                bin,
+                "bin {}",
+                i
+            );
+            if i % 100 == 0 {
+                assert_eq!(decoder.decode_bypass_bits(7).unwrap(), i as u32 % 128);
+                assert!(!decoder.decode_terminate().unwrap());
+            }
+        }
+        assert!(decoder.decode_terminate().unwrap());
+        let mut bits = decoder.into_inner();
+        // The last bit read was the stop bit, so only alignment and the trailing byte remain
+        bits.byte_alignment().unwrap();
+        assert_eq!(bits.remaining_bits(), 8);
+        assert!(encoded_len < 2000);
+    }
+
+    #[test]
+    fn test_decode_errors() {
+        assert!(CabacDecoder::new(Bits::from_static_bytes(&[0xff, 0x00])).is_err());
+        // Running out of data is an error rather than a panic
+        let mut decoder = CabacDecoder::new(Bits::from_static_bytes(&[0x00, 0x00])).unwrap();
+        let result = (0..100).try_for_each(|_| decoder.decode_bypass().map(|_| ()));
+        assert!(result.is_err());
+    }
+}
@@ -1,4 +1,4 @@
-use crate::prelude::BitBufMut;
+use crate::prelude::{BitBufMut, BitOrder};
 
 use super::bit_buf::BitBuf;
 
@@ -37,10 +37,11 @@ impl<T, U> Chain<T, U> {
     }
 }
 
-impl<T, U> BitBuf for Chain<T, U>
+impl<Order, T, U> BitBuf<Order> for Chain<T, U>
 where
-    T: BitBuf,
-    U: BitBuf,
+    Order: BitOrder,
+    T: BitBuf<Order>,
+    U: BitBuf<Order>,
 {
     fn advance_bits(&mut self, mut count: usize) {
         let a_rem = self.a.remaining_bits();
@@ -65,7 +66,7 @@ where
             .saturating_add(self.b.remaining_bits())
     }
 
-    fn chunk_bits(&self) -> &crate::prelude::BitSlice {
+    fn chunk_bits(&self) -> &bitvec::slice::BitSlice<u8, Order> {
         if self.a.has_remaining_bits() {
             self.a.chunk_bits()
         } else {
@@ -86,10 +87,11 @@ where
     }
 }
 
-impl<T, U> BitBufMut for Chain<T, U>
+impl<Order, T, U> BitBufMut<Order> for Chain<T, U>
 where
-    T: BitBufMut,
-    U: BitBufMut,
+    Order: BitOrder,
+    T: BitBufMut<Order>,
+    U: BitBufMut<Order>,
 {
     fn advance_mut_bits(&mut self, mut count: usize) {
         let a_rem = self.a.remaining_mut_bits();
@@ -108,7 +110,7 @@ where
         self.b.advance_mut_bits(count);
     }
 
-    fn chunk_mut_bits(&mut self) -> &mut crate::prelude::BitSlice {
+    fn chunk_mut_bits(&mut self) -> &mut bitvec::slice::BitSlice<u8, Order> {
         if self.a.has_remaining_mut_bits() {
             self.a.chunk_mut_bits()
         } else {
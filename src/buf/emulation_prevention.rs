@@ -0,0 +1,420 @@
+use std::sync::OnceLock;
+
+use bytes::buf::UninitSlice;
+
+use crate::prelude::*;
+
+/// The byte inserted after two zero bytes to stop the payload of a NAL unit from emulating a start
+/// code.
+const EMULATION_PREVENTION_BYTE: u8 = 0x03;
+
+/// Returns the offsets of the emulation-prevention bytes in `bytes`, which must start at the
+/// beginning of the NAL unit payload or just after an emulation-prevention byte.  The bytes are
+/// only scanned as far as the offsets are consumed.
+fn epb_offsets(bytes: &[u8]) -> impl Iterator<Item = usize> + '_ {
+    let mut zero_count = 0;
+    bytes.iter().enumerate().filter_map(move |(offset, &byte)| {
+        if zero_count >= 2 && byte == EMULATION_PREVENTION_BYTE {
+            zero_count = 0;
+            Some(offset)
+        } else {
+            if byte == 0 {
+                zero_count += 1;
+            } else {
+                zero_count = 0;
+            }
+            None
+        }
+    })
+}
+
+/// A `BitBuf` adaptor over the raw bytes of an H.264/H.265 NAL unit which skips
+/// emulation-prevention bytes (the `0x03` in `0x000003`) as they're reached, so the payload can be
+/// read without first copying it into an unescaped buffer.  Each emulation-prevention byte is
+/// found by scanning ahead from the previous one as reading reaches it.
+///
+/// Positions are available in both raw-byte coordinates (offsets into the NAL unit as stored,
+/// which is what stream analyzers show) and unescaped-bit coordinates (offsets into the RBSP, which
+/// is what the syntax tables use).
+pub struct EmulationPreventionReader {
+    /// All of the raw bytes, which emulation-prevention bytes are looked for in
+    raw: Bits,
+    inner: Bits,
+    /// The offset, in raw bytes from where the reader started, of the next emulation-prevention
+    /// byte to be skipped
+    next_epb: Option<usize>,
+    skipped_bytes: usize,
+    /// The total number of emulation-prevention bytes, which is only counted if the number of
+    /// remaining bits is asked for
+    total_epbs: OnceLock<usize>,
+    raw_bit_position: usize,
+}
+
+impl EmulationPreventionReader {
+    /// Creates a reader over `inner`.  Returns an error if `inner` isn't byte-aligned.
+    pub fn new(inner: Bits) -> std::io::Result<Self> {
+        if !inner.byte_aligned() {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!(
+                    "EmulationPreventionReader requires byte-aligned data ({} bits)",
+                    inner.remaining_bits()
+                ),
+            ));
+        }
+        let next_epb = epb_offsets(inner.chunk_bytes()).next();
+        Ok(Self {
+            raw: inner.clone(),
+            inner,
+            next_epb,
+            skipped_bytes: 0,
+            total_epbs: OnceLock::new(),
+            raw_bit_position: 0,
+        })
+    }
+
+    pub fn into_inner(self) -> Bits {
+        self.inner
+    }
+
+    pub fn get_ref(&self) -> &Bits {
+        &self.inner
+    }
+
+    /// The number of raw bytes (including emulation-prevention bytes) which have been fully or
+    /// partially consumed.
+    pub fn raw_byte_position(&self) -> usize {
+        self.raw_bit_position / 8
+    }
+
+    /// The number of unescaped bits which have been consumed.
+    pub fn unescaped_bit_position(&self) -> usize {
+        self.raw_bit_position - self.skipped_bytes * 8
+    }
+
+    /// The number of emulation-prevention bytes which have been skipped so far.
+    pub fn skipped_bytes(&self) -> usize {
+        self.skipped_bytes
+    }
+
+    /// Adds the current position, in both raw-byte and unescaped-bit coordinates, to `error`.
+    /// Useful for reporting errors from reads made through this reader.
+    pub fn with_position(&self, error: std::io::Error) -> std::io::Error {
+        std::io::Error::new(
+            error.kind(),
+            format!(
+                "At raw byte {} (bit {} within it), unescaped bit {}: {}",
+                self.raw_byte_position(),
+                self.raw_bit_position % 8,
+                self.unescaped_bit_position(),
+                error
+            ),
+        )
+    }
+
+    /// The number of bits which can be read before the next emulation-prevention byte.
+    fn bits_before_epb(&self) -> usize {
+        match self.next_epb {
+            Some(offset) => offset * 8 - self.raw_bit_position,
+            None => self.inner.remaining_bits(),
+        }
+    }
+
+    /// Skips the emulation-prevention byte the reader has reached, and looks for the next one.
+    fn skip_epb(&mut self, offset: usize) {
+        self.inner.advance_bits(8);
+        self.raw_bit_position += 8;
+        self.skipped_bytes += 1;
+        self.next_epb = epb_offsets(&self.raw.chunk_bytes()[offset + 1..])
+            .next()
+            .map(|next| offset + 1 + next);
+    }
+}
+
+impl BitBuf for EmulationPreventionReader {
+    fn advance_bits(&mut self, mut count: usize) {
+        loop {
+            // Emulation-prevention bytes are skipped as soon as they're reached, so there are no
+            // bits before the next one only at the end
+            let step = count.min(self.bits_before_epb());
+            assert!(
+                step > 0 || count == 0,
+                "advance past end of EmulationPreventionReader"
+            );
+            self.inner.advance_bits(step);
+            self.raw_bit_position += step;
+            count -= step;
+            // Skip the emulation-prevention byte as soon as it's reached, so chunks never start
+            // with one
+            if let Some(offset) = self.next_epb.filter(|_| self.bits_before_epb() == 0) {
+                self.skip_epb(offset);
+            }
+            if count == 0 {
+                break;
+            }
+        }
+    }
+
+    fn remaining_bits(&self) -> usize {
+        let total_epbs = *self
+            .total_epbs
+            .get_or_init(|| epb_offsets(self.raw.chunk_bytes()).count());
+        self.inner.remaining_bits() - (total_epbs - self.skipped_bytes) * 8
+    }
+
+    fn chunk_bits(&self) -> &BitSlice {
+        &self.inner.chunk_bits()[..self.bits_before_epb()]
+    }
+
+    fn chunk_bytes(&self) -> &[u8] {
+        assert!(self.byte_aligned());
+        &self.inner.chunk_bytes()[..self.bits_before_epb() / 8]
+    }
+
+    fn byte_aligned(&self) -> bool {
+        // Emulation-prevention bytes are whole bytes, so skipping them doesn't change alignment
+        self.inner.byte_aligned()
+    }
+}
+
+/// A `BitBufMut` adaptor which inserts emulation-prevention bytes into the bytes written to an
+/// underlying buffer, turning an RBSP into the payload of an H.264/H.265 NAL unit.  Written bits
+/// are staged until they make up a whole byte, so [`EmulationPreventionWriter::finish`] must be
+/// called once the RBSP is complete.
+///
+/// `remaining_mut_bits` assumes the worst case (an emulation-prevention byte for every two bytes
+/// written), so writes which fit will never overflow the underlying buffer.
+pub struct EmulationPreventionWriter<T> {
+    inner: T,
+    staging: [u8; 8],
+    staged_bits: usize,
+    /// The number of consecutive zero bytes written since the last non-zero or
+    /// emulation-prevention byte
+    zero_count: usize,
+    raw_bytes_written: usize,
+    inserted_bytes: usize,
+}
+
+impl<T: BitBufMut> EmulationPreventionWriter<T> {
+    pub fn new(inner: T) -> Self {
+        Self {
+            inner,
+            staging: [0; 8],
+            staged_bits: 0,
+            zero_count: 0,
+            raw_bytes_written: 0,
+            inserted_bytes: 0,
+        }
+    }
+
+    pub fn get_ref(&self) -> &T {
+        &self.inner
+    }
+
+    /// The number of raw bytes (including emulation-prevention bytes) written to the underlying
+    /// buffer.
+    pub fn raw_byte_position(&self) -> usize {
+        self.raw_bytes_written
+    }
+
+    /// The number of unescaped bits written, including any which are still staged.
+    pub fn unescaped_bit_position(&self) -> usize {
+        (self.raw_bytes_written - self.inserted_bytes) * 8 + self.staged_bits
+    }
+
+    /// The number of emulation-prevention bytes inserted so far.
+    pub fn inserted_bytes(&self) -> usize {
+        self.inserted_bytes
+    }
+
+    /// Finishes writing and returns the underlying buffer.  If the last byte written was zero
+    /// (which can only happen when the RBSP ends with a `cabac_zero_word`) a final
+    /// emulation-prevention byte is appended.
+    ///
+    /// Returns an error if the bits written don't end on a byte boundary.
+    pub fn finish(mut self) -> std::io::Result<T> {
+        if self.staged_bits != 0 {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!(
+                    "Written data ends mid-byte at unescaped bit {}",
+                    self.unescaped_bit_position()
+                ),
+            ));
+        }
+        if self.zero_count > 0 {
+            self.inner.put_u8(EMULATION_PREVENTION_BYTE)?;
+        }
+        Ok(self.inner)
+    }
+
+    fn write_byte(&mut self, byte: u8) {
+        if self.zero_count >= 2 && byte <= EMULATION_PREVENTION_BYTE {
+            self.put_raw_byte(EMULATION_PREVENTION_BYTE);
+            self.inserted_bytes += 1;
+            self.zero_count = 0;
+        }
+        self.put_raw_byte(byte);
+        if byte == 0 {
+            self.zero_count += 1;
+        } else {
+            self.zero_count = 0;
+        }
+    }
+
+    fn put_raw_byte(&mut self, byte: u8) {
+        self.inner
+            .put_u8(byte)
+            .expect("Space is reserved by remaining_mut_bits");
+        self.raw_bytes_written += 1;
+    }
+}
+
+impl<T: BitBufMut> BitBufMut for EmulationPreventionWriter<T> {
+    fn advance_mut_bits(&mut self, count: usize) {
+        assert!(
+            self.staged_bits + count <= self.staging.len() * 8,
+            "advance_mut_bits past end of chunk"
+        );
+        self.staged_bits += count;
+        let whole_bytes = self.staged_bits / 8;
+        for i in 0..whole_bytes {
+            self.write_byte(self.staging[i]);
+        }
+        // Move any partial byte to the front
+        if self.staged_bits % 8 != 0 {
+            self.staging[0] = self.staging[whole_bytes];
+        }
+        self.staged_bits %= 8;
+    }
+
+    fn chunk_mut_bits(&mut self) -> &mut BitSlice {
+        &mut BitSlice::from_slice_mut(&mut self.staging)[self.staged_bits..]
+    }
+
+    fn chunk_mut_bytes(&mut self) -> &mut UninitSlice {
+        assert!(self.byte_aligned_mut());
+        UninitSlice::new(&mut self.staging)
+    }
+
+    fn remaining_mut_bits(&self) -> usize {
+        // Leave room for an emulation-prevention byte after every two bytes, and for the one which
+        // finish may append
+        (self.inner.remaining_mut_bits().saturating_sub(8) / 3 * 2).saturating_sub(self.staged_bits)
+    }
+
+    fn byte_aligned_mut(&self) -> bool {
+        self.staged_bits == 0 && self.inner.byte_aligned_mut()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ESCAPED: [u8; 12] = [
+        0x25, 0x00, 0x00, 0x03, 0x01, 0x00, 0x00, 0x03, 0x00, 0x00, 0x03, 0x02,
+    ];
+    const UNESCAPED: [u8; 9] = [0x25, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x02];
+
+    #[test]
+    fn test_reader() {
+        let mut reader = EmulationPreventionReader::new(Bits::from_static_bytes(&ESCAPED)).unwrap();
+        assert_eq!(reader.remaining_bytes(), UNESCAPED.len());
+        assert_eq!(reader.get_u8().unwrap(), 0x25);
+        // Unaligned reads which span emulation-prevention bytes
+        assert_eq!(reader.get_u4().unwrap(), u4::new(0));
+        assert_eq!(reader.get_u32::<BigEndian>().unwrap(), 0x0000_1000);
+        assert_eq!(reader.skipped_bytes(), 1);
+        assert_eq!(reader.raw_byte_position(), 6);
+        assert_eq!(reader.unescaped_bit_position(), 44);
+
+        let mut rest = [0u8; 3];
+        reader.get_u4().unwrap();
+        reader.try_copy_to_slice_bytes(&mut rest).unwrap();
+        assert_eq!(rest, UNESCAPED[6..]);
+        assert_eq!(reader.skipped_bytes(), 3);
+        assert!(!reader.has_remaining_bits());
+
+        let error = reader.get_u8().unwrap_err();
+        let error = reader.with_position(error);
+        assert!(error
+            .to_string()
+            .starts_with("At raw byte 12 (bit 0 within it), unescaped bit 72"));
+    }
+
+    #[test]
+    fn test_reader_reset_zero_count() {
+        // The zero count restarts after an emulation-prevention byte, so the second 0x03 here is
+        // payload
+        let mut reader =
+            EmulationPreventionReader::new(Bits::from_static_bytes(&[0x00, 0x00, 0x03, 0x03]))
+                .unwrap();
+        assert_eq!(reader.get_u24::<BigEndian>().unwrap(), u24::new(0x000003));
+        assert!(!reader.has_remaining_bits());
+    }
+
+    #[test]
+    fn test_reader_without_remaining_bits() {
+        // Reading through chunks alone never needs the total number of emulation-prevention bytes
+        let mut reader = EmulationPreventionReader::new(Bits::from_static_bytes(&ESCAPED)).unwrap();
+        let mut unescaped = Vec::new();
+        while !reader.chunk_bits().is_empty() {
+            let chunk = reader.chunk_bytes();
+            unescaped.extend_from_slice(chunk);
+            let len = chunk.len();
+            reader.advance_bytes(len);
+        }
+        assert_eq!(unescaped, UNESCAPED);
+        assert!(reader.total_epbs.get().is_none());
+        assert_eq!(reader.skipped_bytes(), 3);
+        assert_eq!(reader.remaining_bits(), 0);
+    }
+
+    #[test]
+    fn test_reader_unaligned_input() {
+        let bits = Bits::from_static_bytes(&ESCAPED).slice_bits(0..20);
+        let error = EmulationPreventionReader::new(bits).err().unwrap();
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidInput);
+    }
+
+    #[test]
+    fn test_writer() {
+        let mut writer = EmulationPreventionWriter::new(BitsMut::new());
+        writer.put_u8(0x25).unwrap();
+        writer.put_u4(u4::new(0)).unwrap();
+        writer.put_u32::<BigEndian>(0x0000_1000).unwrap();
+        writer.put_u4(u4::new(0)).unwrap();
+        writer.try_put_slice_bytes(&UNESCAPED[6..]).unwrap();
+        assert_eq!(writer.inserted_bytes(), 3);
+        assert_eq!(writer.unescaped_bit_position(), 72);
+        assert_eq!(writer.raw_byte_position(), 12);
+        let bits_mut = writer.finish().unwrap();
+        assert_eq!(bits_mut.freeze(), Bits::from_static_bytes(&ESCAPED));
+
+        // A trailing zero byte gets a final emulation-prevention byte
+        let mut writer = EmulationPreventionWriter::new(BitsMut::new());
+        writer.put_u16::<BigEndian>(0x8000).unwrap();
+        let bits_mut = writer.finish().unwrap();
+        assert_eq!(
+            bits_mut.freeze(),
+            Bits::from_static_bytes(&[0x80, 0x00, 0x03])
+        );
+
+        let mut writer = EmulationPreventionWriter::new(BitsMut::new());
+        writer.put_u3(u3::new(1)).unwrap();
+        assert!(writer.finish().is_err());
+    }
+
+    #[test]
+    fn test_writer_capacity() {
+        let mut buf = [0u8; 7];
+        let mut writer = EmulationPreventionWriter::new(&mut buf[..]);
+        // 7 bytes is enough for 4 bytes of payload in the worst case
+        assert_eq!(writer.remaining_mut_bytes(), 4);
+        writer.put_u32::<BigEndian>(0).unwrap();
+        assert!(writer.put_u8(0).is_err());
+        writer.finish().unwrap();
+        assert_eq!(buf, [0x00, 0x00, 0x03, 0x00, 0x00, 0x03, 0x00]);
+    }
+}
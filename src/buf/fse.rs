@@ -0,0 +1,777 @@
+use crate::prelude::*;
+
+use super::reverse_bits::{ReverseBitReader, ReverseBitWriter};
+
+/// The smallest accuracy log an FSE table can be built with, as in zstd.  The spread step isn't
+/// coprime with some smaller table sizes (at log 3 it's the size itself), so symbols would pile
+/// into the same cells.
+pub const FSE_MIN_ACCURACY_LOG: u8 = 5;
+
+/// The largest accuracy log an FSE table can be built with.
+pub const FSE_MAX_ACCURACY_LOG: u8 = 15;
+
+/// Zstandard's predefined literal length distribution (RFC 8878 section 3.1.1.3.2.2.1), with an
+/// accuracy log of 6.
+pub const LITERALS_LENGTH_DEFAULT_DISTRIBUTION: [i16; 36] = [
+    4, 3, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 1, 1, 1, 2, 2, 2, 2, 2, 2, 2, 2, 2, 3, 2, 1, 1, 1, 1, 1,
+    -1, -1, -1, -1,
+];
+
+/// Zstandard's predefined match length distribution, with an accuracy log of 6.
+pub const MATCH_LENGTH_DEFAULT_DISTRIBUTION: [i16; 53] = [
+    1, 4, 3, 2, 2, 2, 2, 2, 2, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1,
+    1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, -1, -1, -1, -1, -1, -1, -1,
+];
+
+/// Zstandard's predefined offset code distribution, with an accuracy log of 5.
+pub const OFFSET_CODE_DEFAULT_DISTRIBUTION: [i16; 29] = [
+    1, 1, 1, 1, 1, 1, 2, 2, 2, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, -1, -1, -1, -1, -1,
+];
+
+#[derive(Clone, Copy, Debug, Default)]
+struct Cell {
+    symbol: u8,
+    num_bits: u8,
+    baseline: u16,
+}
+
+/// A finite state entropy (tANS) table, built from normalized symbol counts as in Zstandard (RFC
+/// 8878 section 4.1).  The same table drives both [`FseDecoder`] and [`FseEncoder`].
+#[derive(Clone, Debug)]
+pub struct FseTable {
+    accuracy_log: u8,
+    /// The decoding table, indexed by state
+    cells: Vec<Cell>,
+    /// For each symbol, the states which decode to it, sorted by baseline.  Encoding looks up the
+    /// one whose range of next states contains the current state.
+    symbol_states: Vec<Vec<u16>>,
+}
+
+impl FseTable {
+    /// Builds a table from the normalized count of each symbol, where -1 means "less than 1" (the
+    /// symbol gets a single state with a full-width update).  The counts (with -1 counting as 1)
+    /// must add up to `1 << accuracy_log`.
+    pub fn from_normalized_counts(counts: &[i16], accuracy_log: u8) -> std::io::Result<Self> {
+        if accuracy_log < FSE_MIN_ACCURACY_LOG {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                format!(
+                    "Accuracy log ({}) is smaller than the minimum ({})",
+                    accuracy_log, FSE_MIN_ACCURACY_LOG
+                ),
+            ));
+        }
+        if accuracy_log > FSE_MAX_ACCURACY_LOG {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                format!(
+                    "Accuracy log ({}) is larger than the maximum ({})",
+                    accuracy_log, FSE_MAX_ACCURACY_LOG
+                ),
+            ));
+        }
+        if counts.len() > 256 {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                format!("Too many symbols ({})", counts.len()),
+            ));
+        }
+        let table_size = 1usize << accuracy_log;
+        let total: i64 = counts
+            .iter()
+            .map(|&count| if count == -1 { 1 } else { count as i64 })
+            .sum();
+        if counts.iter().any(|&count| count < -1) || total != table_size as i64 {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                format!(
+                    "Normalized counts add up to {} rather than {}",
+                    total, table_size
+                ),
+            ));
+        }
+
+        // "Less than 1" symbols take the cells at the end of the table, and the rest are spread
+        // over the remaining cells
+        let mut cells = vec![Cell::default(); table_size];
+        let mut high_threshold = table_size - 1;
+        for (symbol, &count) in counts.iter().enumerate() {
+            if count == -1 {
+                cells[high_threshold].symbol = symbol as u8;
+                high_threshold = high_threshold.wrapping_sub(1);
+            }
+        }
+        let step = (table_size >> 1) + (table_size >> 3) + 3;
+        let mask = table_size - 1;
+        let mut position = 0;
+        for (symbol, &count) in counts.iter().enumerate() {
+            for _ in 0..count.max(0) {
+                cells[position].symbol = symbol as u8;
+                position = (position + step) & mask;
+                while position > high_threshold {
+                    position = (position + step) & mask;
+                }
+            }
+        }
+        if position != 0 {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "Normalized counts don't spread over the table",
+            ));
+        }
+
+        let mut next_state: Vec<usize> = counts
+            .iter()
+            .map(|&count| count.unsigned_abs() as usize)
+            .collect();
+        let mut symbol_states = vec![Vec::new(); counts.len()];
+        for (state, cell) in cells.iter_mut().enumerate() {
+            let symbol = cell.symbol as usize;
+            let next = next_state[symbol];
+            next_state[symbol] += 1;
+            let num_bits = accuracy_log as u32 - next.ilog2();
+            cell.num_bits = num_bits as u8;
+            cell.baseline = ((next << num_bits) - table_size) as u16;
+            symbol_states[symbol].push(state as u16);
+        }
+        for states in &mut symbol_states {
+            states.sort_by_key(|&state| cells[state as usize].baseline);
+        }
+        Ok(Self {
+            accuracy_log,
+            cells,
+            symbol_states,
+        })
+    }
+
+    /// Reads a table description (RFC 8878 section 4.1.1) with at most `max_symbol + 1` symbols
+    /// and an accuracy log of at most `max_accuracy_log`, and builds the table.  The description
+    /// is a little-endian bitstream padded to a whole byte, so the padding is consumed too.
+    pub fn read<B: BitBuf<Lsb0> + ?Sized>(
+        buf: &mut B,
+        max_symbol: usize,
+        max_accuracy_log: u8,
+    ) -> std::io::Result<Self> {
+        let start_bits = buf.remaining_bits();
+        let accuracy_log = buf.get_bits_as::<u8, LittleEndian>(4)? + 5;
+        if accuracy_log > max_accuracy_log {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                format!(
+                    "Accuracy log ({}) is larger than the maximum ({})",
+                    accuracy_log, max_accuracy_log
+                ),
+            ));
+        }
+        let mut counts = Vec::new();
+        let mut remaining = (1i32 << accuracy_log) + 1;
+        let mut threshold = 1i32 << accuracy_log;
+        let mut num_bits = accuracy_log as usize + 1;
+        while remaining > 1 {
+            if counts.len() > max_symbol {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::InvalidData,
+                    format!("More than {} symbols in FSE table", max_symbol + 1),
+                ));
+            }
+            let max = 2 * threshold - 1 - remaining;
+            let mut value = buf.get_bits_as::<u32, LittleEndian>(num_bits - 1)? as i32;
+            if value >= max {
+                value |= (buf.get_bits_as::<u32, LittleEndian>(1)? as i32) << (num_bits - 1);
+                if value >= threshold {
+                    value -= max;
+                }
+            }
+            let count = value - 1;
+            remaining -= count.abs();
+            counts.push(count as i16);
+            if count == 0 {
+                // A zero count is followed by 2-bit repeat flags giving more zero counts
+                loop {
+                    let repeat = buf.get_bits_as::<u8, LittleEndian>(2)?;
+                    counts.extend(std::iter::repeat_n(0, repeat as usize));
+                    if repeat != 3 {
+                        break;
+                    }
+                }
+            }
+            while remaining < threshold && threshold > 1 {
+                num_bits -= 1;
+                threshold >>= 1;
+            }
+        }
+        if remaining != 1 || counts.len() > max_symbol + 1 {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "Invalid FSE table description",
+            ));
+        }
+        let padding = (start_bits - buf.remaining_bits()).next_multiple_of(8)
+            - (start_bits - buf.remaining_bits());
+        buf.get_bits_as::<u8, LittleEndian>(padding)?;
+        Self::from_normalized_counts(&counts, accuracy_log)
+    }
+
+    pub fn accuracy_log(&self) -> u8 {
+        self.accuracy_log
+    }
+
+    /// The number of symbols the table was built with, including those with a count of zero.
+    pub fn num_symbols(&self) -> usize {
+        self.symbol_states.len()
+    }
+}
+
+/// Decodes symbols with an [`FseTable`] from a [`ReverseBitReader`].
+#[derive(Clone, Copy, Debug)]
+pub struct FseDecoder<'a> {
+    table: &'a FseTable,
+    state: usize,
+}
+
+impl<'a> FseDecoder<'a> {
+    /// Creates a decoder, reading its initial state from `reader`.
+    pub fn new(table: &'a FseTable, reader: &mut ReverseBitReader) -> std::io::Result<Self> {
+        let state = reader.get_bits(table.accuracy_log as usize)? as usize;
+        Ok(Self { table, state })
+    }
+
+    /// The symbol for the current state.
+    pub fn peek_symbol(&self) -> u8 {
+        self.table.cells[self.state].symbol
+    }
+
+    /// Moves to the next state, reading its low bits from `reader`.  Zstandard doesn't update the
+    /// states after the last symbol of a stream, so this is separate from `peek_symbol`.
+    pub fn update_state(&mut self, reader: &mut ReverseBitReader) -> std::io::Result<()> {
+        let cell = self.table.cells[self.state];
+        self.state = cell.baseline as usize + reader.get_bits(cell.num_bits as usize)? as usize;
+        Ok(())
+    }
+
+    /// Returns the symbol for the current state and moves to the next state.
+    pub fn decode_symbol(&mut self, reader: &mut ReverseBitReader) -> std::io::Result<u8> {
+        let symbol = self.peek_symbol();
+        self.update_state(reader)?;
+        Ok(symbol)
+    }
+}
+
+/// Encodes symbols with an [`FseTable`] to a [`ReverseBitWriter`].  Symbols are encoded in the
+/// reverse of the order they're decoded in: the encoder starts with the last symbol to be
+/// decoded, and [`FseEncoder::finish`] writes the state the decoder starts from.
+#[derive(Clone, Copy, Debug)]
+pub struct FseEncoder<'a> {
+    table: &'a FseTable,
+    state: usize,
+}
+
+impl<'a> FseEncoder<'a> {
+    /// Creates an encoder whose state decodes to `last_symbol`.  This writes nothing, matching a
+    /// decoder which doesn't call `update_state` after the last symbol.
+    pub fn new(table: &'a FseTable, last_symbol: u8) -> std::io::Result<Self> {
+        let state = *table
+            .symbol_states
+            .get(last_symbol as usize)
+            .and_then(|states| states.first())
+            .ok_or_else(|| missing_symbol(last_symbol))?;
+        Ok(Self {
+            table,
+            state: state as usize,
+        })
+    }
+
+    /// Encodes `symbol`, which is decoded just before the symbols encoded so far.
+    pub fn encode_symbol<B: BitBufMut>(
+        &mut self,
+        writer: &mut ReverseBitWriter<B>,
+        symbol: u8,
+    ) -> std::io::Result<()> {
+        let states = self
+            .table
+            .symbol_states
+            .get(symbol as usize)
+            .filter(|states| !states.is_empty())
+            .ok_or_else(|| missing_symbol(symbol))?;
+        // The states for a symbol split the range of next states between them, so the last one
+        // whose baseline isn't past the current state is the one to move to
+        let index = states.partition_point(|&state| {
+            self.table.cells[state as usize].baseline as usize <= self.state
+        }) - 1;
+        let new_state = states[index] as usize;
+        let cell = self.table.cells[new_state];
+        writer.put_bits(
+            cell.num_bits as usize,
+            (self.state - cell.baseline as usize) as u64,
+        )?;
+        self.state = new_state;
+        Ok(())
+    }
+
+    /// Writes the final state, which the decoder reads first.
+    pub fn finish<B: BitBufMut>(self, writer: &mut ReverseBitWriter<B>) -> std::io::Result<()> {
+        writer.put_bits(self.table.accuracy_log as usize, self.state as u64)
+    }
+}
+
+/// The longest code a Zstandard literals Huffman table can have (RFC 8878 section 4.2.1).
+pub const ZSTD_HUFFMAN_MAX_BITS: u8 = 11;
+
+/// The largest accuracy log of the FSE table which compresses Huffman weights.
+const ZSTD_HUFFMAN_WEIGHTS_MAX_ACCURACY_LOG: u8 = 6;
+
+/// A Zstandard literals Huffman table (RFC 8878 section 4.2), built from the weight of each
+/// symbol.  Codes are read from a [`ReverseBitReader`] by looking up the next `max_bits` bits.
+#[derive(Clone, Debug)]
+pub struct ZstdHuffmanTable {
+    max_bits: u8,
+    /// The (symbol, code length) for each possible value of the next `max_bits` bits
+    entries: Vec<(u8, u8)>,
+}
+
+impl ZstdHuffmanTable {
+    /// Builds a table from the weights of every symbol but the last, whose weight is implied by
+    /// the others.  A weight of 0 means the symbol isn't used, and a weight of `w` gives a code of
+    /// `max_bits + 1 - w` bits.
+    pub fn from_weights(weights: &[u8]) -> std::io::Result<Self> {
+        if weights.len() > 255 {
+            return Err(invalid_weights(format!(
+                "Too many Huffman weights ({})",
+                weights.len()
+            )));
+        }
+        if let Some(&weight) = weights.iter().find(|&&w| w > ZSTD_HUFFMAN_MAX_BITS) {
+            return Err(invalid_weights(format!(
+                "Huffman weight ({}) is larger than the maximum ({})",
+                weight, ZSTD_HUFFMAN_MAX_BITS
+            )));
+        }
+        let weight_total: u32 = weights
+            .iter()
+            .filter(|&&w| w > 0)
+            .map(|&w| 1 << (w - 1))
+            .sum();
+        if weight_total == 0 {
+            return Err(invalid_weights("Huffman weights are all zero".to_string()));
+        }
+        // The last weight brings the total up to the next power of two
+        let max_bits = weight_total.ilog2() + 1;
+        let rest = (1 << max_bits) - weight_total;
+        if max_bits > ZSTD_HUFFMAN_MAX_BITS as u32 || !rest.is_power_of_two() {
+            return Err(invalid_weights(format!(
+                "Huffman weights add up to {}, which can't be completed",
+                weight_total
+            )));
+        }
+        let mut weights = weights.to_vec();
+        weights.push(rest.ilog2() as u8 + 1);
+
+        // Longer codes come first, and codes of the same length are in symbol order
+        let mut entries = Vec::with_capacity(1 << max_bits);
+        for weight in 1..=max_bits as u8 {
+            for (symbol, _) in weights.iter().enumerate().filter(|&(_, &w)| w == weight) {
+                let num_bits = max_bits as u8 + 1 - weight;
+                entries.extend(std::iter::repeat_n(
+                    (symbol as u8, num_bits),
+                    1 << (weight - 1),
+                ));
+            }
+        }
+        Ok(Self {
+            max_bits: max_bits as u8,
+            entries,
+        })
+    }
+
+    /// Reads a Huffman tree description (RFC 8878 section 4.2.1) from the start of `data`, which
+    /// must be byte-aligned, and builds the table.  Returns the table and the length of the
+    /// description in bytes.
+    pub fn read(data: &Bits) -> std::io::Result<(Self, usize)> {
+        if !data.byte_aligned() {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "Huffman tree description must be byte-aligned",
+            ));
+        }
+        let bytes = data.chunk_bytes();
+        let header = *bytes.first().ok_or_else(|| truncated_description(1, 0))?;
+        if header >= 128 {
+            // Weights are written directly, 4 bits each, high nibble first
+            let num_weights = header as usize - 127;
+            let len = 1 + num_weights.div_ceil(2);
+            let packed = bytes
+                .get(1..len)
+                .ok_or_else(|| truncated_description(len, bytes.len()))?;
+            let weights: Vec<u8> = (0..num_weights)
+                .map(|i| (packed[i / 2] >> (4 * (1 - i % 2))) & 0x0f)
+                .collect();
+            return Ok((Self::from_weights(&weights)?, len));
+        }
+
+        // Weights are FSE-compressed: a table description, then a reverse bitstream with two
+        // interleaved states
+        let len = 1 + header as usize;
+        let compressed = bytes
+            .get(1..len)
+            .ok_or_else(|| truncated_description(len, bytes.len()))?;
+        let mut description = LsbBits::copy_from_bytes(compressed);
+        let fse_table =
+            FseTable::read(&mut description, 255, ZSTD_HUFFMAN_WEIGHTS_MAX_ACCURACY_LOG)?;
+        let stream_start = compressed.len() - description.len_bytes();
+        let mut reader = ReverseBitReader::new(data.slice_bytes(1 + stream_start..len))?;
+        let mut states = [
+            FseDecoder::new(&fse_table, &mut reader)?,
+            FseDecoder::new(&fse_table, &mut reader)?,
+        ];
+        let mut weights = Vec::new();
+        for turn in (0..2).cycle() {
+            if weights.len() >= 255 {
+                return Err(invalid_weights(
+                    "Too many compressed Huffman weights".to_string(),
+                ));
+            }
+            weights.push(states[turn].peek_symbol());
+            // Bits past the start of the stream read as zeros, and the first state update which
+            // needs any of them ends the weights, after the other state's symbol
+            if !update_state_padded(&mut states[turn], &mut reader) {
+                weights.push(states[1 - turn].peek_symbol());
+                break;
+            }
+        }
+        Ok((Self::from_weights(&weights)?, len))
+    }
+
+    /// The length of the longest code.
+    pub fn max_bits(&self) -> u8 {
+        self.max_bits
+    }
+
+    /// Decodes one symbol from `reader`.
+    pub fn decode_symbol(&self, reader: &mut ReverseBitReader) -> std::io::Result<u8> {
+        let (symbol, num_bits) = self.entries[reader.peek_bits(self.max_bits as usize) as usize];
+        reader.get_bits(num_bits as usize)?;
+        Ok(symbol)
+    }
+
+    /// Decodes `regenerated_size` literals from `data`, the Huffman-coded streams of a literals
+    /// section (RFC 8878 section 3.1.1.3.1.6).  With `four_streams`, `data` starts with a jump
+    /// table giving the sizes of the first three streams, and each of those streams decodes a
+    /// quarter (rounded up) of the literals.  Every stream must be consumed exactly.
+    pub fn decode_literals(
+        &self,
+        data: Bits,
+        regenerated_size: usize,
+        four_streams: bool,
+    ) -> std::io::Result<Vec<u8>> {
+        let mut literals = Vec::with_capacity(regenerated_size);
+        if !four_streams {
+            self.decode_stream(data, regenerated_size, &mut literals)?;
+            return Ok(literals);
+        }
+        if data.len_bytes() < 6 || !data.byte_aligned() {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                format!(
+                    "Literals ({} bits) are too short for a jump table",
+                    data.len_bits()
+                ),
+            ));
+        }
+        let jump_table = &data.chunk_bytes()[..6];
+        let mut stream_sizes = [0; 4];
+        for (size, bytes) in stream_sizes.iter_mut().zip(jump_table.chunks(2)) {
+            *size = LittleEndian::load_u16(bytes) as usize;
+        }
+        let streams_len = data.len_bytes() - 6;
+        let first_three: usize = stream_sizes[..3].iter().sum();
+        if first_three > streams_len {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                format!(
+                    "Jump table streams ({} bytes) are longer than the literals ({} bytes)",
+                    first_three, streams_len
+                ),
+            ));
+        }
+        stream_sizes[3] = streams_len - first_three;
+        let segment_size = regenerated_size.div_ceil(4);
+        if segment_size * 3 > regenerated_size {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                format!("Too few literals ({}) for four streams", regenerated_size),
+            ));
+        }
+        let mut start = 6;
+        for (i, &size) in stream_sizes.iter().enumerate() {
+            let count = if i < 3 {
+                segment_size
+            } else {
+                regenerated_size - 3 * segment_size
+            };
+            self.decode_stream(data.slice_bytes(start..start + size), count, &mut literals)?;
+            start += size;
+        }
+        Ok(literals)
+    }
+
+    fn decode_stream(
+        &self,
+        stream: Bits,
+        count: usize,
+        literals: &mut Vec<u8>,
+    ) -> std::io::Result<()> {
+        let mut reader = ReverseBitReader::new(stream)?;
+        for _ in 0..count {
+            literals.push(self.decode_symbol(&mut reader)?);
+        }
+        if !reader.is_empty() {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                format!(
+                    "Huffman stream has {} bits left after its literals",
+                    reader.remaining_bits()
+                ),
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Moves `decoder` to its next state, reading any bits past the start of the stream as zeros.
+/// Returns false if it needed any of those bits.
+fn update_state_padded(decoder: &mut FseDecoder, reader: &mut ReverseBitReader) -> bool {
+    let cell = decoder.table.cells[decoder.state];
+    let num_bits = cell.num_bits as usize;
+    if num_bits > reader.remaining_bits() {
+        decoder.state = cell.baseline as usize + reader.peek_bits(num_bits) as usize;
+        return false;
+    }
+    decoder.state =
+        cell.baseline as usize + reader.get_bits(num_bits).expect("Enough bits remain") as usize;
+    true
+}
+
+fn invalid_weights(message: String) -> std::io::Error {
+    std::io::Error::new(std::io::ErrorKind::InvalidData, message)
+}
+
+fn truncated_description(len: usize, available: usize) -> std::io::Error {
+    std::io::Error::new(
+        std::io::ErrorKind::UnexpectedEof,
+        format!(
+            "Huffman tree description ({} bytes) is longer than the data ({} bytes)",
+            len, available
+        ),
+    )
+}
+
+fn missing_symbol(symbol: u8) -> std::io::Error {
+    std::io::Error::new(
+        std::io::ErrorKind::InvalidInput,
+        format!("Symbol {} has a count of zero", symbol),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_distributions() {
+        let table =
+            FseTable::from_normalized_counts(&LITERALS_LENGTH_DEFAULT_DISTRIBUTION, 6).unwrap();
+        // The first cells of the predefined literal length table (RFC 8878 appendix A)
+        let first: Vec<(u8, u8, u16)> = table.cells[..4]
+            .iter()
+            .map(|cell| (cell.symbol, cell.num_bits, cell.baseline))
+            .collect();
+        assert_eq!(first, [(0, 4, 0), (0, 4, 16), (1, 5, 32), (3, 5, 0)]);
+        assert!(FseTable::from_normalized_counts(&MATCH_LENGTH_DEFAULT_DISTRIBUTION, 6).is_ok());
+        assert!(FseTable::from_normalized_counts(&OFFSET_CODE_DEFAULT_DISTRIBUTION, 5).is_ok());
+
+        assert!(FseTable::from_normalized_counts(&[16, 15], 5).is_err());
+        assert!(FseTable::from_normalized_counts(&[16, 15, -2, 3], 5).is_err());
+    }
+
+    #[test]
+    fn test_small_accuracy_log() {
+        // At accuracy log 3 the spread step wraps back to cell 0 every time
+        let error = FseTable::from_normalized_counts(&[4, 4], 3).unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+        assert!(FseTable::from_normalized_counts(&[2, 1, 1], 2).is_err());
+        assert!(FseTable::from_normalized_counts(&[8, 8], 4).is_err());
+        assert!(FseTable::from_normalized_counts(&[16, 16], 5).is_ok());
+    }
+
+    #[test]
+    fn test_read_table_description() {
+        // Accuracy log 5, counts [18, 6, 0, 0, 0, 0, 0, 0, 0, 8] (zeros via repeat flags)
+        let mut bits_mut = LsbBitsMut::new();
+        bits_mut.put_bits_from::<u8, LittleEndian>(4, 0u8).unwrap();
+        // remaining = 33, threshold = 32: 19 is below max (30), so it takes 5 bits
+        bits_mut.put_bits_from::<u8, LittleEndian>(5, 19u8).unwrap();
+        // remaining = 15, threshold = 8: max is 0, so 4 bits
+        bits_mut.put_bits_from::<u8, LittleEndian>(4, 7u8).unwrap();
+        // remaining = 9, threshold = 8: 1 is below max (6), so 3 bits, then repeats of 3, 3 and 0
+        bits_mut.put_bits_from::<u8, LittleEndian>(3, 1u8).unwrap();
+        bits_mut.put_bits_from::<u8, LittleEndian>(2, 3u8).unwrap();
+        bits_mut.put_bits_from::<u8, LittleEndian>(2, 3u8).unwrap();
+        bits_mut.put_bits_from::<u8, LittleEndian>(2, 0u8).unwrap();
+        // 9 is at least the threshold, so it's written as 9 + max in 4 bits
+        bits_mut.put_bits_from::<u8, LittleEndian>(4, 15u8).unwrap();
+        // Padding to the end of the byte
+        bits_mut.put_bits_from::<u8, LittleEndian>(6, 0u8).unwrap();
+        bits_mut.put_u8(0xab).unwrap();
+        let mut bits = bits_mut.freeze();
+
+        let table = FseTable::read(&mut bits, 255, 9).unwrap();
+        assert_eq!(table.accuracy_log(), 5);
+        assert_eq!(table.num_symbols(), 10);
+        assert_eq!(table.symbol_states[0].len(), 18);
+        assert_eq!(table.symbol_states[9].len(), 8);
+        // The padding to the end of the byte was skipped
+        assert_eq!(bits.get_u8().unwrap(), 0xab);
+
+        let mut bits = LsbBits::from_static_bytes(&[0x0f, 0x00]);
+        assert!(FseTable::read(&mut bits, 255, 9).is_err());
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let counts = [7, 0, 12, -1, 3, 1, -1, 5, 0, 2];
+        let table = FseTable::from_normalized_counts(&counts, 5).unwrap();
+        let symbols: Vec<u8> = (0..500u32)
+            .map(|i| match i.wrapping_mul(2_654_435_761) >> 27 {
+                0..=6 => 0,
+                7..=18 => 2,
+                19 => 3,
+                20..=22 => 4,
+                23 => 5,
+                24 => 6,
+                25..=29 => 7,
+                _ => 9,
+            })
+            .collect();
+
+        // Two interleaved states, as zstd's Huffman weight streams use
+        let mut writer = ReverseBitWriter::new(BitsMut::new());
+        let (even, odd): (Vec<u8>, Vec<u8>) = (
+            symbols.iter().step_by(2).copied().collect(),
+            symbols.iter().skip(1).step_by(2).copied().collect(),
+        );
+        let mut even_encoder = FseEncoder::new(&table, *even.last().unwrap()).unwrap();
+        let mut odd_encoder = FseEncoder::new(&table, *odd.last().unwrap()).unwrap();
+        for i in (0..even.len() - 1).rev() {
+            odd_encoder.encode_symbol(&mut writer, odd[i]).unwrap();
+            even_encoder.encode_symbol(&mut writer, even[i]).unwrap();
+        }
+        assert!(even_encoder.encode_symbol(&mut writer, 1).is_err());
+        odd_encoder.finish(&mut writer).unwrap();
+        even_encoder.finish(&mut writer).unwrap();
+        let bits = writer.finish().unwrap().freeze();
+        // Better than the 4 bits a fixed-length code would need
+        assert!(bits.len_bits() < symbols.len() * 4);
+
+        let mut reader = ReverseBitReader::new(bits).unwrap();
+        let mut even_decoder = FseDecoder::new(&table, &mut reader).unwrap();
+        let mut odd_decoder = FseDecoder::new(&table, &mut reader).unwrap();
+        let mut decoded = Vec::new();
+        for i in 0..even.len() {
+            decoded.push(even_decoder.peek_symbol());
+            decoded.push(odd_decoder.peek_symbol());
+            if i + 1 < even.len() {
+                even_decoder.update_state(&mut reader).unwrap();
+                odd_decoder.update_state(&mut reader).unwrap();
+            }
+        }
+        assert_eq!(decoded, symbols);
+        assert!(reader.is_empty());
+    }
+
+    /// The input the zstd literals below were compressed from.
+    fn zstd_sample(len: u32) -> Vec<u8> {
+        let table = b"eeeeeeeetttttaaaaoooiinnsshrdlu ";
+        (0..len)
+            .map(|i| {
+                let mut x = i.wrapping_mul(2_654_435_761).wrapping_add(12345);
+                x ^= x >> 13;
+                x = x.wrapping_mul(0x5bd1_e995);
+                table[(x >> 27) as usize]
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_huffman_from_weights() {
+        // Weights 1 and 1 imply a last weight of 2: codes of 2, 2 and 1 bits
+        let table = ZstdHuffmanTable::from_weights(&[1, 1]).unwrap();
+        assert_eq!(table.max_bits(), 2);
+        assert_eq!(table.entries, [(0, 2), (1, 2), (2, 1), (2, 1)]);
+        // 1 + 2 + 2 = 5 can't be completed to a power of two with one more weight
+        assert!(ZstdHuffmanTable::from_weights(&[1, 2, 2]).is_err());
+        assert!(ZstdHuffmanTable::from_weights(&[0, 0]).is_err());
+        assert!(ZstdHuffmanTable::from_weights(&[12]).is_err());
+
+        // Direct weights: 0x81 is two 4-bit weights, then a stream of symbols 2, 0, 1, 2 after its
+        // padding marker
+        let mut data = BitsMut::new();
+        data.put_u8(0x81).unwrap();
+        data.put_u8(0x11).unwrap();
+        data.put_u8(0b0110_0011).unwrap();
+        let data = data.freeze();
+        let (table, len) = ZstdHuffmanTable::read(&data).unwrap();
+        assert_eq!(len, 2);
+        assert_eq!(table.max_bits(), 2);
+        let literals = table
+            .decode_literals(data.slice_bytes(len..data.len_bytes()), 4, false)
+            .unwrap();
+        assert_eq!(literals, [2, 0, 1, 2]);
+        assert!(table
+            .decode_literals(data.slice_bytes(len..data.len_bytes()), 3, false)
+            .is_err());
+    }
+
+    #[test]
+    fn test_huffman_single_stream() {
+        // The literals section (after its header) of `zstd -19` compressing the 100-byte sample,
+        // with FSE-compressed weights
+        let data = Bits::from_static_bytes(&[
+            0x0b, 0xe0, 0x6d, 0x79, 0x77, 0x0f, 0xdc, 0xdb, 0x08, 0x4c, 0x6b, 0x0d, 0x75, 0x7b,
+            0xe5, 0xb1, 0xab, 0xed, 0xd7, 0x3c, 0xf7, 0xfc, 0xb6, 0xe5, 0x65, 0xe3, 0x19, 0xb4,
+            0x60, 0x1c, 0x5f, 0xdd, 0xbf, 0x1d, 0x7c, 0xcd, 0x63, 0x33, 0xf4, 0x36, 0xae, 0x1b,
+            0x28, 0xbd, 0xe3, 0x43, 0xbf, 0x68, 0xb1, 0x2e, 0xab, 0x47, 0xba, 0x12, 0xaf, 0x01,
+        ]);
+        let (table, len) = ZstdHuffmanTable::read(&data).unwrap();
+        assert_eq!(len, 12);
+        let literals = table
+            .decode_literals(data.slice_bytes(len..data.len_bytes()), 100, false)
+            .unwrap();
+        assert_eq!(literals, zstd_sample(100));
+    }
+
+    #[test]
+    fn test_huffman_four_streams() {
+        // The same for the 300-byte sample, which zstd splits into four streams
+        let data = Bits::from_static_bytes(&[
+            0x0e, 0xd0, 0xa5, 0x03, 0x72, 0x50, 0x3b, 0x50, 0x83, 0x7a, 0x0b, 0x80, 0x2a, 0xe5,
+            0x04, 0x21, 0x00, 0x1e, 0x00, 0x1f, 0x00, 0x2f, 0x4a, 0x5f, 0x84, 0x60, 0x03, 0xd7,
+            0x4c, 0xf6, 0xda, 0x6d, 0xaa, 0xe3, 0x33, 0x07, 0x2b, 0xe7, 0xbd, 0x50, 0x62, 0x3d,
+            0x74, 0x46, 0x49, 0xd8, 0x6a, 0x13, 0xa9, 0xa1, 0x4c, 0xa5, 0xc8, 0x02, 0x83, 0xb1,
+            0x03, 0xe3, 0x45, 0xee, 0xb0, 0x0a, 0x93, 0xbc, 0x49, 0x24, 0x7d, 0xe4, 0x63, 0x51,
+            0x75, 0x54, 0x1f, 0xf2, 0xde, 0x1b, 0xa1, 0x87, 0x64, 0x7f, 0xc7, 0xa7, 0x4f, 0x7b,
+            0x20, 0x10, 0xd0, 0xe9, 0x91, 0xab, 0xad, 0x58, 0x43, 0x2f, 0x0b, 0x85, 0xf1, 0x16,
+            0x67, 0x5a, 0x1f, 0x5d, 0xf7, 0xc0, 0xc5, 0x7e, 0xae, 0x78, 0x9d, 0xde, 0x45, 0xdd,
+            0xd7, 0x9a, 0x46, 0xf5, 0xab, 0xb1, 0x59, 0xb6, 0x60, 0xee, 0x03, 0x2d, 0x12, 0x4c,
+            0xe2, 0x81, 0x4e, 0xda, 0x88, 0x64, 0x0f, 0xb6, 0x34, 0x1f, 0x19, 0x4b, 0x54, 0x9d,
+            0x5f, 0xec, 0x55, 0x80, 0xad, 0xa4, 0x04,
+        ]);
+        let (table, len) = ZstdHuffmanTable::read(&data).unwrap();
+        assert_eq!(len, 15);
+        let streams = data.slice_bytes(len..data.len_bytes());
+        let literals = table.decode_literals(streams.clone(), 300, true).unwrap();
+        assert_eq!(literals, zstd_sample(300));
+        // Each stream must decode exactly its share of the literals
+        assert!(table.decode_literals(streams.clone(), 299, true).is_err());
+        assert!(table.decode_literals(streams, 300, false).is_err());
+    }
+}
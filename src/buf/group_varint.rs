@@ -0,0 +1,349 @@
+use bitvec::{order::Msb0, view::BitView};
+
+use crate::prelude::*;
+
+/// The byte lengths of the 4 values described by each Group Varint tag byte, whose 2-bit length
+/// codes are ordered from the most significant bits down.
+const GROUP_VARINT_LENGTHS: [[u8; 4]; 256] = lengths_table(true);
+
+/// The byte lengths of the 4 values described by each Stream VByte control byte, whose 2-bit
+/// length codes are ordered from the least significant bits up.
+const STREAM_VBYTE_LENGTHS: [[u8; 4]; 256] = lengths_table(false);
+
+const LENGTH_MASKS: [u32; 5] = [0, 0xff, 0xffff, 0xff_ffff, 0xffff_ffff];
+
+/// The longest encoding of a group: a tag byte and 4 4-byte values.
+const MAX_GROUP_LEN: usize = 17;
+
+const fn lengths_table(msb_first: bool) -> [[u8; 4]; 256] {
+    let mut table = [[0; 4]; 256];
+    let mut control = 0;
+    while control < 256 {
+        let mut i = 0;
+        while i < 4 {
+            let shift = if msb_first { 6 - 2 * i } else { 2 * i };
+            table[control][i] = ((control >> shift) & 3) as u8 + 1;
+            i += 1;
+        }
+        control += 1;
+    }
+    table
+}
+
+/// The number of bytes (1 to 4) `value` takes.
+fn encoded_len(value: u32) -> usize {
+    (32 - value.leading_zeros() as usize).div_ceil(8).max(1)
+}
+
+/// Decodes `values.len()` (at most 4) little-endian values with the given lengths from the start
+/// of `data`, which must hold them all, and returns the number of bytes they took.
+fn decode_group(lengths: &[u8; 4], data: &[u8], values: &mut [u32]) -> usize {
+    let mut pos = 0;
+    if data.len() >= 16 {
+        // Every value can be loaded as 4 bytes and masked down to its length
+        for (value, &len) in values.iter_mut().zip(lengths) {
+            let word = u32::from_le_bytes(data[pos..pos + 4].try_into().unwrap());
+            *value = word & LENGTH_MASKS[len as usize];
+            pos += len as usize;
+        }
+    } else {
+        for (value, &len) in values.iter_mut().zip(lengths) {
+            *value = data[pos..pos + len as usize]
+                .iter()
+                .rev()
+                .fold(0, |value, &byte| (value << 8) | byte as u32);
+            pos += len as usize;
+        }
+    }
+    pos
+}
+
+/// Reads a `len`-byte little-endian value.
+fn get_le_value<Order: BitOrder, B: BitBuf<Order> + ?Sized>(
+    buf: &mut B,
+    len: u8,
+) -> std::io::Result<u32> {
+    let mut value = 0;
+    for i in 0..len {
+        value |= (buf.get_u8()? as u32) << (8 * i);
+    }
+    Ok(value)
+}
+
+fn put_bytes<Order: BitOrder, B: BitBufMut<Order> + ?Sized>(
+    buf: &mut B,
+    bytes: &[u8],
+) -> std::io::Result<()> {
+    if buf.byte_aligned_mut() {
+        buf.try_put_slice_bytes(bytes)
+    } else {
+        buf.try_put_bit_slice(bytes.view_bits::<Order>())
+    }
+}
+
+/// Reads of two byte-oriented formats for lists of `u32`s, which store each value in the fewest
+/// little-endian bytes (1 to 4) and describe the lengths with 2-bit codes, 4 to a byte.  The
+/// number of values isn't stored, so it has to be known up front.
+///
+/// Both decode a group of 4 values at a time with a lookup table from the control byte, reading
+/// directly from the buffer's bytes when it's byte-aligned.
+pub trait BitBufGroupVarintExts<Order: BitOrder = Msb0>: BitBuf<Order> {
+    /// Reads `dest.len()` values encoded with Google's Group Varint: each group of 4 is a tag byte
+    /// holding the length codes (the first value's in the top 2 bits), followed by the values.
+    /// The last group may be shorter, in which case its tag's unused codes are zero.
+    fn get_group_varint(&mut self, dest: &mut [u32]) -> std::io::Result<()> {
+        let mut decoded = 0;
+        while decoded < dest.len() {
+            if self.byte_aligned() {
+                let bytes = self.chunk_bytes();
+                let mut pos = 0;
+                // Full groups take the fast path as long as there's room for the longest group
+                while dest.len() - decoded >= 4 && bytes.len() - pos >= MAX_GROUP_LEN {
+                    pos += 1 + decode_group(
+                        &GROUP_VARINT_LENGTHS[bytes[pos] as usize],
+                        &bytes[pos + 1..],
+                        &mut dest[decoded..decoded + 4],
+                    );
+                    decoded += 4;
+                }
+                if pos > 0 {
+                    self.advance_bytes(pos);
+                    continue;
+                }
+            }
+            let lengths = &GROUP_VARINT_LENGTHS[self.get_u8()? as usize];
+            let count = (dest.len() - decoded).min(4);
+            for (value, &len) in dest[decoded..decoded + count].iter_mut().zip(lengths) {
+                *value = get_le_value(self, len)?;
+            }
+            decoded += count;
+        }
+        Ok(())
+    }
+
+    /// Reads `dest.len()` values encoded with Stream VByte: all of the control bytes (the first
+    /// value of each 4 in the bottom 2 bits) followed by all of the values.
+    fn get_stream_vbyte(&mut self, dest: &mut [u32]) -> std::io::Result<()> {
+        let num_controls = dest.len().div_ceil(4);
+        if self.byte_aligned() && self.chunk_bytes().len() >= num_controls {
+            let (controls, data) = self.chunk_bytes().split_at(num_controls);
+            let data_len: usize = controls
+                .iter()
+                .zip(dest.chunks(4))
+                .map(|(&control, group)| {
+                    STREAM_VBYTE_LENGTHS[control as usize][..group.len()]
+                        .iter()
+                        .map(|&len| len as usize)
+                        .sum::<usize>()
+                })
+                .sum();
+            if data.len() >= data_len {
+                let mut pos = 0;
+                for (&control, group) in controls.iter().zip(dest.chunks_mut(4)) {
+                    pos +=
+                        decode_group(&STREAM_VBYTE_LENGTHS[control as usize], &data[pos..], group);
+                }
+                self.advance_bytes(num_controls + data_len);
+                return Ok(());
+            }
+        }
+        let mut controls = vec![0; num_controls];
+        for control in controls.iter_mut() {
+            *control = self.get_u8()?;
+        }
+        for (control, group) in controls.into_iter().zip(dest.chunks_mut(4)) {
+            for (value, &len) in group
+                .iter_mut()
+                .zip(&STREAM_VBYTE_LENGTHS[control as usize])
+            {
+                *value = get_le_value(self, len)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<Order: BitOrder, T: BitBuf<Order> + ?Sized> BitBufGroupVarintExts<Order> for T {}
+
+/// Writes of the formats read by [`BitBufGroupVarintExts`].
+pub trait BitBufMutGroupVarintExts<Order: BitOrder = Msb0>: BitBufMut<Order> {
+    /// Writes `values` with Google's Group Varint.
+    fn put_group_varint(&mut self, values: &[u32]) -> std::io::Result<()> {
+        let mut group_bytes = [0u8; MAX_GROUP_LEN];
+        for group in values.chunks(4) {
+            let mut tag = 0;
+            let mut len = 1;
+            for (i, &value) in group.iter().enumerate() {
+                let value_len = encoded_len(value);
+                tag |= ((value_len - 1) as u8) << (6 - 2 * i);
+                group_bytes[len..len + 4].copy_from_slice(&value.to_le_bytes());
+                len += value_len;
+            }
+            group_bytes[0] = tag;
+            put_bytes(self, &group_bytes[..len])?;
+        }
+        Ok(())
+    }
+
+    /// Writes `values` with Stream VByte.
+    fn put_stream_vbyte(&mut self, values: &[u32]) -> std::io::Result<()> {
+        let mut controls = vec![0u8; values.len().div_ceil(4)];
+        let mut data = Vec::with_capacity(values.len() * 4);
+        for (i, &value) in values.iter().enumerate() {
+            let value_len = encoded_len(value);
+            controls[i / 4] |= ((value_len - 1) as u8) << (2 * (i % 4));
+            data.extend_from_slice(&value.to_le_bytes()[..value_len]);
+        }
+        put_bytes(self, &controls)?;
+        put_bytes(self, &data)
+    }
+}
+
+impl<Order: BitOrder, T: BitBufMut<Order> + ?Sized> BitBufMutGroupVarintExts<Order> for T {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_values() -> Vec<u32> {
+        (0..1001u32)
+            .map(|i| i.wrapping_mul(0x9e37_79b9) >> (i % 32))
+            .collect()
+    }
+
+    #[test]
+    fn test_group_varint() {
+        // The example from Jeff Dean's "Challenges in Building Large-Scale Information Retrieval
+        // Systems"
+        let data = [0b0000_0110, 0x01, 0x0f, 0xff, 0x01, 0xff, 0xff, 0x01];
+        let mut bits = Bits::copy_from_bytes(&data);
+        let mut values = [0; 4];
+        bits.get_group_varint(&mut values).unwrap();
+        assert_eq!(values, [1, 15, 511, 131071]);
+        assert!(bits.is_empty());
+
+        let mut bits_mut = BitsMut::new();
+        bits_mut.put_group_varint(&values).unwrap();
+        assert_eq!(bits_mut.freeze(), Bits::copy_from_bytes(&data));
+
+        // A short last group
+        let mut bits = Bits::from_static_bytes(&[0b0001_0000, 0x02, 0x01, 0x01]);
+        let mut values = [0; 2];
+        bits.get_group_varint(&mut values).unwrap();
+        assert_eq!(values, [2, 257]);
+    }
+
+    #[test]
+    fn test_stream_vbyte() {
+        let data = [
+            0b1110_0100,
+            0x01,
+            0x00,
+            0x01,
+            0x00,
+            0x00,
+            0x01,
+            0x00,
+            0x00,
+            0x00,
+            0x01,
+        ];
+        let mut bits = Bits::copy_from_bytes(&data);
+        let mut values = [0; 4];
+        bits.get_stream_vbyte(&mut values).unwrap();
+        assert_eq!(values, [1, 1 << 8, 1 << 16, 1 << 24]);
+        assert!(bits.is_empty());
+
+        let mut bits_mut = BitsMut::new();
+        bits_mut.put_stream_vbyte(&values).unwrap();
+        assert_eq!(bits_mut.freeze(), Bits::copy_from_bytes(&data));
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let values = test_values();
+        for len in [0, 1, 3, 4, 5, 998, 1001] {
+            let values = &values[..len];
+            let mut bits_mut = BitsMut::new();
+            bits_mut.put_group_varint(values).unwrap();
+            bits_mut.put_stream_vbyte(values).unwrap();
+            bits_mut.put_u8(0xaa).unwrap();
+            let mut bits = bits_mut.freeze();
+
+            let mut decoded = vec![0; len];
+            bits.get_group_varint(&mut decoded).unwrap();
+            assert_eq!(decoded, values);
+            bits.get_stream_vbyte(&mut decoded).unwrap();
+            assert_eq!(decoded, values);
+            assert_eq!(bits.get_u8().unwrap(), 0xaa);
+        }
+    }
+
+    #[test]
+    fn test_unaligned() {
+        // Unaligned buffers and chains take the slow path, which gives the same results
+        let values = test_values();
+        let mut bits_mut = BitsMut::new();
+        bits_mut.put_u3(u3::new(0b101)).unwrap();
+        bits_mut.put_group_varint(&values).unwrap();
+        bits_mut.put_stream_vbyte(&values).unwrap();
+        let mut bits = bits_mut.freeze();
+        assert_eq!(bits.get_u3().unwrap(), u3::new(0b101));
+
+        let mut decoded = vec![0; values.len()];
+        bits.get_group_varint(&mut decoded).unwrap();
+        assert_eq!(decoded, values);
+        bits.get_stream_vbyte(&mut decoded).unwrap();
+        assert_eq!(decoded, values);
+
+        let mut bits_mut = BitsMut::new();
+        bits_mut.put_group_varint(&values).unwrap();
+        let bits = bits_mut.freeze();
+        let mid = bits.len_bytes() / 2;
+        let mut chain = bits
+            .slice_bytes(0..mid)
+            .chain(bits.slice_bytes(mid..bits.len_bytes()));
+        chain.get_group_varint(&mut decoded).unwrap();
+        assert_eq!(decoded, values);
+    }
+
+    #[test]
+    fn test_lsb() {
+        // The tag is a whole byte in either bit order, on the slow path for a lone group...
+        let data = [0b0000_0110, 0x01, 0x0f, 0xff, 0x01, 0xff, 0xff, 0x01];
+        let mut bits = LsbBits::copy_from_bytes(&data);
+        let mut values = [0; 4];
+        bits.get_group_varint(&mut values).unwrap();
+        assert_eq!(values, [1, 15, 511, 131071]);
+        assert!(bits.is_empty());
+
+        // ...and on the fast path once there's room for the longest group
+        let values = test_values();
+        let mut bits_mut = LsbBitsMut::new();
+        bits_mut.put_group_varint(&values).unwrap();
+        bits_mut.put_stream_vbyte(&values).unwrap();
+        let mut bits = bits_mut.freeze();
+        assert!(bits.chunk_bytes().len() >= MAX_GROUP_LEN);
+
+        let mut decoded = vec![0; values.len()];
+        bits.get_group_varint(&mut decoded).unwrap();
+        assert_eq!(decoded, values);
+        bits.get_stream_vbyte(&mut decoded).unwrap();
+        assert_eq!(decoded, values);
+        assert!(bits.is_empty());
+    }
+
+    #[test]
+    fn test_truncated() {
+        let values = test_values();
+        let mut bits_mut = BitsMut::new();
+        bits_mut.put_stream_vbyte(&values).unwrap();
+        let bits = bits_mut.freeze();
+        let mut truncated = bits.slice_bytes(0..bits.len_bytes() - 1);
+        let mut decoded = vec![0; values.len()];
+        assert!(truncated.get_stream_vbyte(&mut decoded).is_err());
+
+        let mut bits = Bits::from_static_bytes(&[0b1100_0000, 0x01, 0x02]);
+        assert!(bits.get_group_varint(&mut decoded[..1]).is_err());
+    }
+}
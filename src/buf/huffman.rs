@@ -0,0 +1,1076 @@
+use bitvec::order::{Lsb0, Msb0};
+
+use crate::prelude::*;
+
+/// The longest code supported
+pub const MAX_CODE_LENGTH: usize = 32;
+
+/// The number of bits used to index the first-level lookup table
+const ROOT_TABLE_BITS: usize = 9;
+
+/// The maximum number of bits used to index each lower-level table
+const SUB_TABLE_BITS: usize = 8;
+
+#[derive(Clone, Copy, Debug)]
+enum Entry {
+    /// No code starts with these bits
+    Invalid,
+    /// A code for `symbol` which is `len` bits long (from the start of this table's bits)
+    Symbol { symbol: u16, len: u8 },
+    /// The code continues in the table starting at `start` and indexed by `bits` bits
+    SubTable { start: u32, bits: u8 },
+}
+
+#[derive(Clone, Copy)]
+struct Code {
+    code: u64,
+    len: usize,
+    symbol: u16,
+}
+
+/// Decodes symbols encoded with a canonical Huffman code, where codes of the same length are
+/// consecutive values assigned in symbol order and shorter codes come before longer ones (as in
+/// DEFLATE, JPEG and MPEG audio).  Codes are read MSB-first.
+///
+/// Decoding peeks at the next bits in the buffer's current chunk and looks them up in a multi-level
+/// table, consuming only as many bits as the matched code.  If a code might span the end of the
+/// chunk (e.g. at the boundary of a [`Chain`](crate::buf::chain::Chain)), decoding falls back to
+/// reading the rest of the code a bit at a time.
+#[derive(Clone, Debug)]
+pub struct HuffmanDecoder {
+    entries: Vec<Entry>,
+    root_bits: usize,
+    max_len: usize,
+    /// The number of codes of each length
+    counts: [u32; MAX_CODE_LENGTH + 1],
+    /// The first (numerically lowest) code of each length
+    first_codes: [u64; MAX_CODE_LENGTH + 1],
+    /// The index in `sorted_symbols` of the symbol for the first code of each length
+    first_indices: [usize; MAX_CODE_LENGTH + 1],
+    /// All the symbols, in code order
+    sorted_symbols: Vec<u16>,
+}
+
+impl HuffmanDecoder {
+    /// Builds a decoder from the code length of each symbol (as in DEFLATE), where `lengths[i]` is
+    /// the length of the code for symbol `i` and 0 means the symbol isn't used.
+    ///
+    /// Returns an error if the code set is over-subscribed (more codes than can be assigned) or,
+    /// unless `allow_incomplete` is set, incomplete (some bit sequences aren't a code).  DEFLATE
+    /// allows a distance code set with a single code, which is incomplete.
+    pub fn from_code_lengths(lengths: &[u8], allow_incomplete: bool) -> std::io::Result<Self> {
+        if lengths.len() > u16::MAX as usize + 1 {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!("Too many symbols ({})", lengths.len()),
+            ));
+        }
+        let mut symbols_by_length = Vec::with_capacity(lengths.len());
+        for (symbol, &len) in lengths.iter().enumerate() {
+            if len != 0 {
+                symbols_by_length.push((len as usize, symbol as u16));
+            }
+        }
+        // A stable sort keeps symbols of the same length in symbol order
+        symbols_by_length.sort_by_key(|&(len, _)| len);
+        Self::build(&symbols_by_length, allow_incomplete)
+    }
+
+    /// Builds a decoder from the number of codes of each length and the symbols in code order (as
+    /// in a JPEG DHT segment), where `counts[i]` is the number of codes of length `i + 1`.
+    ///
+    /// JPEG code sets are always incomplete, since no code may be all ones, so `allow_incomplete`
+    /// must be set for them.
+    pub fn from_counts(
+        counts: &[u8],
+        symbols: &[u16],
+        allow_incomplete: bool,
+    ) -> std::io::Result<Self> {
+        let total: usize = counts.iter().map(|&count| count as usize).sum();
+        if total != symbols.len() {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!(
+                    "Counts give {} codes, but there are {} symbols",
+                    total,
+                    symbols.len()
+                ),
+            ));
+        }
+        let mut symbols_by_length = Vec::with_capacity(symbols.len());
+        let mut symbols = symbols.iter();
+        for (i, &count) in counts.iter().enumerate() {
+            for symbol in symbols.by_ref().take(count as usize) {
+                symbols_by_length.push((i + 1, *symbol));
+            }
+        }
+        Self::build(&symbols_by_length, allow_incomplete)
+    }
+
+    /// Builds the decoder from (length, symbol) pairs in code order.
+    fn build(symbols_by_length: &[(usize, u16)], allow_incomplete: bool) -> std::io::Result<Self> {
+        let mut counts = [0u32; MAX_CODE_LENGTH + 1];
+        for &(len, _) in symbols_by_length {
+            if len > MAX_CODE_LENGTH {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::InvalidInput,
+                    format!(
+                        "Code length ({}) is longer than the maximum ({})",
+                        len, MAX_CODE_LENGTH
+                    ),
+                ));
+            }
+            counts[len] += 1;
+        }
+
+        check_kraft_sum(&counts, allow_incomplete)?;
+
+        let first_codes = first_codes(&counts);
+        let mut first_indices = [0usize; MAX_CODE_LENGTH + 1];
+        for len in 1..MAX_CODE_LENGTH {
+            first_indices[len + 1] = first_indices[len] + counts[len] as usize;
+        }
+
+        let mut next_codes = first_codes;
+        let codes: Vec<Code> = symbols_by_length
+            .iter()
+            .map(|&(len, symbol)| {
+                let code = next_codes[len];
+                next_codes[len] += 1;
+                Code { code, len, symbol }
+            })
+            .collect();
+        let max_len = codes.iter().map(|code| code.len).max().unwrap_or(0);
+
+        let mut entries = Vec::new();
+        let root_bits = if codes.is_empty() {
+            0
+        } else {
+            build_table(&mut entries, &codes, 0, ROOT_TABLE_BITS).1
+        };
+
+        Ok(Self {
+            entries,
+            root_bits,
+            max_len,
+            counts,
+            first_codes,
+            first_indices,
+            sorted_symbols: symbols_by_length
+                .iter()
+                .map(|&(_, symbol)| symbol)
+                .collect(),
+        })
+    }
+
+    /// The length of the longest code
+    pub fn max_code_length(&self) -> usize {
+        self.max_len
+    }
+
+    /// Reads a single code from `buf` and returns its symbol.  Codes are packed starting with
+    /// their most significant bit, so in an [`Lsb0`] buffer they appear bit-reversed compared to
+    /// its other fields, as in DEFLATE (RFC 1951 section 3.1.1).
+    pub fn decode<Order: BitOrder, B: BitBuf<Order> + ?Sized>(
+        &self,
+        buf: &mut B,
+    ) -> std::io::Result<u16> {
+        if self.entries.is_empty() {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "Huffman code set has no codes",
+            ));
+        }
+        let mut start = 0;
+        let mut bits = self.root_bits;
+        // The bits of the code consumed so far, for the fallback path
+        let mut code = 0u64;
+        let mut code_len = 0;
+        loop {
+            let chunk_len = buf.chunk_bits().len();
+            let at_end = chunk_len == buf.remaining_bits();
+            if chunk_len < bits && !at_end {
+                return self.decode_slow(buf, code, code_len);
+            }
+            // At the end of the buffer the lookup is padded with zeros, and then the matched code
+            // must fit in what's actually there
+            let available = chunk_len.min(bits);
+            let index = if available == 0 {
+                0
+            } else {
+                (peek_code(&buf.chunk_bits()[..available]) as usize) << (bits - available)
+            };
+            match self.entries[start + index] {
+                Entry::Symbol { symbol, len } if len as usize <= available => {
+                    buf.advance_bits(len as usize);
+                    return Ok(symbol);
+                }
+                Entry::SubTable {
+                    start: sub_start,
+                    bits: sub_bits,
+                } if available == bits => {
+                    buf.advance_bits(bits);
+                    code = (code << bits) | index as u64;
+                    code_len += bits;
+                    start = sub_start as usize;
+                    bits = sub_bits as usize;
+                }
+                Entry::Invalid if available == bits => {
+                    return Err(std::io::Error::new(
+                        std::io::ErrorKind::InvalidData,
+                        format!(
+                            "Bits {:#b} aren't the start of any Huffman code",
+                            (code << bits) | index as u64
+                        ),
+                    ));
+                }
+                _ => {
+                    return Err(std::io::Error::new(
+                        std::io::ErrorKind::UnexpectedEof,
+                        "Buffer ended in the middle of a Huffman code",
+                    ));
+                }
+            }
+        }
+    }
+
+    /// Reads the rest of a code a bit at a time, given the first `len` bits of it.
+    fn decode_slow<Order: BitOrder, B: BitBuf<Order> + ?Sized>(
+        &self,
+        buf: &mut B,
+        mut code: u64,
+        mut len: usize,
+    ) -> std::io::Result<u16> {
+        while len < self.max_len {
+            code = (code << 1) | buf.get_bool()? as u64;
+            len += 1;
+            // Prefixes of longer codes are always numerically after the codes of this length
+            let offset = code.wrapping_sub(self.first_codes[len]);
+            if code >= self.first_codes[len] && offset < self.counts[len] as u64 {
+                return Ok(self.sorted_symbols[self.first_indices[len] + offset as usize]);
+            }
+        }
+        Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            format!("Bits {:#b} aren't a Huffman code", code),
+        ))
+    }
+}
+
+/// Returns the bits of `bits` (at most 32) as a code, with its first bit as the most significant.
+fn peek_code<Order: BitOrder>(bits: &bitvec::slice::BitSlice<u8, Order>) -> u32 {
+    let value: u32 = Order::FieldOrder::load(bits);
+    if Order::MSB_FIRST {
+        value
+    } else {
+        // The first bit is the least significant of an LSB-first load
+        value.reverse_bits() >> (32 - bits.len())
+    }
+}
+
+/// Checks the Kraft sum of a code set with `counts[len]` codes of each length: each code of length
+/// `len` uses 2^(MAX - len) of the 2^MAX available code space.
+fn check_kraft_sum(
+    counts: &[u32; MAX_CODE_LENGTH + 1],
+    allow_incomplete: bool,
+) -> std::io::Result<()> {
+    let used: u64 = (1..=MAX_CODE_LENGTH)
+        .map(|len| (counts[len] as u64) << (MAX_CODE_LENGTH - len))
+        .sum();
+    if used > 1 << MAX_CODE_LENGTH {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            "Huffman code set is over-subscribed",
+        ));
+    }
+    if used < 1 << MAX_CODE_LENGTH && !allow_incomplete {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            "Huffman code set is incomplete",
+        ));
+    }
+    Ok(())
+}
+
+/// Returns the first (numerically lowest) canonical code of each length, given the number of
+/// codes of each length.
+fn first_codes(counts: &[u32; MAX_CODE_LENGTH + 1]) -> [u64; MAX_CODE_LENGTH + 1] {
+    let mut first_codes = [0u64; MAX_CODE_LENGTH + 1];
+    let mut code = 0u64;
+    for len in 1..=MAX_CODE_LENGTH {
+        code = (code + counts[len - 1] as u64) << 1;
+        first_codes[len] = code;
+    }
+    first_codes
+}
+
+/// Adds a lookup table for `codes` (which all share the same `depth`-bit prefix) to `entries`,
+/// indexed by at most `max_bits` bits, along with any lower-level tables it needs.  Returns the
+/// start of the table and the number of bits which index it.
+fn build_table(
+    entries: &mut Vec<Entry>,
+    codes: &[Code],
+    depth: usize,
+    max_bits: usize,
+) -> (u32, usize) {
+    let longest = codes.iter().map(|code| code.len).max().unwrap_or(depth) - depth;
+    let bits = longest.min(max_bits);
+    let start = entries.len();
+    entries.resize(start + (1 << bits), Entry::Invalid);
+
+    let mut long_codes = Vec::new();
+    for code in codes {
+        let len = code.len - depth;
+        if len <= bits {
+            // Every index which starts with this code maps to it
+            let suffix = code.code & ((1 << len) - 1);
+            let first = (suffix << (bits - len)) as usize;
+            let entry = Entry::Symbol {
+                symbol: code.symbol,
+                len: len as u8,
+            };
+            entries[start + first..start + first + (1 << (bits - len))].fill(entry);
+        } else {
+            long_codes.push(*code);
+        }
+    }
+
+    // Codes which are longer than this table's bits are grouped by their next `bits` bits into a
+    // lower-level table
+    let table_index = |code: &Code| (code.code >> (code.len - depth - bits)) & ((1 << bits) - 1);
+    long_codes.sort_by_key(table_index);
+    for group in long_codes.chunk_by(|a, b| table_index(a) == table_index(b)) {
+        let index = table_index(&group[0]) as usize;
+        let (sub_start, sub_bits) = build_table(entries, group, depth + bits, SUB_TABLE_BITS);
+        entries[start + index] = Entry::SubTable {
+            start: sub_start,
+            bits: sub_bits as u8,
+        };
+    }
+
+    (start as u32, bits)
+}
+
+/// Encodes symbols with a canonical Huffman code, assigned in the same way as
+/// [`HuffmanDecoder`].  Each symbol is written with a single write of its code bits.
+#[derive(Clone, Debug)]
+pub struct HuffmanEncoder {
+    lengths: Vec<u8>,
+    codes: Vec<u32>,
+}
+
+impl HuffmanEncoder {
+    /// Builds an optimal code for symbols with the given frequencies, where no code is longer than
+    /// `max_len` bits, using the package-merge algorithm.  Symbols with a frequency of zero don't
+    /// get a code.  If only one symbol has a non-zero frequency it gets a 1-bit code.
+    ///
+    /// Returns an error if `max_len` is too short for the number of symbols which need a code.
+    pub fn from_frequencies(frequencies: &[u64], max_len: usize) -> std::io::Result<Self> {
+        Self::from_code_lengths(&package_merge(frequencies, max_len)?)
+    }
+
+    /// Builds an encoder from the code length of each symbol, where 0 means the symbol isn't
+    /// used.  Returns an error if the code set is over-subscribed.
+    pub fn from_code_lengths(lengths: &[u8]) -> std::io::Result<Self> {
+        if lengths.len() > u16::MAX as usize + 1 {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!("Too many symbols ({})", lengths.len()),
+            ));
+        }
+        let mut counts = [0u32; MAX_CODE_LENGTH + 1];
+        for &len in lengths {
+            if len as usize > MAX_CODE_LENGTH {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::InvalidInput,
+                    format!(
+                        "Code length ({}) is longer than the maximum ({})",
+                        len, MAX_CODE_LENGTH
+                    ),
+                ));
+            }
+            counts[len as usize] += 1;
+        }
+        counts[0] = 0;
+        check_kraft_sum(&counts, true)?;
+
+        let mut next_codes = first_codes(&counts);
+        let codes = lengths
+            .iter()
+            .map(|&len| {
+                if len == 0 {
+                    return 0;
+                }
+                let code = next_codes[len as usize];
+                next_codes[len as usize] += 1;
+                code as u32
+            })
+            .collect();
+        Ok(Self {
+            lengths: lengths.to_vec(),
+            codes,
+        })
+    }
+
+    /// The code length of each symbol, with 0 for symbols which don't have a code.
+    pub fn code_lengths(&self) -> &[u8] {
+        &self.lengths
+    }
+
+    /// Returns the code for `symbol` (right-aligned) and its length, or `None` if it doesn't have
+    /// one.
+    pub fn code(&self, symbol: u16) -> Option<(u32, usize)> {
+        match self.lengths.get(symbol as usize) {
+            Some(&len) if len != 0 => Some((self.codes[symbol as usize], len as usize)),
+            _ => None,
+        }
+    }
+
+    /// Builds a [`HuffmanDecoder`] for this code.
+    pub fn decoder(&self) -> std::io::Result<HuffmanDecoder> {
+        HuffmanDecoder::from_code_lengths(&self.lengths, true)
+    }
+
+    /// Writes the code for `symbol` to `buf`, starting with its most significant bit.  See
+    /// [`HuffmanDecoder::decode`].
+    pub fn encode<Order: BitOrder, B: BitBufMut<Order> + ?Sized>(
+        &self,
+        buf: &mut B,
+        symbol: u16,
+    ) -> std::io::Result<()> {
+        let (code, len) = self.code(symbol).ok_or_else(|| {
+            std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!("Symbol {} doesn't have a Huffman code", symbol),
+            )
+        })?;
+        let code = if Order::MSB_FIRST {
+            code
+        } else {
+            code.reverse_bits() >> (32 - len)
+        };
+        buf.put_bits_from::<u32, Order::FieldOrder>(len, code)
+    }
+}
+
+/// Computes optimal length-limited code lengths for `frequencies` with the package-merge
+/// algorithm.
+fn package_merge(frequencies: &[u64], max_len: usize) -> std::io::Result<Vec<u8>> {
+    enum Node {
+        Leaf(usize),
+        Package(usize, usize),
+    }
+
+    let mut lengths = vec![0u8; frequencies.len()];
+    let mut used: Vec<usize> = (0..frequencies.len())
+        .filter(|&symbol| frequencies[symbol] != 0)
+        .collect();
+    match used.len() {
+        0 => return Ok(lengths),
+        1 => {
+            lengths[used[0]] = 1;
+            return Ok(lengths);
+        }
+        _ => {}
+    }
+    if max_len == 0 || max_len > MAX_CODE_LENGTH || (used.len() - 1) >> max_len != 0 {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            format!(
+                "A maximum code length of {} can't be used for {} symbols",
+                max_len,
+                used.len()
+            ),
+        ));
+    }
+    used.sort_by_key(|&symbol| frequencies[symbol]);
+
+    // Items are (weight, node), and nodes are stored in an arena so packages can refer to the
+    // items they were made from
+    let mut nodes: Vec<Node> = used.iter().map(|&symbol| Node::Leaf(symbol)).collect();
+    let leaves: Vec<(u64, usize)> = used
+        .iter()
+        .enumerate()
+        .map(|(node, &symbol)| (frequencies[symbol], node))
+        .collect();
+    let mut items = leaves.clone();
+    for _ in 1..max_len {
+        let mut packages = Vec::with_capacity(items.len() / 2);
+        for pair in items.chunks_exact(2) {
+            nodes.push(Node::Package(pair[0].1, pair[1].1));
+            packages.push((pair[0].0.saturating_add(pair[1].0), nodes.len() - 1));
+        }
+        // Merge the packages with the leaves, keeping them sorted by weight
+        items = Vec::with_capacity(leaves.len() + packages.len());
+        let (mut l, mut p) = (0, 0);
+        while l < leaves.len() || p < packages.len() {
+            if p == packages.len() || (l < leaves.len() && leaves[l].0 <= packages[p].0) {
+                items.push(leaves[l]);
+                l += 1;
+            } else {
+                items.push(packages[p]);
+                p += 1;
+            }
+        }
+    }
+
+    // Each symbol's code length is the number of times its leaf appears in the cheapest 2n - 2
+    // items
+    let mut stack: Vec<usize> = items[..2 * used.len() - 2]
+        .iter()
+        .map(|&(_, node)| node)
+        .collect();
+    while let Some(node) = stack.pop() {
+        match nodes[node] {
+            Node::Leaf(symbol) => lengths[symbol] += 1,
+            Node::Package(a, b) => {
+                stack.push(a);
+                stack.push(b);
+            }
+        }
+    }
+    Ok(lengths)
+}
+
+/// The order in which the code lengths of the code-length code are written in DEFLATE
+const CODE_LENGTH_ORDER: [usize; 19] = [
+    16, 17, 18, 0, 8, 7, 9, 6, 10, 5, 11, 4, 12, 3, 13, 2, 14, 1, 15,
+];
+
+/// Run-length encodes code lengths with DEFLATE's code-length alphabet, returning (symbol, extra
+/// bits value) pairs.
+fn run_length_encode(lengths: &[u8]) -> Vec<(u16, u32)> {
+    let mut symbols = Vec::new();
+    let mut i = 0;
+    while i < lengths.len() {
+        let len = lengths[i];
+        let mut run = lengths[i..].iter().take_while(|&&l| l == len).count();
+        i += run;
+        if len == 0 {
+            while run >= 11 {
+                let count = run.min(138);
+                symbols.push((18, (count - 11) as u32));
+                run -= count;
+            }
+            if run >= 3 {
+                symbols.push((17, (run - 3) as u32));
+                run = 0;
+            }
+        } else {
+            symbols.push((len as u16, 0));
+            run -= 1;
+            while run >= 3 {
+                let count = run.min(6);
+                symbols.push((16, (count - 3) as u32));
+                run -= count;
+            }
+        }
+        symbols.extend(std::iter::repeat_n((len as u16, 0), run));
+    }
+    symbols
+}
+
+/// The number of extra bits after each of DEFLATE's code-length symbols
+fn code_length_extra_bits(symbol: u16) -> usize {
+    match symbol {
+        16 => 2,
+        17 => 3,
+        18 => 7,
+        _ => 0,
+    }
+}
+
+/// Huffman decoding from a `BitBuf`.
+pub trait BitBufHuffmanExts<Order: BitOrder = Msb0>: BitBuf<Order> {
+    /// Reads a single code and returns its symbol.  See [`HuffmanDecoder::decode`].
+    fn get_huffman_symbol(&mut self, decoder: &HuffmanDecoder) -> std::io::Result<u16> {
+        decoder.decode(self)
+    }
+}
+
+impl<Order: BitOrder, T: BitBuf<Order> + ?Sized> BitBufHuffmanExts<Order> for T {}
+
+/// Huffman encoding to a `BitBufMut`.
+pub trait BitBufMutHuffmanExts<Order: BitOrder = Msb0>: BitBufMut<Order> {
+    /// Writes the code for `symbol`.  See [`HuffmanEncoder::encode`].
+    fn put_huffman_symbol(&mut self, encoder: &HuffmanEncoder, symbol: u16) -> std::io::Result<()> {
+        encoder.encode(self, symbol)
+    }
+}
+
+impl<Order: BitOrder, T: BitBufMut<Order> + ?Sized> BitBufMutHuffmanExts<Order> for T {}
+
+/// Reads of DEFLATE's Huffman code descriptions, which only exist in LSB-first buffers.
+pub trait BitBufDeflateExts: BitBuf<Lsb0> {
+    /// Reads `num_lengths` code lengths the way DEFLATE writes them in the header of a dynamic
+    /// block.  See [`BitBufMutDeflateExts::put_deflate_code_lengths`].
+    fn get_deflate_code_lengths(&mut self, num_lengths: usize) -> std::io::Result<Vec<u8>> {
+        let num_code_length_codes = self.get_bits_as::<usize, LittleEndian>(4)? + 4;
+        let mut code_length_lengths = [0u8; 19];
+        for &symbol in &CODE_LENGTH_ORDER[..num_code_length_codes] {
+            code_length_lengths[symbol] = self.get_u3()?.into();
+        }
+        let decoder = HuffmanDecoder::from_code_lengths(&code_length_lengths, true)?;
+
+        let mut lengths = Vec::with_capacity(num_lengths);
+        while lengths.len() < num_lengths {
+            let symbol = decoder.decode(self)?;
+            let extra = self.get_bits_as::<usize, LittleEndian>(code_length_extra_bits(symbol))?;
+            let (len, count) = match symbol {
+                0..=15 => (symbol as u8, 1),
+                16 => match lengths.last() {
+                    Some(&prev) => (prev, extra + 3),
+                    None => {
+                        return Err(std::io::Error::new(
+                            std::io::ErrorKind::InvalidData,
+                            "Code length repeat with no previous length",
+                        ))
+                    }
+                },
+                17 => (0, extra + 3),
+                _ => (0, extra + 11),
+            };
+            if lengths.len() + count > num_lengths {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::InvalidData,
+                    format!(
+                        "Code length run goes past the end of the {} lengths",
+                        num_lengths
+                    ),
+                ));
+            }
+            lengths.extend(std::iter::repeat_n(len, count));
+        }
+        Ok(lengths)
+    }
+}
+
+impl<T: BitBuf<Lsb0> + ?Sized> BitBufDeflateExts for T {}
+
+/// Writes of DEFLATE's Huffman code descriptions.
+pub trait BitBufMutDeflateExts: BitBufMut<Lsb0> {
+    /// Writes code lengths (of at most 15 bits) the way DEFLATE does in the header of a dynamic
+    /// block (RFC 1951 section 3.2.7), after HLIT and HDIST: the number of code-length code
+    /// lengths minus 4, the code-length code lengths in DEFLATE's order, and then the run-length
+    /// encoded lengths.  The number of lengths isn't written.
+    fn put_deflate_code_lengths(&mut self, lengths: &[u8]) -> std::io::Result<()> {
+        if let Some(&len) = lengths.iter().find(|&&len| len > 15) {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!("Code length ({}) is longer than DEFLATE allows (15)", len),
+            ));
+        }
+        let symbols = run_length_encode(lengths);
+        let mut frequencies = [0u64; 19];
+        for &(symbol, _) in &symbols {
+            frequencies[symbol as usize] += 1;
+        }
+        // A lone symbol would get an incomplete 1-bit code, which zlib rejects, so unused symbols
+        // make up at least 2
+        let num_used = frequencies
+            .iter()
+            .filter(|&&frequency| frequency > 0)
+            .count();
+        for dummy in frequencies
+            .iter_mut()
+            .filter(|frequency| **frequency == 0)
+            .take(2usize.saturating_sub(num_used))
+        {
+            *dummy = 1;
+        }
+        let code_length_code = HuffmanEncoder::from_frequencies(&frequencies, 7)?;
+        let code_length_lengths = code_length_code.code_lengths();
+        let num_code_length_codes = CODE_LENGTH_ORDER
+            .iter()
+            .rposition(|&symbol| code_length_lengths[symbol] != 0)
+            .map_or(0, |last| last + 1)
+            .max(4);
+
+        self.put_bits_from::<usize, LittleEndian>(4, num_code_length_codes - 4)?;
+        for &symbol in &CODE_LENGTH_ORDER[..num_code_length_codes] {
+            self.put_u3(u3::new(code_length_lengths[symbol]))?;
+        }
+        for (symbol, extra) in symbols {
+            code_length_code.encode(self, symbol)?;
+            self.put_bits_from::<u32, LittleEndian>(code_length_extra_bits(symbol), extra)?;
+        }
+        Ok(())
+    }
+}
+
+impl<T: BitBufMut<Lsb0> + ?Sized> BitBufMutDeflateExts for T {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The code lengths of DEFLATE's fixed literal/length code
+    fn fixed_literal_lengths() -> Vec<u8> {
+        let mut lengths = vec![8; 144];
+        lengths.extend([9; 112]);
+        lengths.extend([7; 24]);
+        lengths.extend([8; 8]);
+        lengths
+    }
+
+    #[test]
+    fn test_fixed_deflate_code() {
+        let decoder = HuffmanDecoder::from_code_lengths(&fixed_literal_lengths(), false).unwrap();
+        let mut bits = Bits::copy_from_bit_slice(bits![
+            0, 0, 1, 1, 0, 0, 0, 0, // 0
+            1, 1, 0, 0, 1, 0, 0, 0, 0, // 144
+            0, 0, 0, 0, 0, 0, 0, // 256
+            1, 1, 0, 0, 0, 1, 1, 1, // 287
+            1, 1, 1, 1, 1, 1, 1, 1, 1, // 255
+        ]);
+        assert_eq!(decoder.decode(&mut bits).unwrap(), 0);
+        assert_eq!(decoder.decode(&mut bits).unwrap(), 144);
+        assert_eq!(decoder.decode(&mut bits).unwrap(), 256);
+        assert_eq!(bits.get_huffman_symbol(&decoder).unwrap(), 287);
+        assert_eq!(bits.get_huffman_symbol(&decoder).unwrap(), 255);
+        assert!(!bits.has_remaining_bits());
+        let error = decoder.decode(&mut bits).unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::UnexpectedEof);
+    }
+
+    #[test]
+    fn test_jpeg_counts() {
+        // The standard JPEG luminance DC table (ITU-T T.81 table K.3)
+        let counts = [0, 1, 5, 1, 1, 1, 1, 1, 1, 0, 0, 0, 0, 0, 0, 0];
+        let symbols: Vec<u16> = (0..12).collect();
+        assert!(HuffmanDecoder::from_counts(&counts, &symbols, false).is_err());
+        let decoder = HuffmanDecoder::from_counts(&counts, &symbols, true).unwrap();
+
+        let mut bits = Bits::copy_from_bit_slice(bits![
+            0, 0, // 0
+            1, 0, 1, // 4
+            1, 1, 1, 1, 1, 1, 1, 1, 0, // 11
+            1, 1, 1, 1, 1, 1, 1, 1, 1, // Not a code
+        ]);
+        assert_eq!(decoder.decode(&mut bits).unwrap(), 0);
+        assert_eq!(decoder.decode(&mut bits).unwrap(), 4);
+        assert_eq!(decoder.decode(&mut bits).unwrap(), 11);
+        let error = decoder.decode(&mut bits).unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn test_invalid_code_sets() {
+        let error = HuffmanDecoder::from_code_lengths(&[1, 1, 1], false).unwrap_err();
+        assert!(error.to_string().contains("over-subscribed"));
+        let error = HuffmanDecoder::from_code_lengths(&[1, 1, 1], true).unwrap_err();
+        assert!(error.to_string().contains("over-subscribed"));
+        let error = HuffmanDecoder::from_code_lengths(&[1, 2], false).unwrap_err();
+        assert!(error.to_string().contains("incomplete"));
+        assert!(HuffmanDecoder::from_code_lengths(&[33, 1], true).is_err());
+
+        // A single code is allowed if incomplete code sets are
+        let decoder = HuffmanDecoder::from_code_lengths(&[0, 1], true).unwrap();
+        let mut bits = Bits::copy_from_bit_slice(bits![0, 1]);
+        assert_eq!(decoder.decode(&mut bits).unwrap(), 1);
+        assert!(decoder.decode(&mut bits).is_err());
+    }
+
+    /// Lengths 1, 2, ..., 19, 20, 20: codes 0, 10, 110, ..., 1111111111111111111(0|1)
+    fn long_code_lengths() -> Vec<u8> {
+        let mut lengths: Vec<u8> = (1..=20).collect();
+        lengths.push(20);
+        lengths
+    }
+
+    fn encode_long_code(symbol: usize) -> BitVec {
+        let mut code = BitVec::repeat(true, symbol.min(19));
+        if symbol < 20 {
+            code.push(false);
+        } else {
+            code.push(true);
+        }
+        code
+    }
+
+    #[test]
+    fn test_multi_level_table() {
+        let decoder = HuffmanDecoder::from_code_lengths(&long_code_lengths(), false).unwrap();
+        assert_eq!(decoder.max_code_length(), 20);
+        let symbols = [20, 0, 19, 9, 10, 17, 1];
+        let mut data = BitVec::new();
+        for symbol in symbols {
+            data.extend_from_bitslice(&encode_long_code(symbol));
+        }
+        let mut bits = Bits::from(data);
+        for symbol in symbols {
+            assert_eq!(decoder.decode(&mut bits).unwrap(), symbol as u16);
+        }
+        assert!(!bits.has_remaining_bits());
+    }
+
+    #[test]
+    fn test_chain_boundary() {
+        let decoder = HuffmanDecoder::from_code_lengths(&long_code_lengths(), false).unwrap();
+        let symbols = [18, 3, 20, 0, 12];
+        let mut data = BitVec::new();
+        for symbol in symbols {
+            data.extend_from_bitslice(&encode_long_code(symbol));
+        }
+        // Split the data at every position, so codes span the boundary at every offset
+        for split in 0..data.len() {
+            let left = Bits::copy_from_bit_slice(&data[..split]);
+            let right = Bits::copy_from_bit_slice(&data[split..]);
+            let mut chain = left.chain(right);
+            for symbol in symbols {
+                assert_eq!(
+                    decoder.decode(&mut chain).unwrap(),
+                    symbol as u16,
+                    "split at {}",
+                    split
+                );
+            }
+            assert!(!chain.has_remaining_bits());
+        }
+    }
+
+    #[test]
+    fn test_package_merge() {
+        let frequencies = [1, 1, 2, 4, 8, 16, 32, 64];
+        let encoder = HuffmanEncoder::from_frequencies(&frequencies, 15).unwrap();
+        assert_eq!(encoder.code_lengths(), [7, 7, 6, 5, 4, 3, 2, 1]);
+
+        // Limiting the length still gives a complete code
+        let encoder = HuffmanEncoder::from_frequencies(&frequencies, 4).unwrap();
+        assert!(encoder.code_lengths().iter().all(|&len| len <= 4));
+        assert!(HuffmanDecoder::from_code_lengths(encoder.code_lengths(), false).is_ok());
+        assert_eq!(encoder.code_lengths(), [4, 4, 4, 4, 4, 4, 3, 1]);
+
+        // Unused symbols don't get a code, and a single used symbol gets a 1-bit code
+        let encoder = HuffmanEncoder::from_frequencies(&[0, 5, 0], 15).unwrap();
+        assert_eq!(encoder.code_lengths(), [0, 1, 0]);
+        assert_eq!(encoder.code(1), Some((0, 1)));
+        assert_eq!(encoder.code(0), None);
+
+        assert!(HuffmanEncoder::from_frequencies(&[1; 9], 3).is_err());
+        assert!(HuffmanEncoder::from_frequencies(&[1; 8], 3).is_ok());
+    }
+
+    #[test]
+    fn test_encoder_matches_decoder() {
+        let encoder = HuffmanEncoder::from_code_lengths(&fixed_literal_lengths()).unwrap();
+        assert_eq!(encoder.code(0), Some((0b00110000, 8)));
+        assert_eq!(encoder.code(144), Some((0b110010000, 9)));
+        assert_eq!(encoder.code(256), Some((0, 7)));
+        assert_eq!(encoder.code(287), Some((0b11000111, 8)));
+        assert!(HuffmanEncoder::from_code_lengths(&[1, 1, 1]).is_err());
+
+        let mut bits_mut = BitsMut::new();
+        assert!(bits_mut.put_huffman_symbol(&encoder, 288).is_err());
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let text = b"this is an example of a huffman tree, with some repetition: aaaaaaaabbbbcc";
+        let mut frequencies = [0u64; 256];
+        for &byte in text {
+            frequencies[byte as usize] += 1;
+        }
+        let encoder = HuffmanEncoder::from_frequencies(&frequencies, 6).unwrap();
+
+        let mut bits_mut = LsbBitsMut::new();
+        bits_mut
+            .put_deflate_code_lengths(encoder.code_lengths())
+            .unwrap();
+        for &byte in text {
+            bits_mut.put_huffman_symbol(&encoder, byte as u16).unwrap();
+        }
+
+        let mut bits = bits_mut.freeze();
+        let lengths = bits.get_deflate_code_lengths(256).unwrap();
+        assert_eq!(lengths, encoder.code_lengths());
+        let decoder = HuffmanDecoder::from_code_lengths(&lengths, false).unwrap();
+        for &byte in text {
+            assert_eq!(bits.get_huffman_symbol(&decoder).unwrap(), byte as u16);
+        }
+        assert!(!bits.has_remaining_bits());
+    }
+
+    #[test]
+    fn test_deflate_code_lengths_run_lengths() {
+        let mut lengths = vec![0u8; 200];
+        lengths.extend([8; 10]);
+        lengths.extend([0; 5]);
+        lengths.extend([3, 3, 4]);
+        assert_eq!(
+            run_length_encode(&lengths),
+            [
+                (18, 127),
+                (18, 51),
+                (8, 0),
+                (16, 3),
+                (16, 0),
+                (17, 2),
+                (3, 0),
+                (3, 0),
+                (4, 0)
+            ]
+        );
+
+        let mut bits_mut = LsbBitsMut::new();
+        bits_mut.put_deflate_code_lengths(&lengths).unwrap();
+        let mut bits = bits_mut.freeze();
+        assert_eq!(
+            bits.get_deflate_code_lengths(lengths.len()).unwrap(),
+            lengths
+        );
+
+        // Reading fewer lengths than were written fails if a run crosses the end
+        let mut bits_mut = LsbBitsMut::new();
+        bits_mut.put_deflate_code_lengths(&lengths).unwrap();
+        assert!(bits_mut.freeze().get_deflate_code_lengths(100).is_err());
+    }
+
+    #[test]
+    fn test_deflate_code_lengths_single_symbol() {
+        // Each of these run-length encodes to a single code-length symbol, or none at all
+        for lengths in [vec![5; 3], vec![0; 19], vec![0; 2], vec![]] {
+            let mut bits_mut = LsbBitsMut::new();
+            bits_mut.put_deflate_code_lengths(&lengths).unwrap();
+            let mut bits = bits_mut.freeze();
+            assert_eq!(
+                bits.clone()
+                    .get_deflate_code_lengths(lengths.len())
+                    .unwrap(),
+                lengths
+            );
+
+            // The code-length code is complete, as zlib requires
+            let num_code_length_codes = bits.get_bits_as::<usize, LittleEndian>(4).unwrap() + 4;
+            let mut code_length_lengths = [0u8; 19];
+            for &symbol in &CODE_LENGTH_ORDER[..num_code_length_codes] {
+                code_length_lengths[symbol] = bits.get_u3().unwrap().into();
+            }
+            assert!(HuffmanDecoder::from_code_lengths(&code_length_lengths, false).is_ok());
+        }
+    }
+
+    #[test]
+    fn test_lsb_codes_are_reversed() {
+        // The fixed literal/length codes for 0 (00110000) and 144 (110010000) are written starting
+        // with their most significant bit
+        let encoder = HuffmanEncoder::from_code_lengths(&fixed_literal_lengths()).unwrap();
+        let mut bits_mut = LsbBitsMut::new();
+        bits_mut.put_huffman_symbol(&encoder, 0).unwrap();
+        bits_mut.put_huffman_symbol(&encoder, 144).unwrap();
+        assert_eq!(
+            bits_mut[..],
+            lsb_bits![0, 0, 1, 1, 0, 0, 0, 0, 1, 1, 0, 0, 1, 0, 0, 0, 0]
+        );
+
+        let decoder = encoder.decoder().unwrap();
+        let text = b"an LSB-first stream, as DEFLATE writes";
+        let mut bits_mut = LsbBitsMut::new();
+        for &byte in text {
+            bits_mut.put_huffman_symbol(&encoder, byte as u16).unwrap();
+        }
+        let mut bits = bits_mut.freeze();
+        for &byte in text {
+            assert_eq!(bits.get_huffman_symbol(&decoder).unwrap(), byte as u16);
+        }
+        assert!(!bits.has_remaining_bits());
+    }
+
+    /// Decodes the compressed data of a DEFLATE block with the given codes, up to its end-of-block
+    /// symbol.
+    fn inflate_block(
+        bits: &mut LsbBits,
+        literal_lengths: &HuffmanDecoder,
+        distances: &HuffmanDecoder,
+    ) -> Vec<u8> {
+        const LENGTH_BASES: [usize; 29] = [
+            3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 17, 19, 23, 27, 31, 35, 43, 51, 59, 67, 83, 99,
+            115, 131, 163, 195, 227, 258,
+        ];
+        const DISTANCE_BASES: [usize; 30] = [
+            1, 2, 3, 4, 5, 7, 9, 13, 17, 25, 33, 49, 65, 97, 129, 193, 257, 385, 513, 769, 1025,
+            1537, 2049, 3073, 4097, 6145, 8193, 12289, 16385, 24577,
+        ];
+        let mut output = Vec::new();
+        loop {
+            let symbol = bits.get_huffman_symbol(literal_lengths).unwrap() as usize;
+            match symbol {
+                0..=255 => output.push(symbol as u8),
+                256 => return output,
+                _ => {
+                    let index = symbol - 257;
+                    let extra = if (8..28).contains(&index) {
+                        index / 4 - 1
+                    } else {
+                        0
+                    };
+                    let length = LENGTH_BASES[index]
+                        + bits.get_bits_as::<usize, LittleEndian>(extra).unwrap();
+                    let index = bits.get_huffman_symbol(distances).unwrap() as usize;
+                    let extra = (index / 2).saturating_sub(1);
+                    let distance = DISTANCE_BASES[index]
+                        + bits.get_bits_as::<usize, LittleEndian>(extra).unwrap();
+                    for _ in 0..length {
+                        output.push(output[output.len() - distance]);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_deflate_dynamic_block() {
+        let text: &[u8] =
+            b"The quick brown fox jumps over the lazy dog. The lazy dog sleeps while the \
+            quick brown fox jumps again and again over the sleeping dog.";
+        // `text` compressed by zlib (level 9, raw DEFLATE), which wrote a single dynamic block
+        let mut bits = LsbBits::from_static_bytes(&[
+            0x6d, 0x8c, 0xd1, 0x0d, 0x80, 0x20, 0x10, 0x43, 0x57, 0xe9, 0x04, 0x4e, 0xc3, 0x02,
+            0xa8, 0x27, 0x9c, 0xe2, 0xa1, 0x20, 0xa2, 0x4e, 0x2f, 0x21, 0xc6, 0xf8, 0xe1, 0x5f,
+            0x9b, 0xbe, 0x3e, 0x65, 0x09, 0x6b, 0xe2, 0x6e, 0x42, 0x1b, 0x7c, 0x16, 0x0c, 0xfe,
+            0xc0, 0x98, 0xe6, 0x25, 0xc2, 0xef, 0x14, 0xb0, 0x95, 0xd9, 0xe9, 0xeb, 0x44, 0xef,
+            0x4d, 0x03, 0xf5, 0x69, 0x88, 0x8e, 0xa8, 0x60, 0xd9, 0xb2, 0xa3, 0xca, 0xfd, 0x6b,
+            0xb4, 0xd1, 0x2c, 0xd0, 0xd2, 0x3f, 0xe9, 0xd5, 0xd6, 0x3f, 0x8b, 0xa9, 0xea, 0x1b,
+        ]);
+        // BFINAL = 1, BTYPE = 2 (dynamic Huffman codes)
+        assert!(bits.get_bool().unwrap());
+        assert_eq!(bits.get_u2().unwrap(), u2::new(2));
+        let num_literal_lengths = bits.get_bits_as::<usize, LittleEndian>(5).unwrap() + 257;
+        let num_distances = bits.get_bits_as::<usize, LittleEndian>(5).unwrap() + 1;
+        let lengths = bits
+            .get_deflate_code_lengths(num_literal_lengths + num_distances)
+            .unwrap();
+        let (literal_lengths, distances) = lengths.split_at(num_literal_lengths);
+        let literal_lengths = HuffmanDecoder::from_code_lengths(literal_lengths, false).unwrap();
+        let distances = HuffmanDecoder::from_code_lengths(distances, true).unwrap();
+        assert_eq!(inflate_block(&mut bits, &literal_lengths, &distances), text);
+        // Only the padding to the end of the last byte is left
+        assert!(bits.remaining_bits() < 8);
+
+        // A dynamic block written with the same code lengths decodes the same way
+        let mut bits_mut = LsbBitsMut::new();
+        bits_mut.put_bool(true).unwrap();
+        bits_mut.put_u2(u2::new(2)).unwrap();
+        bits_mut
+            .put_bits_from::<usize, LittleEndian>(5, num_literal_lengths - 257)
+            .unwrap();
+        bits_mut
+            .put_bits_from::<usize, LittleEndian>(5, num_distances - 1)
+            .unwrap();
+        bits_mut.put_deflate_code_lengths(&lengths).unwrap();
+        let encoder = HuffmanEncoder::from_code_lengths(&lengths[..num_literal_lengths]).unwrap();
+        for &byte in text {
+            bits_mut.put_huffman_symbol(&encoder, byte as u16).unwrap();
+        }
+        bits_mut.put_huffman_symbol(&encoder, 256).unwrap();
+
+        let mut bits = bits_mut.freeze();
+        bits.advance_bits(13);
+        assert_eq!(
+            bits.get_deflate_code_lengths(num_literal_lengths + num_distances)
+                .unwrap(),
+            lengths
+        );
+        assert_eq!(inflate_block(&mut bits, &literal_lengths, &distances), text);
+    }
+}
@@ -1,4 +1,4 @@
-use crate::prelude::BitBufMut;
+use crate::prelude::{BitBufMut, BitOrder};
 
 pub struct Limit<T> {
     inner: T,
@@ -31,14 +31,14 @@ impl<T> Limit<T> {
     }
 }
 
-impl<T: BitBufMut> BitBufMut for Limit<T> {
+impl<Order: BitOrder, T: BitBufMut<Order>> BitBufMut<Order> for Limit<T> {
     fn advance_mut_bits(&mut self, count: usize) {
         assert!(count <= self.limit);
         self.inner.advance_mut_bits(count);
         self.limit -= count;
     }
 
-    fn chunk_mut_bits(&mut self) -> &mut crate::prelude::BitSlice {
+    fn chunk_mut_bits(&mut self) -> &mut bitvec::slice::BitSlice<u8, Order> {
         let chunk = self.inner.chunk_mut_bits();
         let end = std::cmp::min(chunk.len(), self.limit);
         &mut chunk[..end]
@@ -0,0 +1,206 @@
+use std::ops::{Deref, Range};
+
+use bitvec::view::BitView;
+use bytes::Bytes;
+
+use crate::prelude::*;
+
+/// The LSB-first counterpart of [`Bits`]: a cheaply cloneable, immutable view of bytes whose bits
+/// are read least-significant first.  It's a `BitBuf<Lsb0>`, so all of the `BitBuf` ext traits apply
+/// to it: [`LittleEndian`] reads a field in the order its bits are stored.
+#[derive(Clone, Debug, Eq)]
+pub struct LsbBits {
+    pub(crate) inner: Bytes,
+    /// The start of this instance's view of the underlying storage
+    pub(crate) bit_start: usize,
+    /// How many bits, from bit_start, are part of this view
+    pub(crate) bit_len: usize,
+}
+
+impl LsbBits {
+    /// Creates a new [`LsbBits`] from an instance of [`Bytes`]
+    pub fn from_bytes(bytes: Bytes) -> Self {
+        let bit_len = bytes.len() * 8;
+        Self {
+            inner: bytes,
+            bit_start: 0,
+            bit_len,
+        }
+    }
+
+    pub fn from_static_bytes(bytes: &'static [u8]) -> Self {
+        Self::from_bytes(Bytes::from_static(bytes))
+    }
+
+    /// Creates a new `LsbBits` instance from the given u8 slice by copying it.
+    pub fn copy_from_bytes(bytes: &[u8]) -> Self {
+        Self::from_bytes(Bytes::copy_from_slice(bytes))
+    }
+
+    /// Create a slice corresponding to the given range, which is given in bits.  The given range
+    /// is relative to the start of the buffer, not the current position.
+    pub fn slice_bits(&self, range: Range<usize>) -> Self {
+        assert!(
+            range.end <= self.bit_start + self.bit_len,
+            "Range beyond LsbBits length"
+        );
+        Self {
+            inner: self.inner.clone(),
+            bit_start: self.bit_start + range.start,
+            bit_len: range.end - range.start,
+        }
+    }
+
+    /// Returns the number of bits contained in this `LsbBits`
+    pub fn len_bits(&self) -> usize {
+        self.bit_len
+    }
+
+    /// Returns the number of _complete_ bytes contained in this `LsbBits`.
+    pub fn len_bytes(&self) -> usize {
+        self.bit_len / 8
+    }
+
+    /// Returns true if the `LsbBits` has a length of 0.
+    pub fn is_empty(&self) -> bool {
+        self.bit_len == 0
+    }
+}
+
+impl PartialEq for LsbBits {
+    fn eq(&self, other: &Self) -> bool {
+        **self == **other
+    }
+}
+
+impl Deref for LsbBits {
+    type Target = LsbBitSlice;
+
+    fn deref(&self) -> &Self::Target {
+        &self.inner.view_bits()[self.bit_start..self.bit_start + self.bit_len]
+    }
+}
+
+impl BitBuf<Lsb0> for LsbBits {
+    fn advance_bits(&mut self, count: usize) {
+        assert!(
+            count <= self.remaining_bits(),
+            "advance past end of LsbBits"
+        );
+        self.bit_start += count;
+        self.bit_len -= count;
+    }
+
+    fn remaining_bits(&self) -> usize {
+        self.bit_len
+    }
+
+    fn chunk_bits(&self) -> &LsbBitSlice {
+        self
+    }
+
+    fn chunk_bytes(&self) -> &[u8] {
+        assert!(self.byte_aligned());
+
+        let byte_start = self.bit_start / 8;
+        &self.inner[byte_start..byte_start + self.bit_len / 8]
+    }
+
+    fn byte_aligned(&self) -> bool {
+        self.bit_start % 8 == 0 && self.bit_len % 8 == 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_get_lsb_first() {
+        // The DEFLATE block header of a fixed-Huffman final block: BFINAL = 1, BTYPE = 01
+        let mut bits = LsbBits::from_static_bytes(&[0b0000_0011]);
+        assert!(bits.get_bool().unwrap());
+        assert_eq!(bits.get_u2().unwrap(), u2::new(0b01));
+        assert_eq!(bits.remaining_bits(), 5);
+
+        let mut bits = LsbBits::from_static_bytes(&[0b1010_0110, 0b0000_1101]);
+        assert_eq!(bits.get_u4().unwrap(), u4::new(0b0110));
+        // A field which crosses a byte boundary gets its low bits from the first byte
+        assert_eq!(
+            bits.get_bits_as::<u16, LittleEndian>(8).unwrap(),
+            0b1101_1010
+        );
+        assert_eq!(
+            bits.get_uN::<LittleEndian, 4, u4, u8>().unwrap(),
+            u4::new(0)
+        );
+        assert!(bits.get_bool().is_err());
+    }
+
+    #[test]
+    fn test_get_matches_bit_order() {
+        let data = [0x5a, 0xc3, 0x17, 0xe8, 0x99, 0x01, 0xfe, 0x42, 0x7d, 0x30];
+        for start in 0..8 {
+            for n in 1..=64 {
+                let mut bits = LsbBits::copy_from_bytes(&data);
+                bits.advance_bits(start);
+                let expected = bits[..n]
+                    .iter()
+                    .by_vals()
+                    .enumerate()
+                    .fold(0u64, |value, (i, bit)| value | (bit as u64) << i);
+                assert_eq!(bits.get_bits_as::<u64, LittleEndian>(n).unwrap(), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn test_get_multi_byte() {
+        let mut bits = LsbBits::from_static_bytes(&[0x34, 0x12, 0x34, 0x12]);
+        assert_eq!(bits.get_u16::<LittleEndian>().unwrap(), 0x1234);
+        assert_eq!(bits.get_u16::<BigEndian>().unwrap(), 0x3412);
+
+        // LittleEndian reads fields in the order their bits are stored, so the unaligned path
+        // gives the same result as the byte-aligned one
+        let mut bits = LsbBits::from_static_bytes(&[0x68, 0x24, 0x68, 0x24, 0x00]);
+        bits.advance_bits(1);
+        assert!(!bits.byte_aligned());
+        assert_eq!(bits.get_u16::<LittleEndian>().unwrap(), 0x1234);
+        // Like a little-endian read from an MSB-first buffer, a big-endian read depends on where
+        // the value starts: its segments are the 7 bits in the first byte (0x34), then 8 bits
+        // (0x24) and then 1 bit (0)
+        assert_eq!(bits.get_u16::<BigEndian>().unwrap(), 0x6848);
+        assert!(bits.get_u8().is_err());
+
+        let mut bits = LsbBits::from_static_bytes(&[0x78, 0x56, 0x34, 0x12]);
+        assert_eq!(bits.clone().get_u32::<LittleEndian>().unwrap(), 0x12345678);
+        assert_eq!(
+            bits.get_bits_as::<u32, LittleEndian>(32).unwrap(),
+            0x12345678
+        );
+    }
+
+    #[test]
+    fn test_signed() {
+        let mut bits = LsbBits::from_static_bytes(&[0b0000_1110]);
+        assert_eq!(bits.get_bits_as::<i8, LittleEndian>(4).unwrap(), -2);
+    }
+
+    #[test]
+    fn test_take_and_cursor() {
+        let data: &'static [u8] = &[0b1010_0110, 0b0000_1101];
+        let mut take = LsbBits::from_static_bytes(data).take_bits(10);
+        assert_eq!(take.get_u4().unwrap(), u4::new(0b0110));
+        assert_eq!(take.get_u6().unwrap(), u6::new(0b01_1010));
+        assert!(take.get_bool().is_err());
+
+        // A cursor over an LSB-first slice reads it the same way
+        let mut cursor = BitCursor::new(data.view_bits::<Lsb0>());
+        cursor.set_position(4);
+        assert_eq!(
+            cursor.get_u12::<LittleEndian>().unwrap(),
+            u12::new(0b1101_1010)
+        );
+        assert!(!cursor.has_remaining_bits());
+    }
+}
@@ -0,0 +1,281 @@
+use std::ops::{Deref, DerefMut};
+
+use bitvec::view::BitView;
+use bytes::{buf::UninitSlice, BytesMut};
+
+use crate::prelude::*;
+
+use super::util::bytes_needed;
+
+/// The LSB-first counterpart of [`BitsMut`]: a growable buffer whose bits are written
+/// least-significant first.  It's a `BitBufMut<Lsb0>`, so all of the `BitBufMut` ext traits apply
+/// to it.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct LsbBitsMut {
+    /// Zero-filled storage, which may extend past the bits written so far
+    inner: BytesMut,
+    /// How many bits have been written
+    bit_len: usize,
+}
+
+impl LsbBitsMut {
+    /// Creates a new, empty `LsbBitsMut`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a new, empty `LsbBitsMut` which can hold at least `capacity` bits without
+    /// reallocating.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            inner: BytesMut::with_capacity(bytes_needed(capacity)),
+            bit_len: 0,
+        }
+    }
+
+    /// Returns the number of bits written to this `LsbBitsMut`.
+    pub fn len_bits(&self) -> usize {
+        self.bit_len
+    }
+
+    /// Returns true if nothing has been written to this `LsbBitsMut`.
+    pub fn is_empty(&self) -> bool {
+        self.bit_len == 0
+    }
+
+    /// Converts `self` into an immutable [`LsbBits`].  Any bits after the end of the written data
+    /// in the last byte are zero.
+    pub fn freeze(mut self) -> LsbBits {
+        self.inner.truncate(bytes_needed(self.bit_len));
+        LsbBits {
+            inner: self.inner.freeze(),
+            bit_start: 0,
+            bit_len: self.bit_len,
+        }
+    }
+}
+
+impl Deref for LsbBitsMut {
+    type Target = LsbBitSlice;
+
+    fn deref(&self) -> &Self::Target {
+        &self.inner.view_bits()[..self.bit_len]
+    }
+}
+
+impl DerefMut for LsbBitsMut {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.inner.view_bits_mut()[..self.bit_len]
+    }
+}
+
+impl BitBufMut<Lsb0> for LsbBitsMut {
+    fn advance_mut_bits(&mut self, count: usize) {
+        assert!(
+            self.bit_len + count <= self.inner.len() * 8,
+            "advance_mut past end"
+        );
+        self.bit_len += count;
+    }
+
+    fn chunk_mut_bits(&mut self) -> &mut LsbBitSlice {
+        if self.inner.len() * 8 - self.bit_len < 64 {
+            self.inner.resize(self.inner.len() + 8, 0);
+        }
+        &mut self.inner.view_bits_mut()[self.bit_len..]
+    }
+
+    fn chunk_mut_bytes(&mut self) -> &mut UninitSlice {
+        assert!(self.byte_aligned_mut());
+        if self.inner.len() * 8 - self.bit_len < 64 {
+            self.inner.resize(self.inner.len() + 8, 0);
+        }
+        let byte_start = self.bit_len / 8;
+        UninitSlice::new(&mut self.inner[byte_start..])
+    }
+
+    fn remaining_mut_bits(&self) -> usize {
+        usize::MAX - self.bit_len
+    }
+
+    fn byte_aligned_mut(&self) -> bool {
+        self.bit_len % 8 == 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_put_lsb_first() {
+        let mut bits_mut = LsbBitsMut::new();
+        bits_mut.put_bool(true).unwrap();
+        bits_mut.put_u2(u2::new(0b01)).unwrap();
+        bits_mut
+            .put_bits_from::<u16, LittleEndian>(9, 0b1_0110_0101u16)
+            .unwrap();
+        assert_eq!(bits_mut.len_bits(), 12);
+        assert_eq!(bits_mut[..], lsb_bits![1, 1, 0, 1, 0, 1, 0, 0, 1, 1, 0, 1]);
+        assert!(bits_mut.put_bits_from::<u8, LittleEndian>(3, 8u8).is_err());
+
+        let bits = bits_mut.freeze();
+        assert_eq!(&bits.inner[..], &[0b0010_1011, 0b0000_1011]);
+    }
+
+    #[test]
+    fn test_round_trip() {
+        for start in 0..8 {
+            let mut bits_mut = LsbBitsMut::new();
+            bits_mut
+                .put_bits_from::<u8, LittleEndian>(start, 0u8)
+                .unwrap();
+            bits_mut.put_u8(0xa5).unwrap();
+            bits_mut.put_u16::<LittleEndian>(0x1234).unwrap();
+            bits_mut.put_u16::<BigEndian>(0x1234).unwrap();
+            bits_mut.put_u32::<LittleEndian>(0xdeadbeef).unwrap();
+            bits_mut.put_bits_from::<i8, LittleEndian>(5, -3i8).unwrap();
+            bits_mut
+                .put_u64::<BigEndian>(0x0123_4567_89ab_cdef)
+                .unwrap();
+            bits_mut
+                .put_bits_from::<u64, LittleEndian>(64, u64::MAX - 1)
+                .unwrap();
+
+            let mut bits = bits_mut.freeze();
+            assert_eq!(bits.get_bits_as::<u8, LittleEndian>(start).unwrap(), 0);
+            assert_eq!(bits.get_u8().unwrap(), 0xa5);
+            assert_eq!(bits.get_u16::<LittleEndian>().unwrap(), 0x1234);
+            assert_eq!(bits.get_u16::<BigEndian>().unwrap(), 0x1234);
+            assert_eq!(bits.get_u32::<LittleEndian>().unwrap(), 0xdeadbeef);
+            assert_eq!(bits.get_bits_as::<i8, LittleEndian>(5).unwrap(), -3);
+            assert_eq!(bits.get_u64::<BigEndian>().unwrap(), 0x0123_4567_89ab_cdef);
+            assert_eq!(
+                bits.get_bits_as::<u64, LittleEndian>(64).unwrap(),
+                u64::MAX - 1
+            );
+            assert!(!bits.has_remaining_bits());
+        }
+    }
+
+    #[test]
+    fn test_byte_aligned_matches_bytes() {
+        // When aligned, little-endian values are laid out the same as `to_le_bytes`
+        let mut bits_mut = LsbBitsMut::new();
+        bits_mut.put_u32::<LittleEndian>(0x12345678).unwrap();
+        bits_mut
+            .put_bits_from::<u16, LittleEndian>(16, 0xabcdu16)
+            .unwrap();
+        let bits = bits_mut.freeze();
+        assert_eq!(&bits.inner[..], &[0x78, 0x56, 0x34, 0x12, 0xcd, 0xab]);
+    }
+
+    #[test]
+    fn test_named_round_trip() {
+        for start in 0..8 {
+            let mut bits_mut = LsbBitsMut::new();
+            bits_mut
+                .put_bits_from::<u8, LittleEndian>(start, 0)
+                .unwrap();
+            bits_mut.put_u9::<LittleEndian>(u9::new(0x1a5)).unwrap();
+            bits_mut.put_u13::<BigEndian>(u13::new(0x1234)).unwrap();
+            bits_mut
+                .put_u24::<LittleEndian>(u24::new(0xabcdef))
+                .unwrap();
+            bits_mut
+                .put_u31::<BigEndian>(u31::new(0x7654_3210))
+                .unwrap();
+            bits_mut
+                .put_u47::<LittleEndian>(u47::new(0x7fed_cba9_8765))
+                .unwrap();
+            bits_mut.put_i12::<BigEndian>(i12::new(-1000)).unwrap();
+            bits_mut
+                .put_i40::<LittleEndian>(i40::new(-0x12_3456_789a))
+                .unwrap();
+            let bits = bits_mut.freeze();
+
+            // Values which span the two halves of a chain read the same as contiguous ones
+            for split in start..bits.len_bits() {
+                let mut chain = bits
+                    .slice_bits(start..split)
+                    .chain(bits.slice_bits(split..bits.len_bits()));
+                assert_eq!(chain.get_u9::<LittleEndian>().unwrap(), u9::new(0x1a5));
+                assert_eq!(chain.get_u13::<BigEndian>().unwrap(), u13::new(0x1234));
+                assert_eq!(chain.get_u24::<LittleEndian>().unwrap(), u24::new(0xabcdef));
+                assert_eq!(chain.get_u31::<BigEndian>().unwrap(), u31::new(0x7654_3210));
+                assert_eq!(
+                    chain.get_u47::<LittleEndian>().unwrap(),
+                    u47::new(0x7fed_cba9_8765)
+                );
+                assert_eq!(chain.get_i12::<BigEndian>().unwrap(), i12::new(-1000));
+                assert_eq!(
+                    chain.get_i40::<LittleEndian>().unwrap(),
+                    i40::new(-0x12_3456_789a)
+                );
+                assert!(!chain.has_remaining_bits());
+            }
+        }
+    }
+
+    #[test]
+    fn test_little_endian_is_bit_order() {
+        // A little-endian field is bit 0 first, wherever it starts
+        let mut bits_mut = LsbBitsMut::new();
+        bits_mut.put_u1(u1::new(0)).unwrap();
+        bits_mut.put_u13::<LittleEndian>(u13::new(0x1234)).unwrap();
+        let mut expected = LsbBitVec::repeat(false, 1);
+        expected.extend((0..13).map(|i| 0x1234 >> i & 1 == 1));
+        assert_eq!(bits_mut[..], expected[..]);
+    }
+
+    fn check_many<O: ByteOrder, const N: usize>() {
+        let mask = u32::MAX >> (32 - N);
+        let values: Vec<u32> = (0..150u32)
+            .map(|i| i.wrapping_mul(0x9e37_79b9).rotate_left(i) & mask)
+            .collect();
+        for offset in 0..8 {
+            let mut bits_mut = LsbBitsMut::new();
+            let mut expected = LsbBitsMut::new();
+            bits_mut
+                .put_bits_from::<u8, LittleEndian>(offset, 0)
+                .unwrap();
+            expected
+                .put_bits_from::<u8, LittleEndian>(offset, 0)
+                .unwrap();
+            bits_mut.put_many::<O, N>(&values).unwrap();
+            for &value in &values {
+                expected.put_uN::<O, N, u32, u32>(value).unwrap();
+            }
+            assert_eq!(bits_mut[..], expected[..], "u{} at offset {}", N, offset);
+
+            let mut bits = bits_mut.freeze();
+            bits.advance_bits(offset);
+            let mut read = vec![0; values.len()];
+            bits.get_many::<O, N>(&mut read).unwrap();
+            assert_eq!(read, values, "u{} at offset {}", N, offset);
+        }
+    }
+
+    macro_rules! check_many {
+        ($($n:literal)*) => {
+            $(
+                check_many::<BigEndian, $n>();
+                check_many::<LittleEndian, $n>();
+            )*
+        };
+    }
+
+    #[test]
+    fn test_many() {
+        check_many!(1 2 3 5 7 8 9 12 13 16 17 24 25 31 32);
+    }
+
+    #[test]
+    fn test_limit() {
+        let mut limit = LsbBitsMut::new().limit_bits(10);
+        limit.put_u9::<LittleEndian>(u9::new(0x1ff)).unwrap();
+        assert!(limit.put_u2(u2::new(0)).is_err());
+        limit.put_bool(false).unwrap();
+        assert_eq!(limit.into_inner().len_bits(), 10);
+    }
+}
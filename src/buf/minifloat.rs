@@ -0,0 +1,218 @@
+/// Describes the layout of a reduced-precision, IEEE-754-style binary floating point value: a sign
+/// bit followed by `exponent_bits` of biased exponent and `mantissa_bits` of fraction.  The
+/// exponent bias is `2^(exponent_bits - 1) - 1` and, like IEEE-754, an all-ones exponent encodes
+/// infinity (zero fraction) or NaN (non-zero fraction) and an all-zeros exponent encodes zero and
+/// subnormal values.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MinifloatFormat {
+    exponent_bits: u32,
+    mantissa_bits: u32,
+}
+
+impl MinifloatFormat {
+    /// IEEE-754 binary16 (half precision)
+    pub const F16: MinifloatFormat = MinifloatFormat::new(5, 10);
+    /// bfloat16: the top 16 bits of an IEEE-754 binary32
+    pub const BF16: MinifloatFormat = MinifloatFormat::new(8, 7);
+    /// IEEE-754 binary32 (single precision)
+    pub const F32: MinifloatFormat = MinifloatFormat::new(8, 23);
+
+    /// Creates a new format with the given exponent and mantissa widths.  Values are decoded into
+    /// (and encoded from) an `f64`, so the exponent must be between 2 and 11 bits and the mantissa
+    /// between 1 and 52 bits.
+    pub const fn new(exponent_bits: u32, mantissa_bits: u32) -> Self {
+        assert!(
+            exponent_bits >= 2 && exponent_bits <= 11,
+            "exponent_bits must be in [2, 11]"
+        );
+        assert!(
+            mantissa_bits >= 1 && mantissa_bits <= 52,
+            "mantissa_bits must be in [1, 52]"
+        );
+        Self {
+            exponent_bits,
+            mantissa_bits,
+        }
+    }
+
+    pub fn exponent_bits(&self) -> u32 {
+        self.exponent_bits
+    }
+
+    pub fn mantissa_bits(&self) -> u32 {
+        self.mantissa_bits
+    }
+
+    /// The total width of a value in this format, including the sign bit.
+    pub fn bit_width(&self) -> usize {
+        (1 + self.exponent_bits + self.mantissa_bits) as usize
+    }
+
+    fn bias(&self) -> i32 {
+        (1 << (self.exponent_bits - 1)) - 1
+    }
+
+    fn max_biased_exponent(&self) -> u64 {
+        (1 << self.exponent_bits) - 1
+    }
+
+    /// Decodes the raw bits of a value in this format (right-aligned in `raw`) into an `f64`.  The
+    /// conversion is exact.
+    pub fn decode(&self, raw: u64) -> f64 {
+        let m = self.mantissa_bits;
+        let negative = (raw >> (self.exponent_bits + m)) & 1 == 1;
+        let exponent = (raw >> m) & self.max_biased_exponent();
+        let mantissa = raw & ((1 << m) - 1);
+
+        let magnitude = if exponent == self.max_biased_exponent() {
+            if mantissa == 0 {
+                f64::INFINITY
+            } else {
+                f64::NAN
+            }
+        } else if exponent == 0 {
+            // Subnormal: no implicit leading one, and the exponent is pinned to the minimum.
+            mantissa as f64 * pow2(1 - self.bias() - m as i32)
+        } else {
+            ((1 << m) | mantissa) as f64 * pow2(exponent as i32 - self.bias() - m as i32)
+        };
+
+        if negative {
+            -magnitude
+        } else {
+            magnitude
+        }
+    }
+
+    /// Encodes `value` into the raw bits of this format (right-aligned in the returned value),
+    /// rounding to nearest with ties to even.  Values too large for the format become infinity and
+    /// NaNs become a quiet NaN.
+    pub fn encode(&self, value: f64) -> u64 {
+        let m = self.mantissa_bits;
+        let sign = (value.is_sign_negative() as u64) << (self.exponent_bits + m);
+        let max_exponent = self.max_biased_exponent();
+
+        if value.is_nan() {
+            return sign | (max_exponent << m) | (1 << (m - 1));
+        }
+        if value.is_infinite() {
+            return sign | (max_exponent << m);
+        }
+
+        // Express |value| as significand * 2^exponent where significand is an integer.
+        let bits = value.abs().to_bits();
+        let f64_exponent = ((bits >> 52) & 0x7FF) as i32;
+        let f64_fraction = bits & ((1 << 52) - 1);
+        let (significand, exponent) = if f64_exponent == 0 {
+            (f64_fraction, -1074)
+        } else {
+            (f64_fraction | (1 << 52), f64_exponent - 1075)
+        };
+        if significand == 0 {
+            return sign;
+        }
+
+        // Find the unbiased exponent of the result (clamped to the subnormal exponent) and the
+        // exponent of its least significant mantissa bit.
+        let top_bit = 63 - significand.leading_zeros() as i32 + exponent;
+        let mut target_exponent = top_bit.max(1 - self.bias());
+        let shift = target_exponent - m as i32 - exponent;
+        let mut mantissa = if shift <= 0 {
+            significand << -shift
+        } else if shift > 54 {
+            0
+        } else {
+            let truncated = significand >> shift;
+            let remainder = significand & ((1 << shift) - 1);
+            let half = 1 << (shift - 1);
+            if remainder > half || (remainder == half && truncated & 1 == 1) {
+                truncated + 1
+            } else {
+                truncated
+            }
+        };
+        // Rounding may have carried into a new bit
+        if mantissa >> (m + 1) != 0 {
+            mantissa >>= 1;
+            target_exponent += 1;
+        }
+
+        if mantissa >> m == 0 {
+            // Subnormal (or rounded to zero)
+            return sign | mantissa;
+        }
+        let biased_exponent = (target_exponent + self.bias()) as u64;
+        if biased_exponent >= max_exponent {
+            return sign | (max_exponent << m);
+        }
+        sign | (biased_exponent << m) | (mantissa & ((1 << m) - 1))
+    }
+}
+
+/// Returns 2^exp exactly, for any exponent in the range of finite `f64` powers of two.
+fn pow2(exp: i32) -> f64 {
+    debug_assert!((-1074..=1023).contains(&exp));
+    if exp >= -1022 {
+        f64::from_bits(((exp + 1023) as u64) << 52)
+    } else {
+        f64::from_bits(1 << (exp + 1074))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_f16_decode() {
+        let f16 = MinifloatFormat::F16;
+        assert_eq!(f16.decode(0x3C00), 1.0);
+        assert_eq!(f16.decode(0xC000), -2.0);
+        assert_eq!(f16.decode(0x7BFF), 65504.0);
+        // Smallest subnormal
+        assert_eq!(f16.decode(0x0001), 2f64.powi(-24));
+        assert_eq!(f16.decode(0x7C00), f64::INFINITY);
+        assert!(f16.decode(0x7E00).is_nan());
+    }
+
+    #[test]
+    fn test_f16_encode() {
+        let f16 = MinifloatFormat::F16;
+        assert_eq!(f16.encode(1.0), 0x3C00);
+        assert_eq!(f16.encode(-2.0), 0xC000);
+        assert_eq!(f16.encode(65504.0), 0x7BFF);
+        // Rounds up past the max finite value
+        assert_eq!(f16.encode(65520.0), 0x7C00);
+        assert_eq!(f16.encode(2f64.powi(-24)), 0x0001);
+        // Exactly halfway between 0 and the smallest subnormal rounds to even (zero)
+        assert_eq!(f16.encode(2f64.powi(-25)), 0x0000);
+        assert_eq!(f16.encode(-0.0), 0x8000);
+        // 1 + 2^-11 is halfway between 1.0 and the next value and rounds to even
+        assert_eq!(f16.encode(1.0 + 2f64.powi(-11)), 0x3C00);
+        assert_eq!(f16.encode(1.0 + 3.0 * 2f64.powi(-11)), 0x3C02);
+        assert_eq!(f16.encode(f64::NAN) & 0x7E00, 0x7E00);
+    }
+
+    #[test]
+    fn test_f32_matches_native() {
+        let f32_format = MinifloatFormat::F32;
+        for value in [0.1f32, -3.5, f32::MIN_POSITIVE, 1e-45, f32::MAX, 123456.79] {
+            assert_eq!(f32_format.encode(value as f64), value.to_bits() as u64);
+            assert_eq!(f32_format.decode(value.to_bits() as u64), value as f64);
+        }
+        // Rounding from f64 matches the native `as` conversion
+        for value in [0.1f64, 1.0 / 3.0, 1e-40, 3.4028235e38] {
+            assert_eq!(f32_format.encode(value), (value as f32).to_bits() as u64);
+        }
+    }
+
+    #[test]
+    fn test_custom_format() {
+        // An 8-bit E4M3-style format
+        let e4m3 = MinifloatFormat::new(4, 3);
+        assert_eq!(e4m3.bit_width(), 8);
+        assert_eq!(e4m3.decode(e4m3.encode(1.5)), 1.5);
+        assert_eq!(e4m3.decode(e4m3.encode(240.0)), 240.0);
+        assert_eq!(e4m3.decode(e4m3.encode(-0.015625)), -0.015625);
+    }
+}
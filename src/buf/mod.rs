@@ -1,14 +1,33 @@
+pub mod ans;
 pub mod bit_buf;
 pub mod bit_buf_exts;
 pub mod bit_buf_impls;
 pub mod bit_buf_mut;
 pub mod bit_buf_mut_exts;
 pub mod bit_buf_mut_impls;
+pub mod bit_codec;
 pub mod bits;
 pub mod bits_mut;
+pub mod bool_coder;
 pub mod buf_impl;
+mod bulk;
 pub mod byte_order;
+pub mod cabac;
 pub mod chain;
+pub mod emulation_prevention;
+pub mod fse;
+pub mod group_varint;
+pub mod huffman;
 pub mod limit;
+pub mod lsb_bits;
+pub mod lsb_bits_mut;
+pub mod minifloat;
+pub mod packed_array;
+pub mod parquet_rle;
+pub mod per;
+pub mod rbsp;
+pub mod reverse_bits;
 pub mod take;
+pub mod universal_codes;
 mod util;
+pub mod varint;
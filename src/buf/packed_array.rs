@@ -0,0 +1,350 @@
+use std::ops::Range;
+
+use crate::prelude::*;
+
+/// The widest value a packed array can hold.
+pub const PACKED_ARRAY_MAX_WIDTH: usize = 32;
+
+fn check_width(width: usize) -> std::io::Result<()> {
+    if width == 0 || width > PACKED_ARRAY_MAX_WIDTH {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            format!(
+                "Packed array width ({}) must be between 1 and {}",
+                width, PACKED_ARRAY_MAX_WIDTH
+            ),
+        ));
+    }
+    Ok(())
+}
+
+fn check_fits(width: usize, value: u32) -> std::io::Result<()> {
+    if width < 32 && value >> width != 0 {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            format!("Value ({}) does not fit in {} bits", value, width),
+        ));
+    }
+    Ok(())
+}
+
+/// Unpacks the first `dest.len()` values of `width` bits from `bits`.  Values are extracted from
+/// one 64-bit load at a time, rather than with a load per value.
+fn unpack(bits: &BitSlice, width: usize, dest: &mut [u32]) {
+    let per_load = 64 / width;
+    let mask = if width == 32 {
+        u32::MAX
+    } else {
+        (1 << width) - 1
+    };
+    for (group_index, group) in dest.chunks_mut(per_load).enumerate() {
+        let start = group_index * per_load * width;
+        let len = group.len() * width;
+        let word: u64 = BigEndian::load(&bits[start..start + len]);
+        for (i, value) in group.iter_mut().enumerate() {
+            *value = (word >> (len - (i + 1) * width)) as u32 & mask;
+        }
+    }
+}
+
+/// An immutable array of unsigned integers which all have the same bit width (between 1 and 32,
+/// chosen at runtime), packed MSB-first with no padding between them.
+///
+/// The storage is a [`Bits`], so clones and sub-ranges share it rather than copying.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PackedArray {
+    bits: Bits,
+    width: usize,
+}
+
+impl PackedArray {
+    /// Creates an array of `width`-bit values over `bits`.  Any bits after the last whole value
+    /// are ignored.
+    pub fn new(bits: Bits, width: usize) -> std::io::Result<Self> {
+        check_width(width)?;
+        let len = bits.len_bits() / width;
+        let bits = bits.slice_bits(0..len * width);
+        Ok(Self { bits, width })
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// The number of values in the array.
+    pub fn len(&self) -> usize {
+        self.bits.len_bits() / self.width
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bits.is_empty()
+    }
+
+    /// Returns the value at `index`, or `None` if it's out of bounds.
+    pub fn get(&self, index: usize) -> Option<u32> {
+        if index >= self.len() {
+            return None;
+        }
+        let start = index * self.width;
+        Some(BigEndian::load(&self.bits[start..start + self.width]))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = u32> + '_ {
+        (0..self.len()).map(|index| self.get(index).unwrap())
+    }
+
+    /// Returns the values in `range` as a new array which shares this one's storage.
+    pub fn slice(&self, range: Range<usize>) -> Self {
+        assert!(
+            range.start <= range.end && range.end <= self.len(),
+            "Range {:?} beyond PackedArray length {}",
+            range,
+            self.len()
+        );
+        Self {
+            bits: self
+                .bits
+                .slice_bits(range.start * self.width..range.end * self.width),
+            width: self.width,
+        }
+    }
+
+    /// Unpacks the first `dest.len()` values into `dest`.  Returns an error if the array has fewer
+    /// values than that.
+    pub fn unpack_into(&self, dest: &mut [u32]) -> std::io::Result<()> {
+        if dest.len() > self.len() {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::UnexpectedEof,
+                format!(
+                    "Array length ({}) is less than the size of the dest ({})",
+                    self.len(),
+                    dest.len()
+                ),
+            ));
+        }
+        unpack(&self.bits, self.width, dest);
+        Ok(())
+    }
+
+    /// Returns the storage, which holds exactly `len() * width()` bits.
+    pub fn into_bits(self) -> Bits {
+        self.bits
+    }
+}
+
+/// A growable, mutable array of unsigned integers which all have the same bit width, stored in a
+/// [`BitsMut`].  See [`PackedArray`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PackedArrayMut {
+    bits: BitsMut,
+    width: usize,
+}
+
+impl PackedArrayMut {
+    /// Creates an empty array of `width`-bit values.
+    pub fn new(width: usize) -> std::io::Result<Self> {
+        Self::with_capacity(width, 0)
+    }
+
+    /// Creates an empty array of `width`-bit values which can hold at least `capacity` values
+    /// without reallocating.
+    pub fn with_capacity(width: usize, capacity: usize) -> std::io::Result<Self> {
+        check_width(width)?;
+        Ok(Self {
+            bits: BitsMut::with_capacity(capacity * width),
+            width,
+        })
+    }
+
+    /// Creates an array of `len` zeros.
+    pub fn zeroed(width: usize, len: usize) -> std::io::Result<Self> {
+        check_width(width)?;
+        Ok(Self {
+            bits: BitsMut::zeroed_bits(len * width),
+            width,
+        })
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// The number of values in the array.
+    pub fn len(&self) -> usize {
+        self.bits.len_bits() / self.width
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bits.is_empty()
+    }
+
+    /// Returns the value at `index`, or `None` if it's out of bounds.
+    pub fn get(&self, index: usize) -> Option<u32> {
+        if index >= self.len() {
+            return None;
+        }
+        let start = index * self.width;
+        Some(BigEndian::load(&self.bits[start..start + self.width]))
+    }
+
+    /// Replaces the value at `index`.  Returns an error if `value` doesn't fit in the array's
+    /// width.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn set(&mut self, index: usize, value: u32) -> std::io::Result<()> {
+        assert!(
+            index < self.len(),
+            "Index {} beyond PackedArrayMut length {}",
+            index,
+            self.len()
+        );
+        check_fits(self.width, value)?;
+        let start = index * self.width;
+        BigEndian::store(&mut self.bits[start..start + self.width], value);
+        Ok(())
+    }
+
+    /// Appends `value`.  Returns an error if it doesn't fit in the array's width.
+    pub fn push(&mut self, value: u32) -> std::io::Result<()> {
+        check_fits(self.width, value)?;
+        self.bits.put_bits_from::<u32, BigEndian>(self.width, value)
+    }
+
+    /// Appends all of `values`, or none of them if any doesn't fit in the array's width.  Values
+    /// are combined into 64-bit writes rather than written one at a time.
+    pub fn pack_from(&mut self, values: &[u32]) -> std::io::Result<()> {
+        for &value in values {
+            check_fits(self.width, value)?;
+        }
+        self.bits.reserve_bits(values.len() * self.width);
+        for group in values.chunks(64 / self.width) {
+            let word = group
+                .iter()
+                .fold(0u64, |word, &value| (word << self.width) | value as u64);
+            self.bits
+                .put_bits_from::<u64, BigEndian>(group.len() * self.width, word)?;
+        }
+        Ok(())
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = u32> + '_ {
+        (0..self.len()).map(|index| self.get(index).unwrap())
+    }
+
+    /// Unpacks the first `dest.len()` values into `dest`.  Returns an error if the array has fewer
+    /// values than that.
+    pub fn unpack_into(&self, dest: &mut [u32]) -> std::io::Result<()> {
+        if dest.len() > self.len() {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::UnexpectedEof,
+                format!(
+                    "Array length ({}) is less than the size of the dest ({})",
+                    self.len(),
+                    dest.len()
+                ),
+            ));
+        }
+        unpack(&self.bits, self.width, dest);
+        Ok(())
+    }
+
+    /// Converts `self` into an immutable [`PackedArray`] without copying.
+    pub fn freeze(self) -> PackedArray {
+        PackedArray {
+            bits: self.bits.freeze(),
+            width: self.width,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_push_get_set() {
+        let mut array = PackedArrayMut::new(5).unwrap();
+        for value in [3, 31, 0, 17, 8] {
+            array.push(value).unwrap();
+        }
+        assert!(array.push(32).is_err());
+        assert_eq!(array.len(), 5);
+        assert_eq!(array.get(1), Some(31));
+        assert_eq!(array.get(5), None);
+
+        array.set(2, 21).unwrap();
+        assert!(array.set(2, 40).is_err());
+        assert_eq!(array.iter().collect::<Vec<_>>(), [3, 31, 21, 17, 8]);
+
+        let array = array.freeze();
+        // 00011 11111 10101 10001 01000
+        assert_eq!(
+            array.clone().into_bits(),
+            Bits::copy_from_bit_slice(bits![
+                0, 0, 0, 1, 1, 1, 1, 1, 1, 1, 1, 0, 1, 0, 1, 1, 0, 0, 0, 1, 0, 1, 0, 0, 0
+            ])
+        );
+        assert_eq!(array.get(4), Some(8));
+    }
+
+    #[test]
+    fn test_bulk() {
+        for width in [1, 3, 7, 13, 20, 31, 32] {
+            let mask = if width == 32 {
+                u32::MAX
+            } else {
+                (1 << width) - 1
+            };
+            let values: Vec<u32> = (0..1000u32)
+                .map(|i| i.wrapping_mul(2_654_435_761) & mask)
+                .collect();
+            let mut array = PackedArrayMut::new(width).unwrap();
+            array.pack_from(&values[..10]).unwrap();
+            for &value in &values[10..20] {
+                array.push(value).unwrap();
+            }
+            array.pack_from(&values[20..]).unwrap();
+            assert_eq!(array.len(), values.len());
+
+            let mut unpacked = vec![0; values.len()];
+            array.unpack_into(&mut unpacked).unwrap();
+            assert_eq!(unpacked, values);
+
+            let array = array.freeze();
+            let mut unpacked = vec![0; values.len() + 1];
+            assert!(array.unpack_into(&mut unpacked).is_err());
+            array.unpack_into(&mut unpacked[..999]).unwrap();
+            assert_eq!(unpacked[..999], values[..999]);
+        }
+
+        let mut array = PackedArrayMut::new(4).unwrap();
+        assert!(array.pack_from(&[1, 2, 16]).is_err());
+        assert!(array.is_empty());
+    }
+
+    #[test]
+    fn test_slice() {
+        let mut array = PackedArrayMut::zeroed(6, 10).unwrap();
+        for index in 0..10 {
+            array.set(index, index as u32 * 6).unwrap();
+        }
+        let array = array.freeze();
+        let slice = array.slice(3..8);
+        assert_eq!(slice.len(), 5);
+        assert_eq!(slice.iter().collect::<Vec<_>>(), [18, 24, 30, 36, 42]);
+        let mut unpacked = [0; 2];
+        slice.slice(2..5).unpack_into(&mut unpacked).unwrap();
+        assert_eq!(unpacked, [30, 36]);
+
+        // Any bits after the last whole value are ignored
+        let array = PackedArray::new(Bits::from_static_bytes(&[0xab, 0xcd]), 5).unwrap();
+        assert_eq!(array.len(), 3);
+        assert_eq!(
+            array.iter().collect::<Vec<_>>(),
+            [0b10101, 0b01111, 0b00110]
+        );
+        assert!(PackedArray::new(Bits::from_static_bytes(&[]), 33).is_err());
+    }
+}
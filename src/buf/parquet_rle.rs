@@ -0,0 +1,467 @@
+use bitvec::{order::Msb0, view::BitView};
+
+use crate::prelude::*;
+
+/// The widest value the RLE/bit-packing hybrid encoding can hold.
+pub const PARQUET_RLE_MAX_BIT_WIDTH: usize = 32;
+
+/// The shortest run of repeated values which [`ParquetRleEncoder`] writes as an RLE run.
+const MIN_RLE_RUN: usize = 8;
+
+/// The most values [`ParquetRleEncoder`] holds back for a bit-packed run.  63 groups keep the
+/// run's header to one byte.
+const MAX_BIT_PACKED_RUN: usize = 63 * 8;
+
+/// The most groups [`ParquetRleDecoder`] unpacks from one read.
+const GROUPS_PER_READ: usize = 32;
+
+/// Builds an array of `f::<W>` for every bit width, so each width gets its own unrolled copy.
+macro_rules! by_bit_width {
+    ($f:ident) => {
+        by_bit_width!($f; 0 1 2 3 4 5 6 7 8 9 10 11 12 13 14 15 16 17 18 19 20 21 22 23 24 25 26
+            27 28 29 30 31 32)
+    };
+    ($f:ident; $($width:literal)*) => {
+        [$($f::<$width>),*]
+    };
+}
+
+type UnpackGroup = fn(&[u8], &mut [u32]);
+type PackGroup = fn(&[u32], &mut [u8]);
+
+const UNPACK_GROUP: [UnpackGroup; PARQUET_RLE_MAX_BIT_WIDTH + 1] = by_bit_width!(unpack_group);
+const PACK_GROUP: [PackGroup; PARQUET_RLE_MAX_BIT_WIDTH + 1] = by_bit_width!(pack_group);
+
+/// Unpacks a group of 8 `W`-bit values, packed LSB-first, from the start of `bytes` into
+/// `values`.  `bytes` must extend at least 8 bytes past the group's `W` bytes.
+fn unpack_group<const W: usize>(bytes: &[u8], values: &mut [u32]) {
+    for (i, value) in values[..8].iter_mut().enumerate() {
+        let offset = i * W;
+        let word = u64::from_le_bytes(bytes[offset / 8..offset / 8 + 8].try_into().unwrap());
+        *value = ((word >> (offset % 8)) & ((1 << W) - 1)) as u32;
+    }
+}
+
+/// Packs a group of 8 `W`-bit values LSB-first into the first `W` bytes of `bytes`.
+fn pack_group<const W: usize>(values: &[u32], bytes: &mut [u8]) {
+    let mut word = 0u64;
+    let mut word_len = 0;
+    let mut byte_index = 0;
+    for &value in &values[..8] {
+        word |= (value as u64) << word_len;
+        word_len += W;
+        while word_len >= 8 {
+            bytes[byte_index] = word as u8;
+            byte_index += 1;
+            word >>= 8;
+            word_len -= 8;
+        }
+    }
+}
+
+fn check_bit_width(bit_width: usize) -> std::io::Result<()> {
+    if bit_width > PARQUET_RLE_MAX_BIT_WIDTH {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            format!(
+                "Bit width ({}) must be at most {}",
+                bit_width, PARQUET_RLE_MAX_BIT_WIDTH
+            ),
+        ));
+    }
+    Ok(())
+}
+
+fn fits(bit_width: usize, value: u32) -> bool {
+    bit_width >= 32 || value >> bit_width == 0
+}
+
+/// A streaming decoder for Parquet's RLE/bit-packing hybrid encoding, which is used for
+/// repetition and definition levels, dictionary indices and booleans.
+///
+/// The data is a sequence of runs, each starting with a ULEB128 header whose low bit gives its
+/// kind.  An RLE run is one value repeated `header >> 1` times, stored in the fewest whole bytes
+/// which fit the bit width.  A bit-packed run is `header >> 1` groups of 8 values, packed
+/// LSB-first.  The number of values isn't stored in the data (a Parquet page gives it
+/// separately), so the caller should only read that many: the last group may hold padding.
+pub struct ParquetRleDecoder<B: BitBuf> {
+    inner: B,
+    bit_width: usize,
+    rle_value: u32,
+    /// The number of values left in the current RLE run
+    rle_left: usize,
+    /// The number of groups left in the current bit-packed run
+    groups_left: usize,
+    /// A group unpacked for a read of fewer than 8 values, and how much of it has been returned
+    group: [u32; 8],
+    group_pos: usize,
+}
+
+impl<B: BitBuf> ParquetRleDecoder<B> {
+    /// Creates a decoder for `bit_width`-bit (at most 32) values.
+    pub fn new(inner: B, bit_width: usize) -> std::io::Result<Self> {
+        check_bit_width(bit_width)?;
+        Ok(Self {
+            inner,
+            bit_width,
+            rle_value: 0,
+            rle_left: 0,
+            groups_left: 0,
+            group: [0; 8],
+            group_pos: 8,
+        })
+    }
+
+    pub fn into_inner(self) -> B {
+        self.inner
+    }
+
+    pub fn get_ref(&self) -> &B {
+        &self.inner
+    }
+
+    pub fn bit_width(&self) -> usize {
+        self.bit_width
+    }
+
+    /// Reads the next value, or returns `None` if the buffer has run out.
+    pub fn get(&mut self) -> std::io::Result<Option<u32>> {
+        let mut value = [0];
+        Ok((self.get_batch(&mut value)? == 1).then_some(value[0]))
+    }
+
+    /// Reads values into `dest` until it's full or the buffer runs out, and returns the number
+    /// read.  Returns an error if a run is malformed or cut short.
+    pub fn get_batch(&mut self, dest: &mut [u32]) -> std::io::Result<usize> {
+        let mut read = 0;
+        while read < dest.len() {
+            let dest = &mut dest[read..];
+            if self.group_pos < 8 {
+                let count = dest.len().min(8 - self.group_pos);
+                dest[..count].copy_from_slice(&self.group[self.group_pos..self.group_pos + count]);
+                self.group_pos += count;
+                read += count;
+            } else if self.rle_left > 0 {
+                let count = dest.len().min(self.rle_left);
+                dest[..count].fill(self.rle_value);
+                self.rle_left -= count;
+                read += count;
+            } else if self.groups_left > 0 {
+                if dest.len() < 8 {
+                    let mut group = [0; 8];
+                    self.read_groups(&mut group)?;
+                    self.group = group;
+                    self.group_pos = 0;
+                } else {
+                    let groups = self.groups_left.min(dest.len() / 8).min(GROUPS_PER_READ);
+                    self.read_groups(&mut dest[..groups * 8])?;
+                    read += groups * 8;
+                }
+            } else if self.inner.has_remaining_bits() {
+                self.read_header()?;
+            } else {
+                break;
+            }
+        }
+        Ok(read)
+    }
+
+    /// Like [`ParquetRleDecoder::get_batch`], but widens the values to `u64`.
+    pub fn get_batch_u64(&mut self, dest: &mut [u64]) -> std::io::Result<usize> {
+        let mut values = [0; GROUPS_PER_READ * 8];
+        let mut read = 0;
+        while read < dest.len() {
+            let count = values.len().min(dest.len() - read);
+            let values_read = self.get_batch(&mut values[..count])?;
+            for (value, &narrow) in dest[read..].iter_mut().zip(&values[..values_read]) {
+                *value = narrow as u64;
+            }
+            read += values_read;
+            if values_read < count {
+                break;
+            }
+        }
+        Ok(read)
+    }
+
+    fn read_header(&mut self) -> std::io::Result<()> {
+        let header = self.inner.get_uleb128()?;
+        let count = (header >> 1) as usize;
+        if header & 1 == 1 {
+            self.groups_left = count;
+            return Ok(());
+        }
+        let mut value = 0u32;
+        for i in 0..self.bit_width.div_ceil(8) {
+            value |= (self.inner.get_u8()? as u32) << (8 * i);
+        }
+        if !fits(self.bit_width, value) {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                format!(
+                    "RLE run value ({}) does not fit in {} bits",
+                    value, self.bit_width
+                ),
+            ));
+        }
+        self.rle_value = value;
+        self.rle_left = count;
+        Ok(())
+    }
+
+    /// Reads and unpacks `values.len() / 8` groups of the current bit-packed run.
+    fn read_groups(&mut self, values: &mut [u32]) -> std::io::Result<()> {
+        let groups = values.len() / 8;
+        let mut bytes = [0u8; GROUPS_PER_READ * PARQUET_RLE_MAX_BIT_WIDTH + 8];
+        self.inner.try_copy_to_bit_slice(
+            &mut bytes.view_bits_mut::<Msb0>()[..groups * self.bit_width * 8],
+        )?;
+        let unpack = UNPACK_GROUP[self.bit_width];
+        for (i, group) in values.chunks_exact_mut(8).enumerate() {
+            unpack(&bytes[i * self.bit_width..], group);
+        }
+        self.groups_left -= groups;
+        Ok(())
+    }
+}
+
+/// An encoder for Parquet's RLE/bit-packing hybrid encoding (see [`ParquetRleDecoder`]).
+///
+/// Runs of at least 8 repeated values are written as RLE runs, and everything else is
+/// bit-packed.  Only the last bit-packed run may be padded, so when a repeated run follows a
+/// bit-packed one, its first few values fill out the bit-packed run's last group.
+pub struct ParquetRleEncoder<B: BitBufMut> {
+    inner: B,
+    bit_width: usize,
+    /// Values waiting to be written as a bit-packed run
+    literals: Vec<u32>,
+    /// The latest run of repeated values, which hasn't been assigned to a kind of run yet
+    run_value: u32,
+    run_len: usize,
+}
+
+impl<B: BitBufMut> ParquetRleEncoder<B> {
+    /// Creates an encoder for `bit_width`-bit (at most 32) values.
+    pub fn new(inner: B, bit_width: usize) -> std::io::Result<Self> {
+        check_bit_width(bit_width)?;
+        Ok(Self {
+            inner,
+            bit_width,
+            literals: Vec::with_capacity(MAX_BIT_PACKED_RUN),
+            run_value: 0,
+            run_len: 0,
+        })
+    }
+
+    pub fn get_ref(&self) -> &B {
+        &self.inner
+    }
+
+    pub fn bit_width(&self) -> usize {
+        self.bit_width
+    }
+
+    /// Writes `value`.  Returns an error if it doesn't fit in the bit width.
+    pub fn put(&mut self, value: u32) -> std::io::Result<()> {
+        if !fits(self.bit_width, value) {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!("Value ({}) does not fit in {} bits", value, self.bit_width),
+            ));
+        }
+        if self.run_len > 0 && value == self.run_value {
+            self.run_len += 1;
+            return Ok(());
+        }
+        self.end_run()?;
+        self.run_value = value;
+        self.run_len = 1;
+        Ok(())
+    }
+
+    /// Writes all of `values`, or none of them if any doesn't fit in the bit width.
+    pub fn put_batch(&mut self, values: &[u32]) -> std::io::Result<()> {
+        if let Some(&value) = values.iter().find(|&&value| !fits(self.bit_width, value)) {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!("Value ({}) does not fit in {} bits", value, self.bit_width),
+            ));
+        }
+        for &value in values {
+            self.put(value)?;
+        }
+        Ok(())
+    }
+
+    /// Writes any held-back values, padding the last bit-packed group with zeros, and returns the
+    /// inner buffer.
+    pub fn finish(mut self) -> std::io::Result<B> {
+        self.end_run()?;
+        self.write_literals()?;
+        Ok(self.inner)
+    }
+
+    /// Writes the current run of repeated values as an RLE run if it's long enough, or adds it to
+    /// the bit-packed run otherwise.
+    fn end_run(&mut self) -> std::io::Result<()> {
+        let padding = (8 - self.literals.len() % 8) % 8;
+        if self.run_len >= padding + MIN_RLE_RUN {
+            self.literals
+                .resize(self.literals.len() + padding, self.run_value);
+            self.write_literals()?;
+            self.inner
+                .put_uleb128(((self.run_len - padding) as u64) << 1)?;
+            for i in 0..self.bit_width.div_ceil(8) {
+                self.inner.put_u8((self.run_value >> (8 * i)) as u8)?;
+            }
+        } else {
+            for _ in 0..self.run_len {
+                self.literals.push(self.run_value);
+                if self.literals.len() == MAX_BIT_PACKED_RUN {
+                    self.write_literals()?;
+                }
+            }
+        }
+        self.run_len = 0;
+        Ok(())
+    }
+
+    fn write_literals(&mut self) -> std::io::Result<()> {
+        if self.literals.is_empty() {
+            return Ok(());
+        }
+        let groups = self.literals.len().div_ceil(8);
+        self.literals.resize(groups * 8, 0);
+        self.inner.put_uleb128(((groups as u64) << 1) | 1)?;
+        let pack = PACK_GROUP[self.bit_width];
+        let mut bytes = [0u8; PARQUET_RLE_MAX_BIT_WIDTH];
+        for group in self.literals.chunks_exact(8) {
+            pack(group, &mut bytes);
+            self.inner
+                .try_put_bit_slice(&bytes.view_bits::<Msb0>()[..self.bit_width * 8])?;
+        }
+        self.literals.clear();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_spec_example() {
+        // The bit-packed example from the Parquet encodings doc (0 to 7 with a bit width of 3),
+        // followed by an RLE run of ten 4s
+        const DATA: [u8; 6] = [0x03, 0x88, 0xc6, 0xfa, 0x14, 0x04];
+        let mut decoder = ParquetRleDecoder::new(Bits::from_static_bytes(&DATA), 3).unwrap();
+        let mut values = [0; 20];
+        assert_eq!(decoder.get_batch(&mut values).unwrap(), 18);
+        assert_eq!(values[..8], [0, 1, 2, 3, 4, 5, 6, 7]);
+        assert_eq!(values[8..18], [4; 10]);
+        assert_eq!(decoder.get().unwrap(), None);
+
+        let mut encoder = ParquetRleEncoder::new(BitsMut::new(), 3).unwrap();
+        encoder.put_batch(&values[..18]).unwrap();
+        assert_eq!(
+            encoder.finish().unwrap().freeze(),
+            Bits::from_static_bytes(&DATA)
+        );
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let mut seed = 0x2545_f491u32;
+        let mut next = move || {
+            seed ^= seed << 13;
+            seed ^= seed >> 17;
+            seed ^= seed << 5;
+            seed
+        };
+        for bit_width in 0..=PARQUET_RLE_MAX_BIT_WIDTH {
+            let mask = if bit_width == 32 {
+                u32::MAX
+            } else {
+                (1 << bit_width) - 1
+            };
+            // Alternate stretches of distinct values with repeated runs of various lengths
+            let mut values = Vec::new();
+            while values.len() < 3000 {
+                let stretch = next() as usize % 40;
+                if next() % 2 == 0 {
+                    values.extend((0..stretch).map(|_| next() & mask));
+                } else {
+                    values.extend(std::iter::repeat_n(next() & mask, stretch));
+                }
+            }
+            let mut encoder = ParquetRleEncoder::new(BitsMut::new(), bit_width).unwrap();
+            encoder.put_batch(&values).unwrap();
+            let bits = encoder.finish().unwrap().freeze();
+
+            let mut decoder = ParquetRleDecoder::new(bits.clone(), bit_width).unwrap();
+            let mut decoded = vec![0; values.len()];
+            let mut read = 0;
+            for batch in [1, 3, 8, 17, 300].iter().cycle() {
+                let end = (read + batch).min(values.len());
+                assert_eq!(
+                    decoder.get_batch(&mut decoded[read..end]).unwrap(),
+                    end - read
+                );
+                read = end;
+                if read == values.len() {
+                    break;
+                }
+            }
+            assert_eq!(decoded, values, "bit width {}", bit_width);
+
+            let mut decoder = ParquetRleDecoder::new(bits, bit_width).unwrap();
+            let mut decoded = vec![0; values.len()];
+            assert_eq!(decoder.get_batch_u64(&mut decoded).unwrap(), values.len());
+            assert!(decoded
+                .iter()
+                .zip(&values)
+                .all(|(&wide, &value)| wide == value as u64));
+        }
+    }
+
+    #[test]
+    fn test_unaligned() {
+        let mut bits = BitsMut::new();
+        bits.put_u3(u3::new(0b101)).unwrap();
+        let mut encoder = ParquetRleEncoder::new(bits, 5).unwrap();
+        let values: Vec<u32> = (0..100).map(|i| if i < 50 { i % 32 } else { 9 }).collect();
+        encoder.put_batch(&values).unwrap();
+        let mut bits = encoder.finish().unwrap().freeze();
+        bits.advance_bits(3);
+
+        let mut decoder = ParquetRleDecoder::new(bits, 5).unwrap();
+        let mut decoded = vec![0; values.len()];
+        assert_eq!(decoder.get_batch(&mut decoded).unwrap(), values.len());
+        assert_eq!(decoded, values);
+    }
+
+    #[test]
+    fn test_errors() {
+        assert!(ParquetRleDecoder::new(Bits::from_static_bytes(&[]), 33).is_err());
+        assert!(ParquetRleEncoder::new(BitsMut::new(), 33).is_err());
+
+        let mut encoder = ParquetRleEncoder::new(BitsMut::new(), 4).unwrap();
+        assert!(encoder.put(16).is_err());
+        assert!(encoder.put_batch(&[1, 2, 16]).is_err());
+        assert!(encoder.finish().unwrap().is_empty());
+
+        let mut values = [0; 8];
+        // A bit-packed run which is cut short
+        let mut decoder =
+            ParquetRleDecoder::new(Bits::from_static_bytes(&[0x03, 0x88, 0xc6]), 3).unwrap();
+        assert!(decoder.get_batch(&mut values).is_err());
+        // An RLE run whose value is too wide
+        let mut decoder =
+            ParquetRleDecoder::new(Bits::from_static_bytes(&[0x02, 0x08]), 3).unwrap();
+        assert!(decoder.get_batch(&mut values).is_err());
+        // An RLE run which is missing its value
+        let mut decoder =
+            ParquetRleDecoder::new(Bits::from_static_bytes(&[0x02, 0x08]), 9).unwrap();
+        assert!(decoder.get_batch(&mut values).is_err());
+    }
+}
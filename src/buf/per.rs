@@ -0,0 +1,930 @@
+use super::util::bits_to_byte_boundary;
+use crate::prelude::*;
+
+/// Selects between the aligned and unaligned variants of PER, in the same way as [`ByteOrder`]
+/// selects a byte order: `buf.get_per_constrained_int::<Aligned>(0, 999)`.
+pub trait PerVariant {
+    /// Whether fields are padded to octet boundaries where X.691 calls for it.  Boundaries are
+    /// counted from the start of the buffer's underlying storage.
+    const ALIGNED: bool;
+}
+
+/// The ALIGNED variant of PER (APER), as used by NGAP and S1AP
+pub struct Aligned {}
+
+/// The UNALIGNED variant of PER (UPER), as used by RRC
+pub struct Unaligned {}
+
+impl PerVariant for Aligned {
+    const ALIGNED: bool = true;
+}
+
+impl PerVariant for Unaligned {
+    const ALIGNED: bool = false;
+}
+
+/// The number of items in a single fragment unit (16K).  A fragment holds 1 to 4 of these.
+const FRAGMENT_UNIT: usize = 16384;
+
+/// Lengths at or above this (64K) can't be encoded as a constrained whole number.
+const MAX_CONSTRAINED_LENGTH: usize = 65536;
+
+/// A single general length determinant.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PerLength {
+    /// The final (or only) part of the value has this many items
+    Complete(usize),
+    /// This many items (a multiple of 16K, up to 64K) follow, and then another length determinant
+    Fragment(usize),
+}
+
+/// The size constraint on a BIT STRING or OCTET STRING (or any other list of items preceded by a
+/// length).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SizeConstraint {
+    lb: usize,
+    ub: Option<usize>,
+}
+
+impl SizeConstraint {
+    /// No constraint on the size
+    pub const UNCONSTRAINED: SizeConstraint = SizeConstraint { lb: 0, ub: None };
+
+    /// `SIZE (size)`
+    pub fn fixed(size: usize) -> Self {
+        Self {
+            lb: size,
+            ub: Some(size),
+        }
+    }
+
+    /// `SIZE (lb..ub)`
+    pub fn range(lb: usize, ub: usize) -> Self {
+        assert!(
+            lb <= ub,
+            "Lower bound ({}) is larger than upper bound ({})",
+            lb,
+            ub
+        );
+        Self { lb, ub: Some(ub) }
+    }
+
+    /// `SIZE (lb..MAX)`
+    pub fn at_least(lb: usize) -> Self {
+        Self { lb, ub: None }
+    }
+
+    fn contains(&self, size: usize) -> bool {
+        size >= self.lb && self.ub.is_none_or(|ub| size <= ub)
+    }
+}
+
+/// The optional-field bitmap (and extension bit) at the start of a SEQUENCE or SET.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SequencePreamble {
+    /// Whether extension additions are present.  Always false for types without an extension
+    /// marker.
+    pub extended: bool,
+    /// One bit for each OPTIONAL or DEFAULT root component, in order, set if it's present
+    pub optional_present: BitVec,
+}
+
+fn invalid_data(message: String) -> std::io::Error {
+    std::io::Error::new(std::io::ErrorKind::InvalidData, message)
+}
+
+fn invalid_input(message: String) -> std::io::Error {
+    std::io::Error::new(std::io::ErrorKind::InvalidInput, message)
+}
+
+/// Returns the number of values in `lb..=ub`.
+fn constraint_range(lb: i64, ub: i64) -> std::io::Result<u128> {
+    if lb > ub {
+        return Err(invalid_input(format!(
+            "Lower bound ({}) is larger than upper bound ({})",
+            lb, ub
+        )));
+    }
+    Ok((ub as i128 - lb as i128 + 1) as u128)
+}
+
+/// The number of bits in a bit-field which can hold every offset in a range of `range` values.
+fn bits_for_range(range: u128) -> usize {
+    (128 - (range - 1).leading_zeros()) as usize
+}
+
+/// The minimum number of octets (at least one) needed for `value` as a non-negative binary
+/// integer.
+fn octets_for(value: u64) -> usize {
+    (64 - value.leading_zeros() as usize).div_ceil(8).max(1)
+}
+
+/// The minimum number of octets needed for `value` as a 2's-complement binary integer.
+fn signed_octets_for(value: i64) -> usize {
+    let significant_bits = if value < 0 {
+        64 - value.leading_ones()
+    } else {
+        64 - value.leading_zeros()
+    };
+    (significant_bits as usize + 1).div_ceil(8)
+}
+
+/// Skips the padding bits up to the next octet boundary in the aligned variant.
+fn skip_padding<V: PerVariant, B: BitBuf + ?Sized>(buf: &mut B) -> std::io::Result<()> {
+    if !V::ALIGNED {
+        return Ok(());
+    }
+    // byte_aligned() also depends on how many bits remain, so go by the cursor's position within
+    // its byte instead.  Octet boundaries are counted from the start of the underlying storage,
+    // so an encoding must start on one.
+    for _ in 0..bits_to_byte_boundary(buf.chunk_bits()) {
+        buf.get_bool()?;
+    }
+    Ok(())
+}
+
+/// Writes zero bits up to the next octet boundary in the aligned variant.
+fn put_padding<V: PerVariant, B: BitBufMut + ?Sized>(buf: &mut B) -> std::io::Result<()> {
+    if !V::ALIGNED {
+        return Ok(());
+    }
+    for _ in 0..bits_to_byte_boundary(buf.chunk_mut_bits()) {
+        buf.put_bool(false)?;
+    }
+    Ok(())
+}
+
+/// How the number of items in a list with a given size constraint is encoded.
+enum LengthEncoding {
+    /// No length: the list always has this many items
+    Fixed(usize),
+    /// A constrained whole number in `lb..=ub`
+    Constrained(usize, usize),
+    /// General length determinants, possibly fragmented
+    General,
+}
+
+impl From<SizeConstraint> for LengthEncoding {
+    fn from(size: SizeConstraint) -> Self {
+        match size.ub {
+            Some(ub) if ub == size.lb && ub < MAX_CONSTRAINED_LENGTH => LengthEncoding::Fixed(ub),
+            Some(ub) if ub < MAX_CONSTRAINED_LENGTH => LengthEncoding::Constrained(size.lb, ub),
+            _ => LengthEncoding::General,
+        }
+    }
+}
+
+/// Reads the length determinant (in octets) of a semi-constrained or unconstrained integer.
+fn get_integer_length<V: PerVariant, B: BitBuf + ?Sized>(buf: &mut B) -> std::io::Result<usize> {
+    match buf.get_per_length::<V>()? {
+        PerLength::Complete(num_octets @ 1..=8) => Ok(num_octets),
+        length => Err(invalid_data(format!(
+            "Integer length ({:?}) doesn't fit in 64 bits",
+            length
+        ))),
+    }
+}
+
+/// Reads `count` items of `item_bits` bits each.
+fn get_items<B: BitBuf + ?Sized>(
+    buf: &mut B,
+    count: usize,
+    item_bits: usize,
+    items: &mut BitVec,
+) -> std::io::Result<()> {
+    let num_bits = count * item_bits;
+    if buf.remaining_bits() < num_bits {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::UnexpectedEof,
+            format!(
+                "Length of {} items is larger than the remaining {} bits",
+                count,
+                buf.remaining_bits()
+            ),
+        ));
+    }
+    let start = items.len();
+    items.resize(start + num_bits, false);
+    buf.try_copy_to_bit_slice(&mut items[start..])
+}
+
+/// Reads a list of items of `item_bits` bits each (e.g. the bits of a BIT STRING or the octets of
+/// an OCTET STRING) with its length.
+fn get_list<V: PerVariant, B: BitBuf + ?Sized>(
+    buf: &mut B,
+    size: SizeConstraint,
+    item_bits: usize,
+) -> std::io::Result<BitVec> {
+    let mut items = BitVec::new();
+    match LengthEncoding::from(size) {
+        LengthEncoding::Fixed(count) => {
+            // Lists of up to two octets are never aligned
+            if count * item_bits > 16 {
+                skip_padding::<V, B>(buf)?;
+            }
+            get_items(buf, count, item_bits, &mut items)?;
+        }
+        LengthEncoding::Constrained(lb, ub) => {
+            let count =
+                lb + buf.get_per_constrained_whole_number::<V>((ub - lb + 1) as u128)? as usize;
+            if ub * item_bits > 16 {
+                skip_padding::<V, B>(buf)?;
+            }
+            get_items(buf, count, item_bits, &mut items)?;
+        }
+        LengthEncoding::General => {
+            let mut count = 0;
+            loop {
+                let length = buf.get_per_length::<V>()?;
+                let (PerLength::Complete(n) | PerLength::Fragment(n)) = length;
+                get_items(buf, n, item_bits, &mut items)?;
+                count += n;
+                if let PerLength::Complete(_) = length {
+                    break;
+                }
+            }
+            if !size.contains(count) {
+                return Err(invalid_data(format!(
+                    "Length ({}) is outside of the size constraint",
+                    count
+                )));
+            }
+        }
+    }
+    Ok(items)
+}
+
+/// Writes a list of items of `item_bits` bits each (the counterpart of [`get_list`]).
+fn put_list<V: PerVariant, B: BitBufMut + ?Sized>(
+    buf: &mut B,
+    size: SizeConstraint,
+    item_bits: usize,
+    items: &BitSlice,
+) -> std::io::Result<()> {
+    let count = items.len() / item_bits;
+    if !size.contains(count) {
+        return Err(invalid_input(format!(
+            "Length ({}) is outside of the size constraint",
+            count
+        )));
+    }
+    match LengthEncoding::from(size) {
+        LengthEncoding::Fixed(count) => {
+            if count * item_bits > 16 {
+                put_padding::<V, B>(buf)?;
+            }
+            buf.try_put_bit_slice(items)
+        }
+        LengthEncoding::Constrained(lb, ub) => {
+            buf.put_per_constrained_whole_number::<V>((ub - lb + 1) as u128, (count - lb) as u64)?;
+            if ub * item_bits > 16 {
+                put_padding::<V, B>(buf)?;
+            }
+            buf.try_put_bit_slice(items)
+        }
+        LengthEncoding::General => {
+            let mut remaining = items;
+            while remaining.len() / item_bits >= FRAGMENT_UNIT {
+                let fragment_items =
+                    FRAGMENT_UNIT * (remaining.len() / item_bits / FRAGMENT_UNIT).min(4);
+                let (fragment, rest) = remaining.split_at(fragment_items * item_bits);
+                buf.put_per_length::<V>(PerLength::Fragment(fragment_items))?;
+                buf.try_put_bit_slice(fragment)?;
+                remaining = rest;
+            }
+            // A list which ends on a fragment boundary still needs a final (empty) length
+            buf.put_per_length::<V>(PerLength::Complete(remaining.len() / item_bits))?;
+            buf.try_put_bit_slice(remaining)
+        }
+    }
+}
+
+/// Reads of the building blocks of ASN.1 Packed Encoding Rules (ITU-T X.691).  Integer values are
+/// returned as `i64`, so ranges wider than that aren't supported.
+///
+/// Extension markers on constraints aren't handled here: read the extension bit with `get_bool`
+/// and then use the unconstrained form if it's set.
+pub trait BitBufPerExts: BitBuf {
+    /// Reads the offset (from the lower bound) of a constrained whole number in a range of `range`
+    /// values.  In the aligned variant, ranges of more than 255 values are octet-aligned.
+    fn get_per_constrained_whole_number<V: PerVariant>(
+        &mut self,
+        range: u128,
+    ) -> std::io::Result<u64> {
+        let offset = if range <= 1 {
+            0
+        } else if !V::ALIGNED || range <= 255 {
+            self.get_bits_as::<u64, BigEndian>(bits_for_range(range))?
+        } else if range <= 65536 {
+            skip_padding::<V, Self>(self)?;
+            let num_octets = if range == 256 { 1 } else { 2 };
+            self.get_bits_as::<u64, BigEndian>(num_octets * 8)?
+        } else {
+            // The number of octets is itself a constrained whole number, followed by the octets
+            let max_octets = octets_for((range - 1) as u64);
+            let num_octets = 1 + self
+                .get_bits_as::<u64, BigEndian>(bits_for_range(max_octets as u128))?
+                as usize;
+            if num_octets > max_octets {
+                return Err(invalid_data(format!(
+                    "Constrained whole number has {} octets, more than the maximum of {}",
+                    num_octets, max_octets
+                )));
+            }
+            skip_padding::<V, Self>(self)?;
+            self.get_bits_as::<u64, BigEndian>(num_octets * 8)?
+        };
+        if offset as u128 >= range {
+            return Err(invalid_data(format!(
+                "Constrained whole number offset ({}) is outside of the range of {} values",
+                offset, range
+            )));
+        }
+        Ok(offset)
+    }
+
+    /// Reads an integer constrained to `lb..=ub`.
+    fn get_per_constrained_int<V: PerVariant>(&mut self, lb: i64, ub: i64) -> std::io::Result<i64> {
+        let offset = self.get_per_constrained_whole_number::<V>(constraint_range(lb, ub)?)?;
+        Ok((lb as i128 + offset as i128) as i64)
+    }
+
+    /// Reads an integer with only a lower bound: a length determinant followed by the offset from
+    /// `lb` in the minimum number of octets.
+    fn get_per_semi_constrained_int<V: PerVariant>(&mut self, lb: i64) -> std::io::Result<i64> {
+        let num_octets = get_integer_length::<V, Self>(self)?;
+        let offset = self.get_bits_as::<u64, BigEndian>(num_octets * 8)?;
+        i64::try_from(lb as i128 + offset as i128).map_err(|_| {
+            invalid_data(format!(
+                "Semi-constrained integer ({} + {}) overflows an i64",
+                lb, offset
+            ))
+        })
+    }
+
+    /// Reads an integer with no bounds: a length determinant followed by the value in the minimum
+    /// number of octets of 2's-complement.
+    fn get_per_unconstrained_int<V: PerVariant>(&mut self) -> std::io::Result<i64> {
+        let num_octets = get_integer_length::<V, Self>(self)?;
+        let raw = self.get_bits_as::<u64, BigEndian>(num_octets * 8)?;
+        let shift = 64 - num_octets * 8;
+        Ok(((raw << shift) as i64) >> shift)
+    }
+
+    /// Reads a normally small non-negative whole number: a zero bit followed by a 6-bit value, or
+    /// a one bit followed by a semi-constrained value.
+    fn get_per_normally_small<V: PerVariant>(&mut self) -> std::io::Result<u64> {
+        if !self.get_bool()? {
+            return self.get_bits_as::<u64, BigEndian>(6);
+        }
+        Ok(self.get_per_semi_constrained_int::<V>(0)? as u64)
+    }
+
+    /// Reads a general (unconstrained) length determinant.  In the aligned variant it's
+    /// octet-aligned.
+    fn get_per_length<V: PerVariant>(&mut self) -> std::io::Result<PerLength> {
+        skip_padding::<V, Self>(self)?;
+        let first = self.get_u8()?;
+        if first & 0x80 == 0 {
+            return Ok(PerLength::Complete(first as usize));
+        }
+        if first & 0x40 == 0 {
+            let second = self.get_u8()?;
+            return Ok(PerLength::Complete(
+                ((first & 0x3F) as usize) << 8 | second as usize,
+            ));
+        }
+        match first & 0x3F {
+            multiplier @ 1..=4 => Ok(PerLength::Fragment(multiplier as usize * FRAGMENT_UNIT)),
+            _ => Err(invalid_data(format!(
+                "Invalid length determinant octet: {:#04x}",
+                first
+            ))),
+        }
+    }
+
+    /// Reads the index of an ENUMERATED value with `root_count` values in its root.  For
+    /// extensible types, extension additions are returned as `root_count + n`.
+    fn get_per_enumerated<V: PerVariant>(
+        &mut self,
+        root_count: usize,
+        extensible: bool,
+    ) -> std::io::Result<usize> {
+        if extensible && self.get_bool()? {
+            return Ok(root_count + self.get_per_normally_small::<V>()? as usize);
+        }
+        Ok(self.get_per_constrained_whole_number::<V>(root_count as u128)? as usize)
+    }
+
+    /// Reads a BIT STRING with the given size constraint.
+    fn get_per_bit_string<V: PerVariant>(
+        &mut self,
+        size: SizeConstraint,
+    ) -> std::io::Result<BitVec> {
+        get_list::<V, Self>(self, size, 1)
+    }
+
+    /// Reads an OCTET STRING with the given size constraint.
+    fn get_per_octet_string<V: PerVariant>(
+        &mut self,
+        size: SizeConstraint,
+    ) -> std::io::Result<Vec<u8>> {
+        Ok(get_list::<V, Self>(self, size, 8)?.into_vec())
+    }
+
+    /// Reads the preamble of a SEQUENCE or SET: the extension bit (if `extensible`) followed by a
+    /// presence bit for each of `num_optional` OPTIONAL or DEFAULT components.
+    fn get_per_sequence_preamble(
+        &mut self,
+        extensible: bool,
+        num_optional: usize,
+    ) -> std::io::Result<SequencePreamble> {
+        let extended = extensible && self.get_bool()?;
+        let mut optional_present = BitVec::new();
+        get_items(self, num_optional, 1, &mut optional_present)?;
+        Ok(SequencePreamble {
+            extended,
+            optional_present,
+        })
+    }
+}
+
+impl<T: BitBuf + ?Sized> BitBufPerExts for T {}
+
+/// Writes of the building blocks of ASN.1 Packed Encoding Rules.
+pub trait BitBufMutPerExts: BitBufMut {
+    /// Writes `offset` as a constrained whole number in a range of `range` values.
+    fn put_per_constrained_whole_number<V: PerVariant>(
+        &mut self,
+        range: u128,
+        offset: u64,
+    ) -> std::io::Result<()> {
+        if offset as u128 >= range {
+            return Err(invalid_input(format!(
+                "Offset ({}) is outside of the range of {} values",
+                offset, range
+            )));
+        }
+        if range <= 1 {
+            Ok(())
+        } else if !V::ALIGNED || range <= 255 {
+            self.put_bits_from::<u64, BigEndian>(bits_for_range(range), offset)
+        } else if range <= 65536 {
+            put_padding::<V, Self>(self)?;
+            let num_octets = if range == 256 { 1 } else { 2 };
+            self.put_bits_from::<u64, BigEndian>(num_octets * 8, offset)
+        } else {
+            let max_octets = octets_for((range - 1) as u64);
+            let num_octets = octets_for(offset);
+            self.put_bits_from::<u64, BigEndian>(
+                bits_for_range(max_octets as u128),
+                num_octets as u64 - 1,
+            )?;
+            put_padding::<V, Self>(self)?;
+            self.put_bits_from::<u64, BigEndian>(num_octets * 8, offset)
+        }
+    }
+
+    /// Writes `value` as an integer constrained to `lb..=ub`.
+    fn put_per_constrained_int<V: PerVariant>(
+        &mut self,
+        lb: i64,
+        ub: i64,
+        value: i64,
+    ) -> std::io::Result<()> {
+        let range = constraint_range(lb, ub)?;
+        if value < lb || value > ub {
+            return Err(invalid_input(format!(
+                "Value ({}) is outside of the range {}..={}",
+                value, lb, ub
+            )));
+        }
+        self.put_per_constrained_whole_number::<V>(range, (value as i128 - lb as i128) as u64)
+    }
+
+    /// Writes `value` as an integer with a lower bound of `lb`.
+    fn put_per_semi_constrained_int<V: PerVariant>(
+        &mut self,
+        lb: i64,
+        value: i64,
+    ) -> std::io::Result<()> {
+        if value < lb {
+            return Err(invalid_input(format!(
+                "Value ({}) is less than the lower bound ({})",
+                value, lb
+            )));
+        }
+        let offset = (value as i128 - lb as i128) as u64;
+        let num_octets = octets_for(offset);
+        self.put_per_length::<V>(PerLength::Complete(num_octets))?;
+        self.put_bits_from::<u64, BigEndian>(num_octets * 8, offset)
+    }
+
+    /// Writes `value` as an integer with no bounds.
+    fn put_per_unconstrained_int<V: PerVariant>(&mut self, value: i64) -> std::io::Result<()> {
+        let num_octets = signed_octets_for(value);
+        self.put_per_length::<V>(PerLength::Complete(num_octets))?;
+        let raw = value as u64 & (u64::MAX >> (64 - num_octets * 8));
+        self.put_bits_from::<u64, BigEndian>(num_octets * 8, raw)
+    }
+
+    /// Writes `value` as a normally small non-negative whole number.
+    fn put_per_normally_small<V: PerVariant>(&mut self, value: u64) -> std::io::Result<()> {
+        if value < 64 {
+            self.put_bool(false)?;
+            return self.put_bits_from::<u64, BigEndian>(6, value);
+        }
+        let value = i64::try_from(value)
+            .map_err(|_| invalid_input(format!("Value ({}) is too large to encode", value)))?;
+        self.put_bool(true)?;
+        self.put_per_semi_constrained_int::<V>(0, value)
+    }
+
+    /// Writes a general length determinant.  Lengths of 16K or more must be split into
+    /// [`PerLength::Fragment`]s of 16K, 32K, 48K or 64K items.
+    fn put_per_length<V: PerVariant>(&mut self, length: PerLength) -> std::io::Result<()> {
+        put_padding::<V, Self>(self)?;
+        match length {
+            PerLength::Complete(n) if n < 128 => self.put_u8(n as u8),
+            PerLength::Complete(n) if n < FRAGMENT_UNIT => {
+                self.put_u8(0x80 | (n >> 8) as u8)?;
+                self.put_u8(n as u8)
+            }
+            PerLength::Fragment(n)
+                if n % FRAGMENT_UNIT == 0 && (1..=4).contains(&(n / FRAGMENT_UNIT)) =>
+            {
+                self.put_u8(0xC0 | (n / FRAGMENT_UNIT) as u8)
+            }
+            length => Err(invalid_input(format!(
+                "Invalid length determinant: {:?}",
+                length
+            ))),
+        }
+    }
+
+    /// Writes the index of an ENUMERATED value with `root_count` values in its root.  For
+    /// extensible types, indices of `root_count` or more are written as extension additions.
+    fn put_per_enumerated<V: PerVariant>(
+        &mut self,
+        root_count: usize,
+        extensible: bool,
+        index: usize,
+    ) -> std::io::Result<()> {
+        if extensible {
+            self.put_bool(index >= root_count)?;
+            if index >= root_count {
+                return self.put_per_normally_small::<V>((index - root_count) as u64);
+            }
+        }
+        self.put_per_constrained_whole_number::<V>(root_count as u128, index as u64)
+    }
+
+    /// Writes a BIT STRING with the given size constraint.
+    fn put_per_bit_string<V: PerVariant>(
+        &mut self,
+        size: SizeConstraint,
+        value: &BitSlice,
+    ) -> std::io::Result<()> {
+        put_list::<V, Self>(self, size, 1, value)
+    }
+
+    /// Writes an OCTET STRING with the given size constraint.
+    fn put_per_octet_string<V: PerVariant>(
+        &mut self,
+        size: SizeConstraint,
+        value: &[u8],
+    ) -> std::io::Result<()> {
+        put_list::<V, Self>(self, size, 8, BitSlice::from_slice(value))
+    }
+
+    /// Writes the preamble of a SEQUENCE or SET.  If `extensible` is false, `preamble.extended`
+    /// must be false.
+    fn put_per_sequence_preamble(
+        &mut self,
+        extensible: bool,
+        preamble: &SequencePreamble,
+    ) -> std::io::Result<()> {
+        if extensible {
+            self.put_bool(preamble.extended)?;
+        } else if preamble.extended {
+            return Err(invalid_input(
+                "Sequence without an extension marker can't have extensions".to_string(),
+            ));
+        }
+        self.try_put_bit_slice(&preamble.optional_present)
+    }
+}
+
+impl<T: BitBufMut + ?Sized> BitBufMutPerExts for T {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn encode<F: FnOnce(&mut BitsMut) -> std::io::Result<()>>(f: F) -> BitsMut {
+        let mut bits_mut = BitsMut::new();
+        f(&mut bits_mut).unwrap();
+        bits_mut
+    }
+
+    #[test]
+    fn test_constrained_int_unaligned() {
+        let bits_mut = encode(|b| b.put_per_constrained_int::<Unaligned>(0, 7, 5));
+        assert_eq!(&bits_mut[..], bits![1, 0, 1]);
+
+        let bits_mut = encode(|b| b.put_per_constrained_int::<Unaligned>(-500, 499, -495));
+        assert_eq!(&bits_mut[..], bits![0, 0, 0, 0, 0, 0, 0, 1, 0, 1]);
+        let mut bits = bits_mut.freeze();
+        assert_eq!(
+            bits.get_per_constrained_int::<Unaligned>(-500, 499)
+                .unwrap(),
+            -495
+        );
+
+        // A single value takes no bits
+        let bits_mut = encode(|b| b.put_per_constrained_int::<Unaligned>(3, 3, 3));
+        assert!(bits_mut.is_empty());
+        assert!(BitsMut::new()
+            .put_per_constrained_int::<Unaligned>(0, 7, 8)
+            .is_err());
+    }
+
+    #[test]
+    fn test_constrained_int_aligned() {
+        // Ranges up to 255 are bit-fields, so there's no padding
+        let bits_mut = encode(|b| {
+            b.put_bool(true)?;
+            b.put_per_constrained_int::<Aligned>(0, 254, 0x12)
+        });
+        assert_eq!(bits_mut.len_bits(), 9);
+
+        // A range of 256 is a single aligned octet, and up to 64K is two
+        let bits_mut = encode(|b| {
+            b.put_bool(true)?;
+            b.put_per_constrained_int::<Aligned>(0, 255, 0x12)?;
+            b.put_u3(u3::new(0))?;
+            b.put_per_constrained_int::<Aligned>(0, 999, 5)
+        });
+        assert_eq!(
+            bits_mut.freeze(),
+            Bits::from_static_bytes(&[0x80, 0x12, 0x00, 0x00, 0x05])
+        );
+
+        // Larger ranges have a length of 1..=4 octets in 2 bits, then the aligned octets
+        let bits_mut = encode(|b| b.put_per_constrained_int::<Aligned>(0, u32::MAX as i64, 256));
+        assert_eq!(
+            bits_mut.freeze(),
+            Bits::from_static_bytes(&[0x40, 0x01, 0x00])
+        );
+
+        let mut bits = Bits::from_static_bytes(&[0x80, 0x12, 0x00, 0x00, 0x05, 0x40, 0x01, 0x00]);
+        assert!(bits.get_bool().unwrap());
+        assert_eq!(
+            bits.get_per_constrained_int::<Aligned>(0, 255).unwrap(),
+            0x12
+        );
+        assert_eq!(bits.get_u3().unwrap(), u3::new(0));
+        assert_eq!(bits.get_per_constrained_int::<Aligned>(0, 999).unwrap(), 5);
+        assert_eq!(
+            bits.get_per_constrained_int::<Aligned>(0, u32::MAX as i64)
+                .unwrap(),
+            256
+        );
+    }
+
+    #[test]
+    fn test_padding_from_cursor_position() {
+        // A 20-bit buffer is never byte aligned, but reading 3 bits leaves 5 bits of padding
+        let mut bits = Bits::from_static_bytes(&[0xA0, 0x12, 0x30]).slice_bits(0..20);
+        assert_eq!(bits.get_u3().unwrap(), u3::new(0b101));
+        assert_eq!(
+            bits.get_per_constrained_int::<Aligned>(0, 255).unwrap(),
+            0x12
+        );
+        assert_eq!(bits.remaining_bits(), 4);
+
+        // The same across a chain whose parts end mid-byte
+        let data = Bits::from_static_bytes(&[0xA0, 0x12, 0x30]);
+        let mut chain = data.slice_bits(0..6).chain(data.slice_bits(6..20));
+        assert_eq!(chain.get_u3().unwrap(), u3::new(0b101));
+        assert_eq!(
+            chain.get_per_constrained_int::<Aligned>(0, 255).unwrap(),
+            0x12
+        );
+
+        // Writing into the spare capacity of a slice which ends mid-byte
+        let mut data = [0xFFu8; 3];
+        let mut dest = &mut BitSlice::from_slice_mut(&mut data)[..20];
+        dest.put_u3(u3::new(0b101)).unwrap();
+        dest.put_per_constrained_int::<Aligned>(0, 255, 0x12)
+            .unwrap();
+        assert_eq!(dest.len(), 4);
+        assert_eq!(data, [0xA0, 0x12, 0xFF]);
+    }
+
+    #[test]
+    fn test_semi_constrained_and_unconstrained() {
+        let bits_mut = encode(|b| {
+            b.put_per_semi_constrained_int::<Aligned>(0, 256)?;
+            b.put_per_unconstrained_int::<Aligned>(128)?;
+            b.put_per_unconstrained_int::<Aligned>(-129)?;
+            b.put_per_unconstrained_int::<Aligned>(-1)
+        });
+        assert_eq!(
+            bits_mut.freeze(),
+            Bits::from_static_bytes(&[
+                0x02, 0x01, 0x00, 0x02, 0x00, 0x80, 0x02, 0xFF, 0x7F, 0x01, 0xFF
+            ])
+        );
+
+        for value in [0, 1, -1, 127, -128, i64::MIN, i64::MAX] {
+            let mut bits_mut = BitsMut::new();
+            bits_mut.put_u1(u1::new(1)).unwrap();
+            bits_mut
+                .put_per_unconstrained_int::<Unaligned>(value)
+                .unwrap();
+            bits_mut
+                .put_per_semi_constrained_int::<Unaligned>(-10, value.max(-10))
+                .unwrap();
+            let mut bits = bits_mut.freeze();
+            bits.get_u1().unwrap();
+            assert_eq!(
+                bits.get_per_unconstrained_int::<Unaligned>().unwrap(),
+                value
+            );
+            assert_eq!(
+                bits.get_per_semi_constrained_int::<Unaligned>(-10).unwrap(),
+                value.max(-10)
+            );
+        }
+    }
+
+    #[test]
+    fn test_length_determinant() {
+        let bits_mut = encode(|b| {
+            b.put_per_length::<Unaligned>(PerLength::Complete(127))?;
+            b.put_per_length::<Unaligned>(PerLength::Complete(128))?;
+            b.put_per_length::<Unaligned>(PerLength::Fragment(32768))
+        });
+        let mut bits = bits_mut.freeze();
+        assert_eq!(bits, Bits::from_static_bytes(&[0x7F, 0x80, 0x80, 0xC2]));
+        assert_eq!(
+            bits.get_per_length::<Unaligned>().unwrap(),
+            PerLength::Complete(127)
+        );
+        assert_eq!(
+            bits.get_per_length::<Unaligned>().unwrap(),
+            PerLength::Complete(128)
+        );
+        assert_eq!(
+            bits.get_per_length::<Unaligned>().unwrap(),
+            PerLength::Fragment(32768)
+        );
+
+        let mut bits_mut = BitsMut::new();
+        assert!(bits_mut
+            .put_per_length::<Unaligned>(PerLength::Complete(16384))
+            .is_err());
+        assert!(bits_mut
+            .put_per_length::<Unaligned>(PerLength::Fragment(1000))
+            .is_err());
+        assert!(Bits::from_static_bytes(&[0xC5])
+            .get_per_length::<Unaligned>()
+            .is_err());
+    }
+
+    #[test]
+    fn test_octet_string_fragmentation() {
+        let value: Vec<u8> = (0..40000).map(|i| i as u8).collect();
+        let bits_mut = encode(|b| {
+            b.put_bool(true)?;
+            b.put_per_octet_string::<Aligned>(SizeConstraint::UNCONSTRAINED, &value)
+        });
+        let mut bits = bits_mut.freeze();
+        // Padding, then a 32K fragment and the final 7232 octets
+        assert_eq!(
+            &bits.chunk_bits()[..16],
+            bits![1, 0, 0, 0, 0, 0, 0, 0, 1, 1, 0, 0, 0, 0, 1, 0]
+        );
+        assert_eq!(bits.remaining_bytes(), 1 + 1 + 32768 + 2 + 7232);
+        assert!(bits.get_bool().unwrap());
+        assert_eq!(
+            bits.get_per_octet_string::<Aligned>(SizeConstraint::UNCONSTRAINED)
+                .unwrap(),
+            value
+        );
+
+        // A length that's a multiple of 16K ends with an empty final fragment
+        let value = vec![0xAB; 16384];
+        let bits_mut =
+            encode(|b| b.put_per_octet_string::<Unaligned>(SizeConstraint::at_least(1), &value));
+        let mut bits = bits_mut.freeze();
+        assert_eq!(bits.remaining_bytes(), 1 + 16384 + 1);
+        assert_eq!(
+            bits.get_per_octet_string::<Unaligned>(SizeConstraint::at_least(1))
+                .unwrap(),
+            value
+        );
+    }
+
+    #[test]
+    fn test_strings_with_size_constraints() {
+        let bits_mut = encode(|b| {
+            // Fixed sizes up to 16 bits have no length or padding
+            b.put_per_bit_string::<Aligned>(SizeConstraint::fixed(3), bits![1, 0, 1])?;
+            b.put_per_octet_string::<Aligned>(SizeConstraint::fixed(2), &[0xAB, 0xCD])?;
+            // A constrained length, then aligned octets
+            b.put_per_octet_string::<Aligned>(SizeConstraint::range(1, 4), &[0x01, 0x02, 0x03])
+        });
+        let mut bits = bits_mut.freeze();
+        assert_eq!(
+            &bits[..],
+            bits![
+                1, 0, 1, //
+                1, 0, 1, 0, 1, 0, 1, 1, 1, 1, 0, 0, 1, 1, 0, 1, //
+                1, 0, 0, 0, 0, //
+                0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0, 1, 1
+            ]
+        );
+        assert_eq!(
+            bits.get_per_bit_string::<Aligned>(SizeConstraint::fixed(3))
+                .unwrap(),
+            bits![1, 0, 1]
+        );
+        assert_eq!(
+            bits.get_per_octet_string::<Aligned>(SizeConstraint::fixed(2))
+                .unwrap(),
+            [0xAB, 0xCD]
+        );
+        assert_eq!(
+            bits.get_per_octet_string::<Aligned>(SizeConstraint::range(1, 4))
+                .unwrap(),
+            [0x01, 0x02, 0x03]
+        );
+
+        let mut bits_mut = BitsMut::new();
+        assert!(bits_mut
+            .put_per_octet_string::<Unaligned>(SizeConstraint::range(1, 4), &[0; 5])
+            .is_err());
+        assert!(bits_mut.is_empty());
+    }
+
+    #[test]
+    fn test_fixed_size_64k() {
+        // Fixed sizes below 64K have no length...
+        let value = vec![0xAB; 65535];
+        let bits_mut =
+            encode(|b| b.put_per_octet_string::<Unaligned>(SizeConstraint::fixed(65535), &value));
+        let mut bits = bits_mut.freeze();
+        assert_eq!(bits.remaining_bytes(), 65535);
+        assert_eq!(
+            bits.get_per_octet_string::<Unaligned>(SizeConstraint::fixed(65535))
+                .unwrap(),
+            value
+        );
+
+        // ...but 64K itself is fragmented like an unconstrained length: a 64K fragment and an
+        // empty final one
+        let value = vec![0xAB; 65536];
+        let bits_mut =
+            encode(|b| b.put_per_octet_string::<Unaligned>(SizeConstraint::fixed(65536), &value));
+        let mut bits = bits_mut.freeze();
+        assert_eq!(bits.remaining_bytes(), 1 + 65536 + 1);
+        assert_eq!(bits.chunk_bytes()[0], 0xC4);
+        assert_eq!(
+            bits.get_per_octet_string::<Unaligned>(SizeConstraint::fixed(65536))
+                .unwrap(),
+            value
+        );
+    }
+
+    #[test]
+    fn test_enumerated() {
+        let bits_mut = encode(|b| {
+            b.put_per_enumerated::<Unaligned>(3, false, 2)?;
+            b.put_per_enumerated::<Unaligned>(3, true, 1)?;
+            b.put_per_enumerated::<Unaligned>(3, true, 4)
+        });
+        let mut bits = bits_mut.freeze();
+        assert_eq!(&bits[..], bits![1, 0, 0, 0, 1, 1, 0, 0, 0, 0, 0, 0, 1]);
+        assert_eq!(bits.get_per_enumerated::<Unaligned>(3, false).unwrap(), 2);
+        assert_eq!(bits.get_per_enumerated::<Unaligned>(3, true).unwrap(), 1);
+        assert_eq!(bits.get_per_enumerated::<Unaligned>(3, true).unwrap(), 4);
+    }
+
+    #[test]
+    fn test_sequence_preamble() {
+        let preamble = SequencePreamble {
+            extended: true,
+            optional_present: BitVec::from_bitslice(bits![0, 1, 1]),
+        };
+        let bits_mut = encode(|b| b.put_per_sequence_preamble(true, &preamble));
+        let mut bits = bits_mut.freeze();
+        assert_eq!(&bits[..], bits![1, 0, 1, 1]);
+        assert_eq!(bits.get_per_sequence_preamble(true, 3).unwrap(), preamble);
+
+        assert!(BitsMut::new()
+            .put_per_sequence_preamble(false, &preamble)
+            .is_err());
+    }
+}
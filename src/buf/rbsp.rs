@@ -0,0 +1,177 @@
+use super::util::bits_to_byte_boundary;
+use crate::prelude::*;
+
+/// Reads of the framing bits used by video bitstream syntax (H.264, H.265 and AV1) around a raw
+/// byte sequence payload (RBSP).  Byte boundaries are counted from the start of the buffer's
+/// underlying storage, so the payload must start on one.  Errors give the position of the
+/// offending bit the same way, as its index (0 to 7, from the first bit in the buffer's bit order)
+/// within its byte of the storage.
+pub trait BitBufRbspExts: BitBuf {
+    /// Consumes zero-valued alignment bits up to the next byte boundary, as in AV1's
+    /// `byte_alignment()`.  Returns an error if any of them are set.
+    ///
+    /// Note that the `byte_alignment()` of H.265 and H.266 is different: it starts with an
+    /// `alignment_bit_equal_to_one`, and so has the same form as the `rbsp_trailing_bits()` read
+    /// by [`rbsp_trailing_bits`](Self::rbsp_trailing_bits).
+    fn byte_alignment(&mut self) -> std::io::Result<()> {
+        read_zero_alignment_bits(self)
+    }
+
+    /// Returns whether there's more payload before the `rbsp_trailing_bits()`: i.e. whether the
+    /// last set bit in the remaining data (the stop bit) comes after the current position.  This
+    /// looks ahead without consuming anything.
+    ///
+    /// The remaining data must all be in the current chunk (which is always the case for
+    /// [`Bits`], [`BitSlice`] and `&[u8]`), otherwise an error is returned.
+    fn more_rbsp_data(&self) -> std::io::Result<bool> {
+        let chunk = self.chunk_bits();
+        if chunk.len() != self.remaining_bits() {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::Unsupported,
+                format!(
+                    "Can't look ahead past the current chunk ({} of {} remaining bits)",
+                    chunk.len(),
+                    self.remaining_bits()
+                ),
+            ));
+        }
+        Ok(chunk.last_one().is_some_and(|stop_bit| stop_bit > 0))
+    }
+
+    /// Consumes the `rbsp_trailing_bits()`: a stop bit equal to one followed by zero-valued
+    /// alignment bits up to the next byte boundary.
+    fn rbsp_trailing_bits(&mut self) -> std::io::Result<()> {
+        let position = bit_in_byte(self.chunk_bits());
+        if !self.get_bool()? {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                format!("RBSP stop bit (bit {} of its byte) is not one", position),
+            ));
+        }
+        read_zero_alignment_bits(self)
+    }
+}
+
+impl<T: BitBuf + ?Sized> BitBufRbspExts for T {}
+
+/// The index of the first bit of `chunk` within its byte of the underlying storage.
+fn bit_in_byte(chunk: &BitSlice) -> usize {
+    chunk.as_bitptr().bit().into_inner() as usize
+}
+
+/// Consumes zero-valued bits up to the next byte boundary.
+fn read_zero_alignment_bits<B: BitBuf + ?Sized>(buf: &mut B) -> std::io::Result<()> {
+    // byte_aligned() also depends on how many bits remain, so go by the cursor's position within
+    // its byte instead
+    for _ in 0..bits_to_byte_boundary(buf.chunk_bits()) {
+        let position = bit_in_byte(buf.chunk_bits());
+        if buf.get_bool()? {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                format!("Alignment bit {} of its byte is not zero", position),
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Writes of the framing bits around a raw byte sequence payload.
+pub trait BitBufMutRbspExts: BitBufMut {
+    /// Writes zero bits up to the next byte boundary, as in AV1's `byte_alignment()`.  See
+    /// [`BitBufRbspExts::byte_alignment`] for how H.265's and H.266's differ.
+    fn put_byte_alignment(&mut self) -> std::io::Result<()> {
+        for _ in 0..bits_to_byte_boundary(self.chunk_mut_bits()) {
+            self.put_bool(false)?;
+        }
+        Ok(())
+    }
+
+    /// Writes the `rbsp_trailing_bits()`: a stop bit followed by zero bits up to the next byte
+    /// boundary.
+    fn put_rbsp_trailing_bits(&mut self) -> std::io::Result<()> {
+        self.put_bool(true)?;
+        self.put_byte_alignment()
+    }
+}
+
+impl<T: BitBufMut + ?Sized> BitBufMutRbspExts for T {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_more_rbsp_data() {
+        let mut bits = Bits::from_static_bytes(&[0b0110_0000, 0b1000_0000]);
+        assert!(bits.more_rbsp_data().unwrap());
+        bits.advance_bits(3);
+        assert!(bits.more_rbsp_data().unwrap());
+        bits.advance_bits(5);
+        // Only the stop bit (and the zero bits after it) remain
+        assert!(!bits.more_rbsp_data().unwrap());
+        bits.rbsp_trailing_bits().unwrap();
+        assert!(!bits.has_remaining_bits());
+        assert!(!bits.more_rbsp_data().unwrap());
+
+        let chain = Bits::from_static_bytes(&[0x80]).chain(Bits::from_static_bytes(&[0x80]));
+        assert!(chain.more_rbsp_data().is_err());
+    }
+
+    #[test]
+    fn test_byte_alignment() {
+        let mut bits = Bits::from_static_bytes(&[0b1010_0000, 0b1100_0100]);
+        bits.advance_bits(3);
+        bits.byte_alignment().unwrap();
+        assert_eq!(bits.remaining_bits(), 8);
+        // Already aligned, so nothing is consumed
+        bits.byte_alignment().unwrap();
+        assert_eq!(bits.remaining_bits(), 8);
+
+        bits.advance_bits(2);
+        let error = bits.byte_alignment().unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+        assert!(error.to_string().contains("bit 5 of its byte"));
+
+        // Alignment goes by the cursor's position, not by how many bits remain
+        let mut bits = Bits::from_static_bytes(&[0b1010_0000, 0x12, 0x30]).slice_bits(0..20);
+        bits.advance_bits(3);
+        bits.byte_alignment().unwrap();
+        assert_eq!(bits.get_u8().unwrap(), 0x12);
+        let mut chain = Bits::from_static_bytes(&[0b1010_0000, 0x12])
+            .slice_bits(0..6)
+            .chain(Bits::from_static_bytes(&[0b1010_0000, 0x12]).slice_bits(6..16));
+        chain.advance_bits(3);
+        chain.byte_alignment().unwrap();
+        assert_eq!(chain.get_u8().unwrap(), 0x12);
+
+        let mut data = [0xFFu8; 3];
+        let mut dest = &mut BitSlice::from_slice_mut(&mut data)[..20];
+        dest.put_u3(u3::new(0b101)).unwrap();
+        dest.put_byte_alignment().unwrap();
+        assert_eq!(dest.len(), 12);
+        assert_eq!(data[0], 0b1010_0000);
+    }
+
+    #[test]
+    fn test_rbsp_trailing_bits() {
+        let mut bits = Bits::from_static_bytes(&[0b1010_0000]);
+        bits.advance_bits(1);
+        let error = bits.rbsp_trailing_bits().unwrap_err();
+        assert!(error.to_string().contains("stop bit (bit 1 of its byte)"));
+        let mut bits = Bits::from_static_bytes(&[0b1100_1000]);
+        bits.advance_bits(1);
+        let error = bits.rbsp_trailing_bits().unwrap_err();
+        assert!(error.to_string().contains("Alignment bit 4 of its byte"));
+
+        let mut bits_mut = BitsMut::new();
+        bits_mut.put_u3(u3::new(0b101)).unwrap();
+        bits_mut.put_rbsp_trailing_bits().unwrap();
+        bits_mut.put_rbsp_trailing_bits().unwrap();
+        bits_mut.put_byte_alignment().unwrap();
+        let mut bits = bits_mut.freeze();
+        assert_eq!(bits, Bits::from_static_bytes(&[0b1011_0000, 0b1000_0000]));
+        bits.advance_bits(3);
+        bits.rbsp_trailing_bits().unwrap();
+        bits.rbsp_trailing_bits().unwrap();
+    }
+}
@@ -0,0 +1,192 @@
+use crate::prelude::*;
+
+/// Reads a bitstream backwards from its end, as Zstandard's FSE and Huffman streams are read.
+///
+/// The data is treated as one little-endian integer.  The highest set bit of the last byte is a
+/// sentinel marking where the stream starts, and each read takes the next `n` bits below the
+/// current position, returning them as an `n`-bit value.
+pub struct ReverseBitReader {
+    inner: Bits,
+    /// The number of bits left to read, which is also the position of the next bit above them
+    position: usize,
+}
+
+impl ReverseBitReader {
+    /// Creates a reader over `inner`, which must be byte-aligned.  Returns an error if `inner` is
+    /// empty or its last byte is zero (so it has no sentinel bit).
+    pub fn new(inner: Bits) -> std::io::Result<Self> {
+        if !inner.byte_aligned() {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "Buf beginning and end must both be byte-aligned",
+            ));
+        }
+        let last_byte = inner.chunk_bytes().last().copied().unwrap_or(0);
+        if last_byte == 0 {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "Reverse bitstream has no sentinel bit in its last byte",
+            ));
+        }
+        let position = inner.len_bits() - 1 - last_byte.leading_zeros() as usize;
+        Ok(Self { inner, position })
+    }
+
+    pub fn into_inner(self) -> Bits {
+        self.inner
+    }
+
+    /// The number of bits between the current position and the start of the stream.
+    pub fn remaining_bits(&self) -> usize {
+        self.position
+    }
+
+    /// Returns true if every bit has been read.
+    pub fn is_empty(&self) -> bool {
+        self.position == 0
+    }
+
+    /// Reads the next `n` (at most 64) bits.  Returns an error if fewer than `n` bits remain.
+    pub fn get_bits(&mut self, n: usize) -> std::io::Result<u64> {
+        assert!(n <= 64, "Can't read {} bits into a u64", n);
+        if n > self.position {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::UnexpectedEof,
+                format!(
+                    "Remaining bits ({}) are less than the size of the dest ({})",
+                    self.position, n
+                ),
+            ));
+        }
+        self.position -= n;
+        Ok(self.load(self.position, n))
+    }
+
+    /// Returns the next `n` (at most 64) bits without consuming them.  Bits past the start of the
+    /// stream read as zeros, which lets table-driven decoders look ahead by their maximum code
+    /// length near the end.
+    pub fn peek_bits(&self, n: usize) -> u64 {
+        assert!(n <= 64, "Can't read {} bits into a u64", n);
+        if n > self.position {
+            self.load(0, self.position) << (n - self.position)
+        } else {
+            self.load(self.position - n, n)
+        }
+    }
+
+    /// Loads the `n` bits starting at bit `start` of the little-endian integer.
+    fn load(&self, start: usize, n: usize) -> u64 {
+        if n == 0 {
+            return 0;
+        }
+        let bytes = &self.inner.chunk_bytes()[start / 8..(start + n - 1) / 8 + 1];
+        let value = bytes
+            .iter()
+            .rev()
+            .fold(0u128, |value, &byte| (value << 8) | byte as u128);
+        ((value >> (start % 8)) & ((1u128 << n) - 1)) as u64
+    }
+}
+
+/// Writes a bitstream to be read backwards by a [`ReverseBitReader`]: the first value written is
+/// the last one read.  Values are packed from the least significant bit of each byte up, and
+/// [`ReverseBitWriter::finish`] adds the sentinel bit.
+pub struct ReverseBitWriter<B: BitBufMut> {
+    inner: B,
+    /// Bits which don't yet make up a whole byte
+    pending: u128,
+    pending_len: usize,
+}
+
+impl<B: BitBufMut> ReverseBitWriter<B> {
+    pub fn new(inner: B) -> Self {
+        Self {
+            inner,
+            pending: 0,
+            pending_len: 0,
+        }
+    }
+
+    /// Writes the low `n` (at most 64) bits of `value`.  Returns an error if `value` doesn't fit
+    /// in `n` bits.
+    pub fn put_bits(&mut self, n: usize, value: u64) -> std::io::Result<()> {
+        assert!(n <= 64, "Can't write {} bits from a u64", n);
+        if n < 64 && value >> n != 0 {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!("Value ({}) does not fit in {} bits", value, n),
+            ));
+        }
+        self.pending |= (value as u128) << self.pending_len;
+        self.pending_len += n;
+        while self.pending_len >= 8 {
+            self.inner.put_u8(self.pending as u8)?;
+            self.pending >>= 8;
+            self.pending_len -= 8;
+        }
+        Ok(())
+    }
+
+    /// Writes the sentinel bit, pads the last byte with zeros and returns the inner buffer.
+    pub fn finish(mut self) -> std::io::Result<B> {
+        self.put_bits(1, 1)?;
+        if self.pending_len > 0 {
+            self.inner.put_u8(self.pending as u8)?;
+        }
+        Ok(self.inner)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reverse_read() {
+        // The sentinel is bit 4 of the last byte, so reading starts with bits 3:0 of it
+        let mut reader =
+            ReverseBitReader::new(Bits::from_static_bytes(&[0b1010_0101, 0b0001_0110])).unwrap();
+        assert_eq!(reader.remaining_bits(), 12);
+        assert_eq!(reader.get_bits(3).unwrap(), 0b011);
+        // A read which crosses into the previous byte
+        assert_eq!(reader.get_bits(3).unwrap(), 0b010);
+        assert_eq!(reader.peek_bits(8), 0b1001_0100);
+        assert_eq!(reader.get_bits(6).unwrap(), 0b100101);
+        assert!(reader.is_empty());
+        assert_eq!(reader.peek_bits(3), 0);
+        assert!(reader.get_bits(1).is_err());
+
+        assert!(ReverseBitReader::new(Bits::from_static_bytes(&[0x12, 0x00])).is_err());
+        assert!(ReverseBitReader::new(Bits::from_static_bytes(&[])).is_err());
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let values: Vec<(usize, u64)> = (0..200)
+            .map(|i| {
+                let n = (i * 7) % 65;
+                let value = 0x0123_4567_89ab_cdefu64.rotate_left(i as u32);
+                (
+                    n,
+                    if n == 64 {
+                        value
+                    } else {
+                        value & ((1 << n) - 1)
+                    },
+                )
+            })
+            .collect();
+        let mut writer = ReverseBitWriter::new(BitsMut::new());
+        for &(n, value) in &values {
+            writer.put_bits(n, value).unwrap();
+        }
+        assert!(writer.put_bits(3, 8).is_err());
+        let bits = writer.finish().unwrap().freeze();
+
+        let mut reader = ReverseBitReader::new(bits).unwrap();
+        for &(n, value) in values.iter().rev() {
+            assert_eq!(reader.get_bits(n).unwrap(), value);
+        }
+        assert!(reader.is_empty());
+    }
+}
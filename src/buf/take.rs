@@ -34,7 +34,7 @@ impl<T> Take<T> {
     }
 }
 
-impl<T: BitBuf> BitBuf for Take<T> {
+impl<Order: BitOrder, T: BitBuf<Order>> BitBuf<Order> for Take<T> {
     fn advance_bits(&mut self, count: usize) {
         assert!(count <= self.limit);
         self.inner.advance_bits(count);
@@ -45,7 +45,7 @@ impl<T: BitBuf> BitBuf for Take<T> {
         std::cmp::min(self.inner.remaining_bits(), self.limit)
     }
 
-    fn chunk_bits(&self) -> &BitSlice {
+    fn chunk_bits(&self) -> &bitvec::slice::BitSlice<u8, Order> {
         let chunk = self.inner.chunk_bits();
         let end = std::cmp::min(chunk.len(), self.limit);
         &chunk[..end]
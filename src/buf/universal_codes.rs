@@ -0,0 +1,412 @@
+use super::util::consume_run;
+use crate::prelude::*;
+
+/// The universal codes supported by [`BitBufUniversalExts`] and [`BitBufMutUniversalExts`].  All
+/// of them encode positive integers: zero can't be represented.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum UniversalCode {
+    EliasGamma,
+    EliasDelta,
+    EliasOmega,
+    Fibonacci,
+}
+
+impl std::fmt::Display for UniversalCode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            UniversalCode::EliasGamma => "Elias gamma",
+            UniversalCode::EliasDelta => "Elias delta",
+            UniversalCode::EliasOmega => "Elias omega",
+            UniversalCode::Fibonacci => "Fibonacci",
+        };
+        f.write_str(name)
+    }
+}
+
+/// The number of Fibonacci numbers (starting from 1, 2, 3, 5...) which fit in a `u64`, and so the
+/// longest Fibonacci code (excluding the terminating bit) a `u64` can be decoded from.
+const FIBONACCI_LEN: usize = 92;
+
+const FIBONACCI: [u64; FIBONACCI_LEN] = {
+    let mut table = [0u64; FIBONACCI_LEN];
+    table[0] = 1;
+    table[1] = 2;
+    let mut i = 2;
+    while i < FIBONACCI_LEN {
+        table[i] = table[i - 1] + table[i - 2];
+        i += 1;
+    }
+    table
+};
+
+fn invalid_data(message: String) -> std::io::Error {
+    std::io::Error::new(std::io::ErrorKind::InvalidData, message)
+}
+
+fn check_positive(code: UniversalCode, value: u64) -> std::io::Result<()> {
+    if value == 0 {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            format!("{} codes can't represent 0", code),
+        ));
+    }
+    Ok(())
+}
+
+/// Adds the index of the value being processed to an error from a batch operation, keeping its
+/// kind.
+fn batch_error(
+    code: UniversalCode,
+    index: usize,
+    count: usize,
+    e: std::io::Error,
+) -> std::io::Error {
+    std::io::Error::new(
+        e.kind(),
+        format!("{} code {} of {}: {}", code, index, count, e),
+    )
+}
+
+/// Reads of universal codes: self-delimiting codes for positive integers which don't need to know
+/// the range of values ahead of time.
+pub trait BitBufUniversalExts<Order: BitOrder = Msb0>: BitBuf<Order> {
+    /// Reads an Elias gamma code: `N` zero bits followed by the `N + 1` bit binary representation
+    /// of the value (which always starts with a one).
+    fn get_elias_gamma(&mut self) -> std::io::Result<u64> {
+        let num_zeros = consume_run(self, false, 63)?;
+        // consume_run already consumed the leading one
+        let low_bits = self.get_bits_as::<u64, Order::FieldOrder>(num_zeros)?;
+
+        Ok((1 << num_zeros) | low_bits)
+    }
+
+    /// Reads an Elias delta code: the length of the value's binary representation as an Elias
+    /// gamma code, followed by that representation without its leading one.
+    fn get_elias_delta(&mut self) -> std::io::Result<u64> {
+        let len = self.get_elias_gamma()?;
+        if len > 64 {
+            return Err(invalid_data(format!(
+                "Elias delta code has a length of {} bits, which overflows a u64",
+                len
+            )));
+        }
+        let num_bits = len as usize - 1;
+        let low_bits = self.get_bits_as::<u64, Order::FieldOrder>(num_bits)?;
+
+        Ok((1 << num_bits) | low_bits)
+    }
+
+    /// Reads an Elias omega code: a series of groups, each of which is the binary representation
+    /// of the length of the next group minus one, ending with a zero bit.
+    fn get_elias_omega(&mut self) -> std::io::Result<u64> {
+        let mut value = 1u64;
+        while self.get_bool()? {
+            if value >= 64 {
+                return Err(invalid_data(format!(
+                    "Elias omega group of {} bits overflows a u64",
+                    value + 1
+                )));
+            }
+            // The leading one of this group was already read
+            let num_bits = value as usize;
+            value = (1 << num_bits) | self.get_bits_as::<u64, Order::FieldOrder>(num_bits)?;
+        }
+
+        Ok(value)
+    }
+
+    /// Reads a Fibonacci code: the value's Zeckendorf representation (a sum of non-consecutive
+    /// Fibonacci numbers), least significant term first, terminated by an extra one bit so that
+    /// every code ends with `11`.
+    fn get_fibonacci(&mut self) -> std::io::Result<u64> {
+        let mut value = 0u64;
+        let mut prev_bit = false;
+        for fib in FIBONACCI.iter() {
+            let bit = self.get_bool()?;
+            if bit && prev_bit {
+                return Ok(value);
+            }
+            if bit {
+                value = value
+                    .checked_add(*fib)
+                    .ok_or_else(|| invalid_data("Fibonacci code overflows a u64".to_string()))?;
+            }
+            prev_bit = bit;
+        }
+        // Every term has been used, so the next bit must be the terminator
+        if prev_bit && self.get_bool()? {
+            return Ok(value);
+        }
+        Err(invalid_data(format!(
+            "Fibonacci code is longer than the maximum ({} bits)",
+            FIBONACCI_LEN + 1
+        )))
+    }
+
+    /// Reads a single value encoded with `code`.
+    fn get_universal(&mut self, code: UniversalCode) -> std::io::Result<u64> {
+        match code {
+            UniversalCode::EliasGamma => self.get_elias_gamma(),
+            UniversalCode::EliasDelta => self.get_elias_delta(),
+            UniversalCode::EliasOmega => self.get_elias_omega(),
+            UniversalCode::Fibonacci => self.get_fibonacci(),
+        }
+    }
+
+    /// Reads `values.len()` values encoded with `code` into `values`.  If any of the codes is
+    /// truncated or invalid, the error notes which one.
+    fn get_universal_into(
+        &mut self,
+        code: UniversalCode,
+        values: &mut [u64],
+    ) -> std::io::Result<()> {
+        let count = values.len();
+        for (i, value) in values.iter_mut().enumerate() {
+            *value = self
+                .get_universal(code)
+                .map_err(|e| batch_error(code, i, count, e))?;
+        }
+        Ok(())
+    }
+}
+
+impl<Order: BitOrder, T: BitBuf<Order> + ?Sized> BitBufUniversalExts<Order> for T {}
+
+/// Writes of universal codes.  Writing zero returns an error, since none of the codes can
+/// represent it.
+pub trait BitBufMutUniversalExts<Order: BitOrder = Msb0>: BitBufMut<Order> {
+    fn put_elias_gamma(&mut self, value: u64) -> std::io::Result<()> {
+        check_positive(UniversalCode::EliasGamma, value)?;
+        let num_bits = 64 - value.leading_zeros() as usize;
+        self.put_bits_from::<u64, Order::FieldOrder>(num_bits - 1, 0)?;
+        // As when reading, the leading one is written on its own so that it comes first in either
+        // bit order
+        self.put_bool(true)?;
+        self.put_bits_from::<u64, Order::FieldOrder>(num_bits - 1, value & !(1 << (num_bits - 1)))
+    }
+
+    fn put_elias_delta(&mut self, value: u64) -> std::io::Result<()> {
+        check_positive(UniversalCode::EliasDelta, value)?;
+        let num_bits = 64 - value.leading_zeros() as usize;
+        self.put_elias_gamma(num_bits as u64)?;
+        self.put_bits_from::<u64, Order::FieldOrder>(num_bits - 1, value & !(1 << (num_bits - 1)))
+    }
+
+    fn put_elias_omega(&mut self, value: u64) -> std::io::Result<()> {
+        check_positive(UniversalCode::EliasOmega, value)?;
+        // Groups are generated last-first, so collect them before writing.  A u64 never needs more
+        // than 4 groups (64 bits, then 63, 5 and 2).
+        let mut groups = [(0u64, 0usize); 4];
+        let mut num_groups = 0;
+        let mut remaining = value;
+        while remaining > 1 {
+            let num_bits = 64 - remaining.leading_zeros() as usize;
+            groups[num_groups] = (remaining, num_bits);
+            num_groups += 1;
+            remaining = num_bits as u64 - 1;
+        }
+        for &(group, num_bits) in groups[..num_groups].iter().rev() {
+            self.put_bool(true)?;
+            self.put_bits_from::<u64, Order::FieldOrder>(
+                num_bits - 1,
+                group & !(1 << (num_bits - 1)),
+            )?;
+        }
+        self.put_bool(false)
+    }
+
+    fn put_fibonacci(&mut self, value: u64) -> std::io::Result<()> {
+        check_positive(UniversalCode::Fibonacci, value)?;
+        // Greedily pick the largest Fibonacci numbers, which gives the Zeckendorf representation
+        let highest = FIBONACCI.partition_point(|&fib| fib <= value) - 1;
+        let mut code = bitvec::vec::BitVec::<u8, Order>::repeat(false, highest + 2);
+        let mut remaining = value;
+        for i in (0..=highest).rev() {
+            if FIBONACCI[i] <= remaining {
+                code.set(i, true);
+                remaining -= FIBONACCI[i];
+            }
+        }
+        code.set(highest + 1, true);
+        self.try_put_bit_slice(&code)
+    }
+
+    /// Writes a single value encoded with `code`.
+    fn put_universal(&mut self, code: UniversalCode, value: u64) -> std::io::Result<()> {
+        match code {
+            UniversalCode::EliasGamma => self.put_elias_gamma(value),
+            UniversalCode::EliasDelta => self.put_elias_delta(value),
+            UniversalCode::EliasOmega => self.put_elias_omega(value),
+            UniversalCode::Fibonacci => self.put_fibonacci(value),
+        }
+    }
+
+    /// Writes each of `values` encoded with `code`.
+    fn put_universal_from(&mut self, code: UniversalCode, values: &[u64]) -> std::io::Result<()> {
+        for (i, value) in values.iter().enumerate() {
+            self.put_universal(code, *value)
+                .map_err(|e| batch_error(code, i, values.len(), e))?;
+        }
+        Ok(())
+    }
+}
+
+impl<Order: BitOrder, T: BitBufMut<Order> + ?Sized> BitBufMutUniversalExts<Order> for T {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALL_CODES: [UniversalCode; 4] = [
+        UniversalCode::EliasGamma,
+        UniversalCode::EliasDelta,
+        UniversalCode::EliasOmega,
+        UniversalCode::Fibonacci,
+    ];
+
+    fn encode(code: UniversalCode, value: u64) -> BitVec {
+        let mut bits_mut = BitsMut::new();
+        bits_mut.put_universal(code, value).unwrap();
+        bits_mut.freeze().chunk_bits().to_bitvec()
+    }
+
+    #[test]
+    fn test_known_encodings() {
+        assert_eq!(encode(UniversalCode::EliasGamma, 1), bits![1]);
+        assert_eq!(
+            encode(UniversalCode::EliasGamma, 9),
+            bits![0, 0, 0, 1, 0, 0, 1]
+        );
+        assert_eq!(encode(UniversalCode::EliasDelta, 1), bits![1]);
+        assert_eq!(
+            encode(UniversalCode::EliasDelta, 9),
+            bits![0, 0, 1, 0, 0, 0, 0, 1]
+        );
+        assert_eq!(encode(UniversalCode::EliasOmega, 1), bits![0]);
+        assert_eq!(
+            encode(UniversalCode::EliasOmega, 17),
+            bits![1, 0, 1, 0, 0, 1, 0, 0, 0, 1, 0]
+        );
+        assert_eq!(encode(UniversalCode::Fibonacci, 1), bits![1, 1]);
+        assert_eq!(encode(UniversalCode::Fibonacci, 4), bits![1, 0, 1, 1]);
+        assert_eq!(
+            encode(UniversalCode::Fibonacci, 11),
+            bits![0, 0, 1, 0, 1, 1]
+        );
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let values = [
+            1,
+            2,
+            3,
+            4,
+            5,
+            16,
+            17,
+            1000,
+            u32::MAX as u64,
+            u64::MAX - 1,
+            u64::MAX,
+        ];
+        for code in ALL_CODES {
+            let mut bits_mut = BitsMut::new();
+            // Start mid-byte to make sure nothing depends on alignment
+            bits_mut.put_u3(u3::new(0b101)).unwrap();
+            bits_mut.put_universal_from(code, &values).unwrap();
+
+            let mut bits = bits_mut.freeze();
+            bits.advance_bits(3);
+            let mut decoded = [0u64; 11];
+            bits.get_universal_into(code, &mut decoded).unwrap();
+            assert_eq!(decoded, values, "{}", code);
+            assert!(!bits.has_remaining_bits());
+        }
+    }
+
+    #[test]
+    fn test_lsb_round_trip() {
+        // Each code's leading one comes first in either bit order
+        let mut bits_mut = LsbBitsMut::new();
+        bits_mut.put_elias_gamma(9).unwrap();
+        assert_eq!(bits_mut[..], lsb_bits![0, 0, 0, 1, 1, 0, 0]);
+
+        let values = [1, 2, 3, 17, 1000, u32::MAX as u64, u64::MAX];
+        for code in ALL_CODES {
+            let mut bits_mut = LsbBitsMut::new();
+            bits_mut.put_u3(u3::new(0b101)).unwrap();
+            bits_mut.put_universal_from(code, &values).unwrap();
+
+            let mut bits = bits_mut.freeze();
+            bits.advance_bits(3);
+            let mut decoded = [0u64; 7];
+            bits.get_universal_into(code, &mut decoded).unwrap();
+            assert_eq!(decoded, values, "{}", code);
+            assert!(!bits.has_remaining_bits());
+        }
+    }
+
+    #[test]
+    fn test_zero() {
+        let mut bits_mut = BitsMut::new();
+        for code in ALL_CODES {
+            let error = bits_mut.put_universal(code, 0).unwrap_err();
+            assert_eq!(error.kind(), std::io::ErrorKind::InvalidInput);
+        }
+        let error = bits_mut
+            .put_universal_from(UniversalCode::EliasGamma, &[1, 2, 0])
+            .unwrap_err();
+        assert!(error.to_string().contains("code 2 of 3"));
+    }
+
+    #[test]
+    fn test_truncated() {
+        for code in ALL_CODES {
+            let encoded = encode(code, 1000);
+            let mut bits = Bits::copy_from_bit_slice(&encoded[..encoded.len() - 1]);
+            let error = bits.get_universal(code).unwrap_err();
+            assert_eq!(error.kind(), std::io::ErrorKind::UnexpectedEof, "{}", code);
+        }
+
+        let mut bits = Bits::copy_from_bit_slice(bits![1, 1, 0, 1]);
+        let mut values = [0u64; 3];
+        let error = bits
+            .get_universal_into(UniversalCode::EliasGamma, &mut values)
+            .unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::UnexpectedEof);
+        assert!(error.to_string().contains("code 2 of 3"));
+    }
+
+    #[test]
+    fn test_invalid() {
+        // 64 leading zeros can't be followed by a value which fits in a u64
+        let mut bits = Bits::from(BitVec::repeat(false, 80));
+        let error = bits.get_elias_gamma().unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+
+        // A delta code with a length of 65 bits
+        let mut bits_mut = BitsMut::new();
+        bits_mut.put_elias_gamma(65).unwrap();
+        bits_mut.put_u64::<BigEndian>(0).unwrap();
+        let error = bits_mut.freeze().get_elias_delta().unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+
+        // An omega code whose groups keep growing
+        let mut raw = encode(UniversalCode::EliasOmega, 64);
+        let last = raw.len() - 1;
+        // Replace the terminating zero with the start of another (65 bit) group
+        raw.set(last, true);
+        raw.extend_from_bitslice(&BitVec::repeat(false, 64));
+        let error = Bits::from(raw).get_elias_omega().unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+
+        // A Fibonacci code with no terminator
+        let mut raw = BitVec::repeat(false, 0);
+        for _ in 0..50 {
+            raw.extend_from_bitslice(bits![1, 0]);
+        }
+        let error = Bits::from(raw).get_fibonacci().unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+    }
+}
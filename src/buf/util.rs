@@ -1,4 +1,60 @@
+use crate::prelude::*;
+
 /// Returns the number of bytes needed to accommodate the given number of bits
 pub(crate) fn bytes_needed(num_bits: usize) -> usize {
     num_bits.div_ceil(8)
 }
+
+/// Returns the number of bits from the start of `chunk` to the next byte boundary of its
+/// underlying storage.  Unlike `byte_aligned`, this doesn't depend on how long the chunk is.
+pub(crate) fn bits_to_byte_boundary<Order: BitOrder>(
+    chunk: &bitvec::slice::BitSlice<u8, Order>,
+) -> usize {
+    (8 - chunk.as_bitptr().bit().into_inner() as usize) % 8
+}
+
+/// Consumes a run of bits equal to `bit` from `buf`, along with the opposite-valued bit which
+/// terminates it, and returns the length of the run.  The run is scanned a chunk at a time rather
+/// than bit-by-bit, and may span chunk boundaries (e.g. in a `Chain`).
+///
+/// Returns an error if the run is longer than `max_run` or if the buffer ends before the
+/// terminating bit.
+pub(crate) fn consume_run<Order: BitOrder, B: BitBuf<Order> + ?Sized>(
+    buf: &mut B,
+    bit: bool,
+    max_run: usize,
+) -> std::io::Result<usize> {
+    let mut run = 0;
+    loop {
+        let chunk = buf.chunk_bits();
+        if chunk.is_empty() {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::UnexpectedEof,
+                format!(
+                    "Buffer ended after a run of {} bits with no terminator",
+                    run
+                ),
+            ));
+        }
+        let terminator = if bit {
+            chunk.first_zero()
+        } else {
+            chunk.first_one()
+        };
+        let count = terminator.unwrap_or(chunk.len());
+        if run + count > max_run {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                format!("Run of bits is longer than the maximum ({})", max_run),
+            ));
+        }
+        run += count;
+        match terminator {
+            Some(_) => {
+                buf.advance_bits(count + 1);
+                return Ok(run);
+            }
+            None => buf.advance_bits(count),
+        }
+    }
+}
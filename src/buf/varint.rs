@@ -0,0 +1,673 @@
+use crate::prelude::*;
+
+/// Maps a signed value onto an unsigned one so that values with a small magnitude (positive or
+/// negative) have a small encoding: 0, -1, 1, -2, 2... become 0, 1, 2, 3, 4...
+pub fn zigzag_encode(value: i64) -> u64 {
+    ((value << 1) ^ (value >> 63)) as u64
+}
+
+/// The inverse of [`zigzag_encode`]
+pub fn zigzag_decode(value: u64) -> i64 {
+    ((value >> 1) as i64) ^ -((value & 1) as i64)
+}
+
+/// The largest value that can be encoded as a QUIC variable-length integer (2^62 - 1)
+pub const QUIC_VARINT_MAX: u64 = (1 << 62) - 1;
+
+/// Returns the number of bytes in the minimal QUIC variable-length integer encoding of `value`, or
+/// `None` if `value` is larger than [`QUIC_VARINT_MAX`].
+pub fn quic_varint_encoded_len(value: u64) -> Option<usize> {
+    match value {
+        0..=0x3F => Some(1),
+        0x40..=0x3FFF => Some(2),
+        0x4000..=0x3FFF_FFFF => Some(4),
+        0x4000_0000..=QUIC_VARINT_MAX => Some(8),
+        _ => None,
+    }
+}
+
+/// Returns the total length in bytes of the QUIC variable-length integer which starts with
+/// `first_byte`, as given by its 2-bit length prefix.
+pub fn quic_varint_len_from_first_byte(first_byte: u8) -> usize {
+    1 << (first_byte >> 6)
+}
+
+/// The default limit on the number of continuation bytes in an HPACK/QPACK prefixed integer: enough
+/// for any `u64`.  Protocols which only need smaller values should set a tighter limit with
+/// [`BitBufVarintExts::get_prefixed_int_with_limit`].
+pub const PREFIXED_INT_MAX_CONTINUATION_BYTES: usize = 10;
+
+fn check_prefix_bits(prefix_bits: usize) -> std::io::Result<()> {
+    if !(1..=8).contains(&prefix_bits) {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            format!("Prefix ({} bits) must be between 1 and 8 bits", prefix_bits),
+        ));
+    }
+    Ok(())
+}
+
+fn invalid_data(message: &str) -> std::io::Error {
+    std::io::Error::new(std::io::ErrorKind::InvalidData, message.to_string())
+}
+
+/// Variable-length integer reads.  Each byte of a varint is read with `get_u8`, so they can start
+/// at any bit offset.
+pub trait BitBufVarintExts<Order: BitOrder = Msb0>: BitBuf<Order> {
+    /// Reads an unsigned LEB128 value (as used by protobuf, WebAssembly, DWARF and AV1): 7 bits
+    /// per byte, least significant group first, with the top bit of each byte set if another byte
+    /// follows.
+    ///
+    /// Returns an error if the value doesn't fit in a `u64` or the encoding isn't minimal.
+    fn get_uleb128(&mut self) -> std::io::Result<u64> {
+        let mut result = 0u64;
+        let mut shift = 0;
+        loop {
+            let byte = self.get_u8()?;
+            let payload = (byte & 0x7F) as u64;
+            if shift == 63 && payload > 1 {
+                return Err(invalid_data("ULEB128 value overflows a u64"));
+            }
+            result |= payload << shift;
+            if byte & 0x80 == 0 {
+                // A trailing zero byte adds nothing to the value
+                if byte == 0 && shift > 0 {
+                    return Err(invalid_data("Over-long ULEB128 encoding"));
+                }
+                return Ok(result);
+            }
+            shift += 7;
+            if shift > 63 {
+                return Err(invalid_data("ULEB128 value overflows a u64"));
+            }
+        }
+    }
+
+    /// Reads a signed LEB128 value: like [`BitBufVarintExts::get_uleb128`], but the value is
+    /// sign-extended from the top bit of the last group.
+    ///
+    /// Returns an error if the value doesn't fit in an `i64` or the encoding isn't minimal.
+    fn get_sleb128(&mut self) -> std::io::Result<i64> {
+        let mut result = 0i64;
+        let mut shift = 0;
+        let mut prev_byte = None;
+        loop {
+            let byte = self.get_u8()?;
+            let payload = byte & 0x7F;
+            // The 10th byte only holds bit 63, so the rest of its payload must be a
+            // sign-extension of that bit and it can't be followed by another byte.
+            if shift == 63 && ((payload != 0 && payload != 0x7F) || byte & 0x80 != 0) {
+                return Err(invalid_data("SLEB128 value overflows an i64"));
+            }
+            result |= (payload as i64) << shift;
+            if byte & 0x80 == 0 {
+                // The last byte is redundant if it's nothing but a sign-extension of the previous
+                // byte's sign bit
+                if let Some(prev_byte) = prev_byte {
+                    let prev_negative = prev_byte & 0x40 != 0;
+                    if (payload == 0 && !prev_negative) || (payload == 0x7F && prev_negative) {
+                        return Err(invalid_data("Over-long SLEB128 encoding"));
+                    }
+                }
+                shift += 7;
+                if shift < 64 && payload & 0x40 != 0 {
+                    result |= -1i64 << shift;
+                }
+                return Ok(result);
+            }
+            prev_byte = Some(payload);
+            shift += 7;
+        }
+    }
+
+    /// Reads a zigzag-encoded signed value stored as an unsigned LEB128 value (protobuf's
+    /// `sint64`).
+    fn get_zigzag_varint(&mut self) -> std::io::Result<i64> {
+        Ok(zigzag_decode(self.get_uleb128()?))
+    }
+
+    /// Reads a big-endian variable-length quantity (as used by MIDI): 7 bits per byte, most
+    /// significant group first, with the top bit of each byte set if another byte follows.
+    ///
+    /// Returns an error if the value doesn't fit in a `u64` or the encoding isn't minimal.
+    fn get_vlq(&mut self) -> std::io::Result<u64> {
+        let mut result = 0u64;
+        let mut first = true;
+        loop {
+            let byte = self.get_u8()?;
+            // A leading group of zeros adds nothing to the value
+            if first && byte == 0x80 {
+                return Err(invalid_data("Over-long VLQ encoding"));
+            }
+            first = false;
+            if result >> 57 != 0 {
+                return Err(invalid_data("VLQ value overflows a u64"));
+            }
+            result = (result << 7) | (byte & 0x7F) as u64;
+            if byte & 0x80 == 0 {
+                return Ok(result);
+            }
+        }
+    }
+
+    /// Reads a QUIC variable-length integer (RFC 9000 section 16): a 2-bit prefix giving the
+    /// length of the encoding (1, 2, 4 or 8 bytes) followed by a 6, 14, 30 or 62-bit value.
+    /// Non-minimal encodings are allowed, as the RFC requires.
+    fn get_quic_varint(&mut self) -> std::io::Result<u64> {
+        let prefix: u8 = self.get_u2()?.into();
+        let value_bits = 8 * (1 << prefix) - 2;
+        self.get_bits_as::<u64, Order::FieldOrder>(value_bits)
+    }
+
+    /// Reads an HPACK/QPACK integer (RFC 7541 section 5.1) with a `prefix_bits`-bit prefix.  If
+    /// the prefix isn't all ones it holds the value, otherwise the rest of the value (minus the
+    /// prefix) follows as 7-bit groups, least significant first, with the top bit of each byte set
+    /// if another byte follows.
+    ///
+    /// The prefix is read from the current position, so this can be called after reading the flag
+    /// bits which share its byte (e.g. with `get_u1` or `get_u2`).  At most
+    /// [`PREFIXED_INT_MAX_CONTINUATION_BYTES`] continuation bytes are accepted.
+    fn get_prefixed_int(&mut self, prefix_bits: usize) -> std::io::Result<u64> {
+        self.get_prefixed_int_with_limit(prefix_bits, PREFIXED_INT_MAX_CONTINUATION_BYTES)
+    }
+
+    /// Like [`BitBufVarintExts::get_prefixed_int`], but returns an error if the value has more
+    /// than `max_continuation_bytes` continuation bytes.
+    fn get_prefixed_int_with_limit(
+        &mut self,
+        prefix_bits: usize,
+        max_continuation_bytes: usize,
+    ) -> std::io::Result<u64> {
+        check_prefix_bits(prefix_bits)?;
+        let max_prefix = (1u64 << prefix_bits) - 1;
+        let mut result = self.get_bits_as::<u64, Order::FieldOrder>(prefix_bits)?;
+        if result < max_prefix {
+            return Ok(result);
+        }
+        let mut shift = 0;
+        for _ in 0..max_continuation_bytes {
+            let byte = self.get_u8()?;
+            let payload = (byte & 0x7F) as u64;
+            if payload != 0 {
+                result = payload
+                    .checked_shl(shift)
+                    .filter(|shifted| shifted >> shift == payload)
+                    .and_then(|shifted| result.checked_add(shifted))
+                    .ok_or_else(|| invalid_data("Prefixed integer overflows a u64"))?;
+            }
+            if byte & 0x80 == 0 {
+                return Ok(result);
+            }
+            shift += 7;
+        }
+        Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            format!(
+                "Prefixed integer has more than {} continuation bytes",
+                max_continuation_bytes
+            ),
+        ))
+    }
+
+    /// Reads an ID3v2 synchsafe integer of `num_bytes` bytes: each byte holds 7 bits of the value,
+    /// most significant first, and must have its top bit clear.  ID3v2 tag and frame sizes are 4
+    /// bytes long.
+    fn get_synchsafe(&mut self, num_bytes: usize) -> std::io::Result<u64> {
+        if num_bytes > 9 {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!(
+                    "Synchsafe integers of {} bytes don't fit in a u64",
+                    num_bytes
+                ),
+            ));
+        }
+        let mut result = 0u64;
+        for _ in 0..num_bytes {
+            let byte = self.get_u8()?;
+            if byte & 0x80 != 0 {
+                return Err(invalid_data("Synchsafe integer byte has its top bit set"));
+            }
+            result = (result << 7) | byte as u64;
+        }
+        Ok(result)
+    }
+}
+
+impl<Order: BitOrder, T: BitBuf<Order> + ?Sized> BitBufVarintExts<Order> for T {}
+
+/// Variable-length integer writes.  All writes produce the minimal encoding of the value.
+pub trait BitBufMutVarintExts<Order: BitOrder = Msb0>: BitBufMut<Order> {
+    fn put_uleb128(&mut self, mut value: u64) -> std::io::Result<()> {
+        loop {
+            let byte = (value & 0x7F) as u8;
+            value >>= 7;
+            if value == 0 {
+                return self.put_u8(byte);
+            }
+            self.put_u8(byte | 0x80)?;
+        }
+    }
+
+    fn put_sleb128(&mut self, mut value: i64) -> std::io::Result<()> {
+        loop {
+            let byte = (value & 0x7F) as u8;
+            value >>= 7;
+            // We're done once the rest of the value is just a sign-extension of this byte's top
+            // bit
+            let sign_bit_set = byte & 0x40 != 0;
+            if (value == 0 && !sign_bit_set) || (value == -1 && sign_bit_set) {
+                return self.put_u8(byte);
+            }
+            self.put_u8(byte | 0x80)?;
+        }
+    }
+
+    fn put_zigzag_varint(&mut self, value: i64) -> std::io::Result<()> {
+        self.put_uleb128(zigzag_encode(value))
+    }
+
+    fn put_vlq(&mut self, value: u64) -> std::io::Result<()> {
+        let num_groups = (64 - value.leading_zeros()).div_ceil(7).max(1);
+        for group in (0..num_groups).rev() {
+            let byte = ((value >> (group * 7)) & 0x7F) as u8;
+            let continuation = if group == 0 { 0 } else { 0x80 };
+            self.put_u8(byte | continuation)?;
+        }
+        Ok(())
+    }
+
+    /// Writes `value` as a QUIC variable-length integer using the minimal encoding.
+    ///
+    /// Returns an error if `value` is larger than [`QUIC_VARINT_MAX`].
+    fn put_quic_varint(&mut self, value: u64) -> std::io::Result<()> {
+        let len = quic_varint_encoded_len(value).ok_or_else(|| {
+            std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!("Value ({}) is too large for a QUIC varint", value),
+            )
+        })?;
+        self.put_quic_varint_with_len(value, len)
+    }
+
+    /// Writes `value` as a QUIC variable-length integer using an encoding of exactly `len` bytes,
+    /// even if a shorter one would do.  This is useful for reserving space for a length field
+    /// which is filled in later.
+    ///
+    /// Returns an error if `len` isn't 1, 2, 4 or 8 or if `value` doesn't fit in that encoding.
+    fn put_quic_varint_with_len(&mut self, value: u64, len: usize) -> std::io::Result<()> {
+        let prefix = match len {
+            1 => 0,
+            2 => 1,
+            4 => 2,
+            8 => 3,
+            _ => {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::InvalidInput,
+                    format!("QUIC varints can't be {} bytes long", len),
+                ))
+            }
+        };
+        let value_bits = 8 * len - 2;
+        if value >> value_bits != 0 {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!(
+                    "Value ({}) does not fit in a {}-byte QUIC varint",
+                    value, len
+                ),
+            ));
+        }
+        self.put_u2(u2::new(prefix))?;
+        self.put_bits_from::<u64, Order::FieldOrder>(value_bits, value)
+    }
+
+    /// Writes `value` as an HPACK/QPACK integer with a `prefix_bits`-bit prefix, starting at the
+    /// current position.  See [`BitBufVarintExts::get_prefixed_int`].
+    fn put_prefixed_int(&mut self, prefix_bits: usize, value: u64) -> std::io::Result<()> {
+        self.put_prefixed_int_with_limit(prefix_bits, value, PREFIXED_INT_MAX_CONTINUATION_BYTES)
+    }
+
+    /// Like [`BitBufMutVarintExts::put_prefixed_int`], but returns an error (without writing
+    /// anything) if `value` would need more than `max_continuation_bytes` continuation bytes.
+    fn put_prefixed_int_with_limit(
+        &mut self,
+        prefix_bits: usize,
+        value: u64,
+        max_continuation_bytes: usize,
+    ) -> std::io::Result<()> {
+        check_prefix_bits(prefix_bits)?;
+        let max_prefix = (1u64 << prefix_bits) - 1;
+        if value < max_prefix {
+            return self.put_bits_from::<u64, Order::FieldOrder>(prefix_bits, value);
+        }
+        let mut remaining = value - max_prefix;
+        let num_continuation_bytes = (64 - remaining.leading_zeros() as usize).div_ceil(7).max(1);
+        if num_continuation_bytes > max_continuation_bytes {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!(
+                    "Value ({}) needs more than {} continuation bytes",
+                    value, max_continuation_bytes
+                ),
+            ));
+        }
+        self.put_bits_from::<u64, Order::FieldOrder>(prefix_bits, max_prefix)?;
+        while remaining >= 0x80 {
+            self.put_u8((remaining & 0x7F) as u8 | 0x80)?;
+            remaining >>= 7;
+        }
+        self.put_u8(remaining as u8)
+    }
+
+    /// Writes `value` as an ID3v2 synchsafe integer of `num_bytes` bytes.
+    ///
+    /// Returns an error if `num_bytes` is more than 9, the most [`get_synchsafe`] can read back,
+    /// or if `value` doesn't fit in `7 * num_bytes` bits.
+    ///
+    /// [`get_synchsafe`]: BitBufVarintExts::get_synchsafe
+    fn put_synchsafe(&mut self, num_bytes: usize, value: u64) -> std::io::Result<()> {
+        if num_bytes > 9 {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!(
+                    "Synchsafe integers of {} bytes don't fit in a u64",
+                    num_bytes
+                ),
+            ));
+        }
+        if value >> (7 * num_bytes) != 0 {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!(
+                    "Value ({}) does not fit in a {}-byte synchsafe integer",
+                    value, num_bytes
+                ),
+            ));
+        }
+        for i in (0..num_bytes).rev() {
+            self.put_u8(((value >> (i * 7)) & 0x7F) as u8)?;
+        }
+        Ok(())
+    }
+}
+
+impl<Order: BitOrder, T: BitBufMut<Order> + ?Sized> BitBufMutVarintExts<Order> for T {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_uleb128() {
+        // Example from the DWARF spec
+        let mut bits = Bits::from_static_bytes(&[0xE5, 0x8E, 0x26]);
+        assert_eq!(bits.get_uleb128().unwrap(), 624485);
+
+        let mut bits_mut = BitsMut::new();
+        bits_mut.put_uleb128(624485).unwrap();
+        assert_eq!(
+            bits_mut.freeze(),
+            Bits::from_static_bytes(&[0xE5, 0x8E, 0x26])
+        );
+
+        let mut bits_mut = BitsMut::new();
+        for value in [0, 1, 127, 128, u32::MAX as u64, u64::MAX] {
+            bits_mut.put_uleb128(value).unwrap();
+        }
+        let mut bits = bits_mut.freeze();
+        for value in [0, 1, 127, 128, u32::MAX as u64, u64::MAX] {
+            assert_eq!(bits.get_uleb128().unwrap(), value);
+        }
+    }
+
+    #[test]
+    fn test_uleb128_invalid() {
+        // Over-long encoding of 0
+        let mut bits = Bits::from_static_bytes(&[0x80, 0x00]);
+        assert!(bits.get_uleb128().is_err());
+        // 11 bytes
+        let mut bits = Bits::from_static_bytes(&[0xFF; 11]);
+        assert!(bits.get_uleb128().is_err());
+        // 10 bytes but more than 64 bits of value
+        let mut bits =
+            Bits::from_static_bytes(&[0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x02]);
+        assert!(bits.get_uleb128().is_err());
+        let mut bits = Bits::from_static_bytes(&[0x80]);
+        assert_eq!(
+            bits.get_uleb128().unwrap_err().kind(),
+            std::io::ErrorKind::UnexpectedEof
+        );
+    }
+
+    #[test]
+    fn test_sleb128() {
+        let mut bits = Bits::from_static_bytes(&[0xC0, 0xBB, 0x78]);
+        assert_eq!(bits.get_sleb128().unwrap(), -123456);
+
+        let values = [0, 1, -1, 63, 64, -64, -65, i64::MAX, i64::MIN];
+        let mut bits_mut = BitsMut::new();
+        for value in values {
+            bits_mut.put_sleb128(value).unwrap();
+        }
+        let mut bits = bits_mut.freeze();
+        for value in values {
+            assert_eq!(bits.get_sleb128().unwrap(), value);
+        }
+        assert!(!bits.has_remaining_bits());
+    }
+
+    #[test]
+    fn test_sleb128_invalid() {
+        // Over-long encodings of 0 and -1
+        let mut bits = Bits::from_static_bytes(&[0x80, 0x00]);
+        assert!(bits.get_sleb128().is_err());
+        let mut bits = Bits::from_static_bytes(&[0xFF, 0x7F]);
+        assert!(bits.get_sleb128().is_err());
+        // 64 isn't over-long: it needs the second byte for its sign
+        let mut bits = Bits::from_static_bytes(&[0xC0, 0x00]);
+        assert_eq!(bits.get_sleb128().unwrap(), 64);
+        // Overflow
+        let mut bits =
+            Bits::from_static_bytes(&[0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x01]);
+        assert!(bits.get_sleb128().is_err());
+    }
+
+    #[test]
+    fn test_zigzag() {
+        assert_eq!(zigzag_encode(0), 0);
+        assert_eq!(zigzag_encode(-1), 1);
+        assert_eq!(zigzag_encode(1), 2);
+        assert_eq!(zigzag_encode(i64::MIN), u64::MAX);
+        assert_eq!(zigzag_decode(u64::MAX), i64::MIN);
+
+        let mut bits_mut = BitsMut::new();
+        bits_mut.put_zigzag_varint(-65).unwrap();
+        let mut bits = bits_mut.freeze();
+        assert_eq!(bits.get_zigzag_varint().unwrap(), -65);
+    }
+
+    #[test]
+    fn test_vlq() {
+        // Examples from the MIDI spec
+        let cases: [(u64, &[u8]); 5] = [
+            (0x00, &[0x00]),
+            (0x7F, &[0x7F]),
+            (0x80, &[0x81, 0x00]),
+            (0x2000, &[0xC0, 0x00]),
+            (0x0FFFFFFF, &[0xFF, 0xFF, 0xFF, 0x7F]),
+        ];
+        for (value, encoded) in cases {
+            let mut bits_mut = BitsMut::new();
+            bits_mut.put_vlq(value).unwrap();
+            assert_eq!(bits_mut.freeze(), Bits::copy_from_bytes(encoded));
+            let mut bits = Bits::copy_from_bytes(encoded);
+            assert_eq!(bits.get_vlq().unwrap(), value);
+        }
+        let mut bits_mut = BitsMut::new();
+        bits_mut.put_vlq(u64::MAX).unwrap();
+        assert_eq!(bits_mut.freeze().get_vlq().unwrap(), u64::MAX);
+
+        let mut bits = Bits::from_static_bytes(&[0x80, 0x01]);
+        assert!(bits.get_vlq().is_err());
+        let mut bits = Bits::from_static_bytes(&[0xFF; 10]);
+        assert!(bits.get_vlq().is_err());
+    }
+
+    #[test]
+    fn test_synchsafe() {
+        let mut bits = Bits::from_static_bytes(&[0x00, 0x00, 0x02, 0x01]);
+        assert_eq!(bits.get_synchsafe(4).unwrap(), 257);
+
+        let mut bits_mut = BitsMut::new();
+        bits_mut.put_synchsafe(4, 0x0FFFFFFF).unwrap();
+        assert!(bits_mut.put_synchsafe(4, 0x10000000).is_err());
+        assert_eq!(
+            bits_mut.freeze(),
+            Bits::from_static_bytes(&[0x7F, 0x7F, 0x7F, 0x7F])
+        );
+
+        let mut bits = Bits::from_static_bytes(&[0x00, 0x80, 0x00, 0x00]);
+        assert!(bits.get_synchsafe(4).is_err());
+
+        // Both directions accept at most 9 bytes
+        let mut bits_mut = BitsMut::new();
+        bits_mut.put_synchsafe(9, u64::MAX >> 1).unwrap();
+        assert!(bits_mut.put_synchsafe(10, 0).is_err());
+        let mut bits = bits_mut.freeze();
+        assert_eq!(bits.len_bytes(), 9);
+        assert_eq!(bits.get_synchsafe(9).unwrap(), u64::MAX >> 1);
+        let mut bits = Bits::from_static_bytes(&[0; 10]);
+        assert!(bits.get_synchsafe(10).is_err());
+    }
+
+    #[test]
+    fn test_varint_unaligned() {
+        let mut bits_mut = BitsMut::new();
+        bits_mut.put_u3(u3::new(0b101)).unwrap();
+        bits_mut.put_uleb128(300).unwrap();
+        bits_mut.put_vlq(300).unwrap();
+        let mut bits = bits_mut.freeze();
+        assert_eq!(bits.get_u3().unwrap(), u3::new(0b101));
+        assert_eq!(bits.get_uleb128().unwrap(), 300);
+        assert_eq!(bits.get_vlq().unwrap(), 300);
+    }
+
+    #[test]
+    fn test_varint_lsb() {
+        // Byte-oriented encodings are the same bytes in an LSB-first buffer
+        let mut bits = LsbBits::from_static_bytes(&[0xE5, 0x8E, 0x26]);
+        assert_eq!(bits.get_uleb128().unwrap(), 624485);
+
+        let mut bits_mut = LsbBitsMut::new();
+        bits_mut.put_u3(u3::new(0b101)).unwrap();
+        bits_mut.put_uleb128(300).unwrap();
+        bits_mut.put_sleb128(-300).unwrap();
+        bits_mut.put_vlq(300).unwrap();
+        bits_mut.put_prefixed_int(5, 1337).unwrap();
+        let mut bits = bits_mut.freeze();
+        assert_eq!(bits.get_u3().unwrap(), u3::new(0b101));
+        assert_eq!(bits.get_uleb128().unwrap(), 300);
+        assert_eq!(bits.get_sleb128().unwrap(), -300);
+        assert_eq!(bits.get_vlq().unwrap(), 300);
+        assert_eq!(bits.get_prefixed_int(5).unwrap(), 1337);
+    }
+
+    #[test]
+    fn test_quic_varint() {
+        // Examples from RFC 9000 appendix A.1
+        let cases: [(u64, &[u8]); 4] = [
+            (
+                151288809941952652,
+                &[0xC2, 0x19, 0x7C, 0x5E, 0xFF, 0x14, 0xE8, 0x8C],
+            ),
+            (494878333, &[0x9D, 0x7F, 0x3E, 0x7D]),
+            (15293, &[0x7B, 0xBD]),
+            (37, &[0x25]),
+        ];
+        for (value, encoded) in cases {
+            let mut bits = Bits::copy_from_bytes(encoded);
+            assert_eq!(bits.get_quic_varint().unwrap(), value);
+            assert_eq!(quic_varint_len_from_first_byte(encoded[0]), encoded.len());
+            assert_eq!(quic_varint_encoded_len(value), Some(encoded.len()));
+
+            let mut bits_mut = BitsMut::new();
+            bits_mut.put_quic_varint(value).unwrap();
+            assert_eq!(bits_mut.freeze(), Bits::copy_from_bytes(encoded));
+        }
+        // The RFC's two-byte encoding of 37 must also decode
+        let mut bits = Bits::from_static_bytes(&[0x40, 0x25]);
+        assert_eq!(bits.get_quic_varint().unwrap(), 37);
+    }
+
+    #[test]
+    fn test_quic_varint_with_len() {
+        let mut bits_mut = BitsMut::new();
+        bits_mut.put_quic_varint_with_len(37, 2).unwrap();
+        assert_eq!(bits_mut.freeze(), Bits::from_static_bytes(&[0x40, 0x25]));
+
+        let mut bits_mut = BitsMut::new();
+        assert!(bits_mut.put_quic_varint_with_len(64, 1).is_err());
+        assert!(bits_mut.put_quic_varint_with_len(1, 3).is_err());
+        assert!(bits_mut.put_quic_varint(QUIC_VARINT_MAX + 1).is_err());
+        assert!(bits_mut.is_empty());
+        assert_eq!(quic_varint_encoded_len(QUIC_VARINT_MAX + 1), None);
+    }
+
+    #[test]
+    fn test_prefixed_int() {
+        // Examples from RFC 7541 appendix C.1, each preceded by 3 flag bits
+        let cases: [(u64, &[u8]); 3] = [
+            (10, &[0b1010_1010]),
+            (1337, &[0b1011_1111, 0x9A, 0x0A]),
+            (42, &[0x2A]),
+        ];
+        for (i, (value, encoded)) in cases.into_iter().enumerate() {
+            let prefix_bits = if i == 2 { 8 } else { 5 };
+            let mut bits = Bits::copy_from_bytes(encoded);
+            if prefix_bits == 5 {
+                assert_eq!(bits.get_u3().unwrap(), u3::new(0b101));
+            }
+            assert_eq!(bits.get_prefixed_int(prefix_bits).unwrap(), value);
+            assert!(!bits.has_remaining_bits());
+
+            let mut bits_mut = BitsMut::new();
+            if prefix_bits == 5 {
+                bits_mut.put_u3(u3::new(0b101)).unwrap();
+            }
+            bits_mut.put_prefixed_int(prefix_bits, value).unwrap();
+            assert_eq!(bits_mut.freeze(), Bits::copy_from_bytes(encoded));
+        }
+
+        // A value exactly equal to the all-ones prefix needs a zero continuation byte
+        let mut bits_mut = BitsMut::new();
+        bits_mut.put_prefixed_int(4, 15).unwrap();
+        bits_mut.put_prefixed_int(4, u64::MAX).unwrap();
+        let mut bits = bits_mut.freeze();
+        assert_eq!(bits.get_prefixed_int(4).unwrap(), 15);
+        assert_eq!(bits.get_prefixed_int(4).unwrap(), u64::MAX);
+    }
+
+    #[test]
+    fn test_prefixed_int_invalid() {
+        let mut bits = Bits::from_static_bytes(&[0xFF, 0x9A, 0x0A]);
+        let error = bits.get_prefixed_int_with_limit(8, 1).unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+
+        // Overflows a u64
+        let mut bits = Bits::from_static_bytes(&[
+            0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x01,
+        ]);
+        assert!(bits.get_prefixed_int(8).is_err());
+
+        let mut bits = Bits::from_static_bytes(&[0xFF, 0x9A]);
+        let error = bits.get_prefixed_int(8).unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::UnexpectedEof);
+
+        let mut bits_mut = BitsMut::new();
+        assert!(bits_mut.put_prefixed_int(0, 1).is_err());
+        assert!(bits_mut.put_prefixed_int(9, 1).is_err());
+        assert!(bits_mut.put_prefixed_int_with_limit(5, 1337, 1).is_err());
+        assert!(bits_mut.is_empty());
+    }
+}
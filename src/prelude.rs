@@ -3,8 +3,11 @@ pub mod internal {
     pub use bitvec::{bits, bitvec};
 }
 // Bitvec wrappers that are always u8 & Msb0
-pub use crate::bit_types::{from_raw_parts_mut, BitSlice, BitStore, BitVec};
-pub use crate::{bits, bitvec};
+pub use crate::bit_types::{
+    from_raw_parts_mut, BitOrder, BitSlice, BitStore, BitVec, LsbBitSlice, LsbBitVec,
+};
+pub use crate::{bits, bitvec, lsb_bits};
+pub use bitvec::order::{Lsb0, Msb0};
 
 // nsw-types re-export
 pub use nsw_types::from_bitslice::BitSliceUxExts;
@@ -16,9 +19,22 @@ pub use crate::buf::{
     bit_buf_exts::BitBufExts,
     bit_buf_mut::BitBufMut,
     bit_buf_mut_exts::BitBufMutExts,
+    bit_codec::{BitDecode, BitEncode},
     bits::Bits,
     bits_mut::BitsMut,
     byte_order::{BigEndian, ByteOrder, LittleEndian, NetworkOrder},
+    group_varint::{BitBufGroupVarintExts, BitBufMutGroupVarintExts},
+    huffman::{
+        BitBufDeflateExts, BitBufHuffmanExts, BitBufMutDeflateExts, BitBufMutHuffmanExts,
+        HuffmanDecoder, HuffmanEncoder,
+    },
+    lsb_bits::LsbBits,
+    lsb_bits_mut::LsbBitsMut,
+    minifloat::MinifloatFormat,
+    per::{BitBufMutPerExts, BitBufPerExts},
+    rbsp::{BitBufMutRbspExts, BitBufRbspExts},
+    universal_codes::{BitBufMutUniversalExts, BitBufUniversalExts, UniversalCode},
+    varint::{BitBufMutVarintExts, BitBufVarintExts},
 };
 pub use crate::io::bit_cursor::BitCursor;
 pub use crate::io::bit_read::BitRead;